@@ -55,6 +55,9 @@ async fn receiver_loop(mut receiver: splaycast::Receiver<Arc<Semaphore>>) {
             splaycast::Message::Lagged { count } => {
                 eprintln!("lagged {count}")
             }
+            splaycast::Message::Reset { epoch } => {
+                eprintln!("reset to epoch {epoch}")
+            }
         }
     }
 }
@@ -0,0 +1,24 @@
+//! Reports steady-state heap bytes per parked subscriber and per buffered entry.
+//!
+//! Run with `cargo bench --bench memory_bench`.
+
+#[path = "../tests/common/memory.rs"]
+mod memory;
+
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
+
+fn main() {
+    for subscribers in [1_000, 10_000, 100_000, 500_000] {
+        println!(
+            "subscribers: {subscribers:>7} bytes per parked subscriber: {:.1}",
+            memory::bytes_per_parked_subscriber(subscribers)
+        );
+    }
+    for entries in [64, 1024, 16_384] {
+        println!(
+            "entries: {entries:>7} bytes per buffered entry: {:.1}",
+            memory::bytes_per_buffered_entry(entries)
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Compares writing a subscriber's backlog to a file one entry at a time against flushing it
+//! with a `VectoredReceiver`, and reports the write syscalls and time for each.
+//!
+//! Run with `cargo bench --bench vectored_bench --features bytes`.
+
+use std::{
+    fs::File,
+    io::Write,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use futures::{task::noop_waker_ref, FutureExt, StreamExt};
+use splaycast::{buffer_policy::BufferLengthPolicy, Message};
+
+const ENTRIES: usize = 100_000;
+const ENTRY_SIZE: usize = 64;
+
+#[allow(clippy::expect_used)] // it is a benchmark, it's fine
+fn main() {
+    for backlog in [1, 8, 64, 512] {
+        let (writes, elapsed) = per_entry(backlog);
+        println!("backlog: {backlog:>4} per entry: {writes:>7} writes in {elapsed:?}");
+        let (writes, elapsed) = vectored(backlog);
+        println!("backlog: {backlog:>4} vectored:  {writes:>7} writes in {elapsed:?}");
+    }
+}
+
+/// Publish entries `backlog` at a time, and write each one with its own syscall.
+#[allow(clippy::expect_used)] // it is a benchmark, it's fine
+fn per_entry(backlog: usize) -> (usize, Duration) {
+    let (sender, mut engine, splaycast) =
+        splaycast::channel_with_policy(backlog, BufferLengthPolicy::new(backlog));
+    let mut receiver = splaycast.subscribe();
+    let mut out = File::create("/dev/null").expect("can open /dev/null");
+    let mut context = Context::from_waker(noop_waker_ref());
+    let entry = Bytes::from(vec![0_u8; ENTRY_SIZE]);
+
+    let mut writes = 0;
+    let start = Instant::now();
+    for _ in 0..ENTRIES / backlog {
+        for _ in 0..backlog {
+            sender.send(entry.clone()).expect("room in the send buffer");
+        }
+        let _ = engine.poll_unpin(&mut context);
+        while let Poll::Ready(Some(Message::Entry { item })) =
+            receiver.poll_next_unpin(&mut context)
+        {
+            out.write_all(&item).expect("can write to /dev/null");
+            writes += 1;
+        }
+    }
+    (writes, start.elapsed())
+}
+
+/// Publish entries `backlog` at a time, and flush them with vectored writes.
+#[allow(clippy::expect_used)] // it is a benchmark, it's fine
+fn vectored(backlog: usize) -> (usize, Duration) {
+    let (sender, mut engine, splaycast) =
+        splaycast::channel_with_policy(backlog, BufferLengthPolicy::new(backlog));
+    let mut receiver = splaycast.subscribe().into_vectored(1024);
+    let mut out = File::create("/dev/null").expect("can open /dev/null");
+    let mut context = Context::from_waker(noop_waker_ref());
+    let entry = Bytes::from(vec![0_u8; ENTRY_SIZE]);
+
+    let mut writes = 0;
+    let start = Instant::now();
+    for _ in 0..ENTRIES / backlog {
+        for _ in 0..backlog {
+            sender.send(entry.clone()).expect("room in the send buffer");
+        }
+        let _ = engine.poll_unpin(&mut context);
+        while let Poll::Ready(Some(Message::Entry { item: slices })) =
+            receiver.poll_ready_slices(&mut context)
+        {
+            let written = out.write_vectored(&slices).expect("can write to /dev/null");
+            writes += 1;
+            receiver.consume(written);
+        }
+    }
+    (writes, start.elapsed())
+}
@@ -0,0 +1,19 @@
+//! Run with `cargo +nightly fuzz run decode_frame` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use splaycast::bridge::Frame;
+
+fuzz_target!(|data: &[u8]| {
+    let mut bytes = data;
+    while let Ok(Some((frame, used))) = Frame::decode(bytes) {
+        assert!(0 < used && used <= bytes.len());
+
+        // Whatever decodes must encode back to the same bytes.
+        let mut encoded = Vec::new();
+        frame.encode(&mut encoded);
+        assert_eq!(&bytes[..used], encoded.as_slice());
+
+        bytes = &bytes[used..];
+    }
+});
@@ -0,0 +1,154 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{stream::FusedStream, Stream};
+use tokio::time::{Instant, Sleep};
+
+use crate::{Message, Receiver, SubscriptionMode};
+
+/// What happened on an [`ActivityMarkers`] stream.
+#[derive(Debug, PartialEq)]
+pub enum Activity<T> {
+    /// The next entry, as from [`Message::Entry`].
+    Entry(T),
+    /// This many entries were skipped, as from [`Message::Lagged`].
+    Lag(usize),
+    /// The sequence was reset, as from [`Message::Reset`].
+    Reset(u64),
+    /// Nothing has happened for this long. It is repeated every `idle` for as long as the
+    /// Receiver stays quiet, and the duration keeps counting from the last message.
+    Idle(Duration),
+}
+
+/// A Receiver that also reports when it has been idle, from
+/// [`crate::Receiver::with_activity_markers`].
+///
+/// This is for protocol encoders, which need to decide per connection whether to send a
+/// ping, a resync hint, or nothing. There is one timer per stream, and it is only touched
+/// when the Receiver parks: a busy Receiver never resets it, and a stale deadline is just
+/// moved forward when it fires.
+pub struct ActivityMarkers<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    receiver: Receiver<Item, Mode>,
+    idle: Duration,
+    last_message: Instant,
+    /// The last message or Idle, whichever is later. The next Idle is due `idle` after it.
+    last_mark: Instant,
+    sleep: Pin<Box<Sleep>>,
+    terminated: bool,
+}
+
+impl<Item, Mode> std::fmt::Debug for ActivityMarkers<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ActivityMarkers")
+            .field("receiver", &self.receiver)
+            .field("idle", &self.idle)
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+impl<Item, Mode> Receiver<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Convert this Receiver into a stream of [`Activity`], which also yields
+    /// [`Activity::Idle`] whenever nothing has arrived for `idle`.
+    ///
+    /// This uses the tokio timer, so it must be polled on a tokio runtime.
+    pub fn with_activity_markers(self, idle: Duration) -> ActivityMarkers<Item, Mode> {
+        let now = Instant::now();
+        ActivityMarkers {
+            receiver: self,
+            idle,
+            last_message: now,
+            last_mark: now,
+            sleep: Box::pin(tokio::time::sleep_until(now + idle)),
+            terminated: false,
+        }
+    }
+}
+
+impl<Item, Mode> ActivityMarkers<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Get back the Receiver.
+    pub fn into_inner(self) -> Receiver<Item, Mode> {
+        self.receiver
+    }
+}
+
+/// Safety: I don't use unsafe for this type
+impl<Item, Mode> Unpin for ActivityMarkers<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+}
+
+impl<Item, Mode> Stream for ActivityMarkers<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    type Item = Activity<Item>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        match Pin::new(&mut self.receiver).poll_next(context) {
+            Poll::Ready(Some(message)) => {
+                let now = Instant::now();
+                self.last_message = now;
+                self.last_mark = now;
+                return Poll::Ready(Some(match message {
+                    Message::Entry { item } => Activity::Entry(item),
+                    Message::Lagged { count } => Activity::Lag(count),
+                    Message::Reset { epoch } => Activity::Reset(epoch),
+                }));
+            }
+            Poll::Ready(None) => {
+                self.terminated = true;
+                return Poll::Ready(None);
+            }
+            Poll::Pending => (),
+        }
+
+        // Parked: make sure the timer wakes us when the next Idle is due.
+        let deadline = self.last_mark + self.idle;
+        if self.sleep.deadline() != deadline {
+            self.sleep.as_mut().reset(deadline);
+        }
+        if self.sleep.as_mut().poll(context).is_ready() {
+            let now = Instant::now();
+            self.last_mark = now;
+            return Poll::Ready(Some(Activity::Idle(now - self.last_message)));
+        }
+        Poll::Pending
+    }
+}
+
+impl<Item, Mode> FusedStream for ActivityMarkers<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
@@ -0,0 +1,177 @@
+//! Recording wrappers for the crate's atomics, with `--cfg splaycast_audit`.
+//!
+//! Each wrapper forwards to the std atomic it wraps, and records the call site, operation
+//! and ordering of every access. Sites are recorded per thread, without synchronizing, so
+//! a ThreadSanitizer build sees exactly the happens-before edges that the crate itself
+//! creates. A thread's sites are collected when it exits, or when it calls [`accesses`].
+//!
+//! ```text
+//! RUSTFLAGS="--cfg splaycast_audit" cargo test --test atomics_audit_test
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    panic::Location,
+    sync::{atomic, atomic::Ordering, Mutex, PoisonError},
+};
+
+/// One way an atomic was accessed, from [`accesses`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub struct Access {
+    /// The source file of the access, like `src/shared.rs`.
+    pub file: &'static str,
+    /// The line of the accessing method call.
+    pub line: u32,
+    /// The column of the accessing method's name.
+    pub column: u32,
+    /// The method, like `load` or `fetch_add`.
+    pub operation: &'static str,
+    /// The ordering it was called with, like `"Acquire"`. Operations with a success and a
+    /// failure ordering record both.
+    pub ordering: &'static str,
+}
+
+/// Every distinct atomic access recorded so far, in source order.
+pub fn accesses() -> Vec<Access> {
+    let _ = SITES.try_with(|sites| sites.collect());
+    let mut accesses: Vec<_> = COLLECTED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .flatten()
+        .copied()
+        .collect();
+    accesses.sort();
+    accesses
+}
+
+static COLLECTED: Mutex<Option<HashSet<Access>>> = Mutex::new(None);
+
+struct Sites(RefCell<HashSet<Access>>);
+
+impl Sites {
+    fn collect(&self) {
+        collect(self.0.borrow_mut().drain());
+    }
+}
+
+impl Drop for Sites {
+    fn drop(&mut self) {
+        self.collect();
+    }
+}
+
+fn collect(accesses: impl IntoIterator<Item = Access>) {
+    COLLECTED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_with(Default::default)
+        .extend(accesses);
+}
+
+thread_local! {
+    static SITES: Sites = Sites(Default::default());
+}
+
+#[track_caller]
+fn record(operation: &'static str, ordering: Ordering) {
+    let location = Location::caller();
+    let access = Access {
+        file: location.file(),
+        line: location.line(),
+        column: location.column(),
+        operation,
+        ordering: match ordering {
+            Ordering::Relaxed => "Relaxed",
+            Ordering::Release => "Release",
+            Ordering::Acquire => "Acquire",
+            Ordering::AcqRel => "AcqRel",
+            _ => "SeqCst",
+        },
+    };
+    // An atomic touched from another thread-local's destructor goes straight to the set.
+    if SITES
+        .try_with(|sites| sites.0.borrow_mut().insert(access))
+        .is_err()
+    {
+        collect([access]);
+    }
+}
+
+macro_rules! audited {
+    ($name:ident, $value:ty) => {
+        #[doc = concat!("A recording [`std::sync::atomic::", stringify!($name), "`].")]
+        #[derive(Debug, Default)]
+        pub(crate) struct $name(atomic::$name);
+
+        #[allow(dead_code)] // Not every atomic uses every operation.
+        impl $name {
+            pub(crate) const fn new(value: $value) -> Self {
+                Self(atomic::$name::new(value))
+            }
+
+            #[track_caller]
+            pub(crate) fn load(&self, ordering: Ordering) -> $value {
+                record("load", ordering);
+                self.0.load(ordering)
+            }
+
+            #[track_caller]
+            pub(crate) fn store(&self, value: $value, ordering: Ordering) {
+                record("store", ordering);
+                self.0.store(value, ordering)
+            }
+
+            #[track_caller]
+            pub(crate) fn swap(&self, value: $value, ordering: Ordering) -> $value {
+                record("swap", ordering);
+                self.0.swap(value, ordering)
+            }
+
+            #[track_caller]
+            pub(crate) fn fetch_update<F>(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                f: F,
+            ) -> Result<$value, $value>
+            where
+                F: FnMut($value) -> Option<$value>,
+            {
+                record("fetch_update", set_order);
+                record("fetch_update", fetch_order);
+                self.0.fetch_update(set_order, fetch_order, f)
+            }
+        }
+    };
+    ($name:ident, $value:ty, integer) => {
+        audited!($name, $value);
+
+        #[allow(dead_code)] // Not every atomic uses every operation.
+        impl $name {
+            #[track_caller]
+            pub(crate) fn fetch_add(&self, value: $value, ordering: Ordering) -> $value {
+                record("fetch_add", ordering);
+                self.0.fetch_add(value, ordering)
+            }
+
+            #[track_caller]
+            pub(crate) fn fetch_sub(&self, value: $value, ordering: Ordering) -> $value {
+                record("fetch_sub", ordering);
+                self.0.fetch_sub(value, ordering)
+            }
+
+            #[track_caller]
+            pub(crate) fn fetch_max(&self, value: $value, ordering: Ordering) -> $value {
+                record("fetch_max", ordering);
+                self.0.fetch_max(value, ordering)
+            }
+        }
+    };
+}
+
+audited!(AtomicBool, bool);
+audited!(AtomicU64, u64, integer);
+audited!(AtomicUsize, usize, integer);
@@ -0,0 +1,66 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use crate::shared::Shared;
+
+/// A Future that resolves once a splaycast has enough subscribers, from
+/// [`crate::Splaycast::await_subscribers`].
+///
+/// It resolves with `true` once the subscriber count reaches the target, or with `false`
+/// if the splaycast terminates first.
+pub struct AwaitSubscribers<Item>
+where
+    Item: Clone,
+{
+    shared: Arc<Shared<Item>>,
+    count: usize,
+}
+
+impl<Item> std::fmt::Debug for AwaitSubscribers<Item>
+where
+    Item: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AwaitSubscribers")
+            .field("count", &self.count)
+            .finish()
+    }
+}
+
+impl<Item> AwaitSubscribers<Item>
+where
+    Item: Clone,
+{
+    pub(crate) fn new(shared: Arc<Shared<Item>>, count: usize) -> Self {
+        Self { shared, count }
+    }
+
+    fn check(&self) -> Poll<bool> {
+        if self.count <= self.shared.subscriber_count() {
+            Poll::Ready(true)
+        } else if self.shared.is_dead() {
+            Poll::Ready(false)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<Item> futures::Future for AwaitSubscribers<Item>
+where
+    Item: Clone,
+{
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(reached) = self.check() {
+            return Poll::Ready(reached);
+        }
+        self.shared.register_count_waiter(context.waker());
+        // The count may have moved before we registered, so check once more.
+        self.check()
+    }
+}
@@ -0,0 +1,68 @@
+use crate::EntryMetadata;
+
+/// A CRC-32C of an entry's payload, carried in a [`crate::bridge::Frame::CheckedEntry`] so
+/// corruption on the way across the bridge is detected instead of delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Checksum(pub u32);
+
+/// Reflected Castagnoli polynomial.
+const POLYNOMIAL: u32 = 0x82f6_3b78;
+
+const TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+impl Checksum {
+    /// The checksum of `bytes`.
+    pub fn of(bytes: &[u8]) -> Self {
+        let crc = bytes.iter().fold(!0_u32, |crc, byte| {
+            TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+        });
+        Self(!crc)
+    }
+
+    /// The checksum the [`checksum_hook`] attached to an entry, e.g., from
+    /// [`crate::Receiver::metadata`].
+    pub fn from_metadata(metadata: Option<&EntryMetadata>) -> Option<Self> {
+        metadata?.downcast_ref().copied()
+    }
+}
+
+/// A [metadata hook](crate::Engine::set_metadata_hook) that checksums each entry as the
+/// Engine absorbs it. Read it back with [`Checksum::from_metadata`] when you encode the
+/// entry, so corruption anywhere between absorption and the far side is caught.
+///
+/// This takes the Engine's metadata hook, so it can't be combined with another one.
+pub fn checksum_hook<Item>() -> impl FnMut(&Item) -> Option<EntryMetadata> + Send + 'static
+where
+    Item: AsRef<[u8]>,
+{
+    |item: &Item| Some(EntryMetadata::new(Checksum::of(item.as_ref())))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bridge::Checksum;
+
+    #[test]
+    fn check_value() {
+        assert_eq!(Checksum(0xe306_9283), Checksum::of(b"123456789"));
+        assert_eq!(Checksum(0), Checksum::of(b""));
+    }
+}
@@ -0,0 +1,16 @@
+//! Building blocks for carrying a splaycast across a process boundary.
+//!
+//! The [`Frame`] wire format is versioned and length-prefixed, so a bridge can stream
+//! entries over any byte transport, like a tcp socket. Items are opaque bytes here: you
+//! serialize your `Item` however you like before putting it in a [`Frame::Entry`].
+//!
+//! Once data leaves process memory, it can be corrupted without anybody noticing. A
+//! [`Frame::CheckedEntry`] carries a [`Checksum`] of its payload, ideally computed by the
+//! [`checksum_hook`] when the Engine absorbed the entry, and the decoder turns a mismatch
+//! into a [`Frame::Corrupt`].
+
+mod checksum;
+mod wire;
+
+pub use checksum::{checksum_hook, Checksum};
+pub use wire::{DecodeError, Frame, MAX_FRAME_LENGTH, WIRE_VERSION};
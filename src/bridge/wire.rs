@@ -0,0 +1,280 @@
+use super::Checksum;
+
+/// The version of the wire format written by [`Frame::encode`].
+///
+/// Decoders reject other versions rather than guessing at them.
+pub const WIRE_VERSION: u8 = 1;
+
+/// The largest frame body a decoder will accept, in bytes. This bounds what a corrupt or
+/// hostile length prefix can make a decoder allocate.
+pub const MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
+/// version (1) + kind (1) + body length (4)
+const HEADER_LENGTH: usize = 6;
+
+const KIND_ENTRY: u8 = 0;
+const KIND_LAGGED: u8 = 1;
+const KIND_HEARTBEAT: u8 = 2;
+const KIND_CHECKED_ENTRY: u8 = 3;
+const KIND_CORRUPT: u8 = 4;
+
+/// One unit of the bridge wire format.
+///
+/// Every frame starts with a header: the [`WIRE_VERSION`] byte, a kind byte, and the
+/// body length as a big-endian u32. Integers in the body are big-endian too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// An entry, with its sequence id and serialized item.
+    Entry { sequence_id: u64, payload: Vec<u8> },
+    /// The sending side lagged, and skipped `count` entries.
+    Lagged { count: u64 },
+    /// Keeps an idle connection alive. `sequence_id` is the newest id the sending side
+    /// knows about, so the receiving side can notice entries it missed.
+    Heartbeat { sequence_id: u64 },
+    /// An entry whose payload is verified against its checksum when it is decoded.
+    CheckedEntry {
+        sequence_id: u64,
+        checksum: Checksum,
+        payload: Vec<u8>,
+    },
+    /// A [`Frame::CheckedEntry`] whose payload didn't match its checksum. The payload is
+    /// dropped, but the frame boundaries are intact, so the stream can carry on, e.g., after
+    /// asking for `sequence_id` again. A relay can forward it as is.
+    Corrupt { sequence_id: u64 },
+}
+
+/// Why a frame could not be decoded. A decoder should drop the connection on any of these:
+/// there is no way to find the next frame boundary in a corrupt stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The frame was written with a wire format version this decoder doesn't speak.
+    UnsupportedVersion(u8),
+    /// The frame kind is not known to this version of the wire format.
+    UnknownKind(u8),
+    /// The length prefix is larger than [`MAX_FRAME_LENGTH`].
+    TooLong(usize),
+    /// The body doesn't match the layout for its kind.
+    Malformed,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported wire version {version}")
+            }
+            DecodeError::UnknownKind(kind) => write!(f, "unknown frame kind {kind}"),
+            DecodeError::TooLong(length) => write!(f, "frame length {length} is too long"),
+            DecodeError::Malformed => f.write_str("malformed frame body"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl Frame {
+    /// A [`Frame::CheckedEntry`], checksummed now. Prefer the checksum from the
+    /// [`crate::bridge::checksum_hook`] when you have it, since it was taken earlier.
+    pub fn checked_entry(sequence_id: u64, payload: Vec<u8>) -> Self {
+        Frame::CheckedEntry {
+            sequence_id,
+            checksum: Checksum::of(&payload),
+            payload,
+        }
+    }
+
+    /// Append this frame to `buffer`.
+    ///
+    /// Entry payloads longer than [`MAX_FRAME_LENGTH`] can be encoded, but will not decode.
+    pub fn encode(&self, buffer: &mut Vec<u8>) {
+        let (kind, body_length) = match self {
+            Frame::Entry { payload, .. } => (KIND_ENTRY, 8 + payload.len()),
+            Frame::Lagged { .. } => (KIND_LAGGED, 8),
+            Frame::Heartbeat { .. } => (KIND_HEARTBEAT, 8),
+            Frame::CheckedEntry { payload, .. } => (KIND_CHECKED_ENTRY, 12 + payload.len()),
+            Frame::Corrupt { .. } => (KIND_CORRUPT, 8),
+        };
+        buffer.reserve(HEADER_LENGTH + body_length);
+        buffer.push(WIRE_VERSION);
+        buffer.push(kind);
+        buffer.extend_from_slice(&(body_length as u32).to_be_bytes());
+        match self {
+            Frame::Entry {
+                sequence_id,
+                payload,
+            } => {
+                buffer.extend_from_slice(&sequence_id.to_be_bytes());
+                buffer.extend_from_slice(payload);
+            }
+            Frame::Lagged { count } => buffer.extend_from_slice(&count.to_be_bytes()),
+            Frame::Heartbeat { sequence_id } | Frame::Corrupt { sequence_id } => {
+                buffer.extend_from_slice(&sequence_id.to_be_bytes())
+            }
+            Frame::CheckedEntry {
+                sequence_id,
+                checksum,
+                payload,
+            } => {
+                buffer.extend_from_slice(&sequence_id.to_be_bytes());
+                buffer.extend_from_slice(&checksum.0.to_be_bytes());
+                buffer.extend_from_slice(payload);
+            }
+        }
+    }
+
+    /// Decode the frame at the start of `bytes`.
+    ///
+    /// A [`Frame::CheckedEntry`] that fails its checksum decodes as a [`Frame::Corrupt`].
+    /// Returns the frame and how many bytes it used, or `None` if `bytes` doesn't hold a
+    /// whole frame yet. Any input is safe to pass here: bad input is an error, never a panic.
+    pub fn decode(bytes: &[u8]) -> Result<Option<(Frame, usize)>, DecodeError> {
+        let Some(header) = bytes.get(..HEADER_LENGTH) else {
+            return Ok(None);
+        };
+        if header[0] != WIRE_VERSION {
+            return Err(DecodeError::UnsupportedVersion(header[0]));
+        }
+        let kind = header[1];
+        let body_length = u32::from_be_bytes([header[2], header[3], header[4], header[5]]) as usize;
+        if MAX_FRAME_LENGTH < body_length {
+            return Err(DecodeError::TooLong(body_length));
+        }
+        let Some(body) = bytes.get(HEADER_LENGTH..HEADER_LENGTH + body_length) else {
+            return Ok(None);
+        };
+        let frame = match kind {
+            KIND_ENTRY => {
+                let (sequence_id, payload) = split_u64(body).ok_or(DecodeError::Malformed)?;
+                Frame::Entry {
+                    sequence_id,
+                    payload: payload.to_vec(),
+                }
+            }
+            KIND_LAGGED => Frame::Lagged {
+                count: exact_u64(body)?,
+            },
+            KIND_HEARTBEAT => Frame::Heartbeat {
+                sequence_id: exact_u64(body)?,
+            },
+            KIND_CHECKED_ENTRY => {
+                let (sequence_id, rest) = split_u64(body).ok_or(DecodeError::Malformed)?;
+                let (checksum, payload) = rest
+                    .split_first_chunk::<4>()
+                    .ok_or(DecodeError::Malformed)?;
+                let checksum = Checksum(u32::from_be_bytes(*checksum));
+                if Checksum::of(payload) == checksum {
+                    Frame::CheckedEntry {
+                        sequence_id,
+                        checksum,
+                        payload: payload.to_vec(),
+                    }
+                } else {
+                    Frame::Corrupt { sequence_id }
+                }
+            }
+            KIND_CORRUPT => Frame::Corrupt {
+                sequence_id: exact_u64(body)?,
+            },
+            unknown => return Err(DecodeError::UnknownKind(unknown)),
+        };
+        Ok(Some((frame, HEADER_LENGTH + body_length)))
+    }
+}
+
+fn split_u64(body: &[u8]) -> Option<(u64, &[u8])> {
+    let (integer, rest) = body.split_first_chunk::<8>()?;
+    Some((u64::from_be_bytes(*integer), rest))
+}
+
+fn exact_u64(body: &[u8]) -> Result<u64, DecodeError> {
+    match split_u64(body) {
+        Some((integer, [])) => Ok(integer),
+        _ => Err(DecodeError::Malformed),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bridge::{DecodeError, Frame, WIRE_VERSION};
+
+    #[test]
+    fn round_trip() {
+        let frames = [
+            Frame::Entry {
+                sequence_id: 7,
+                payload: b"hello".to_vec(),
+            },
+            Frame::Lagged { count: 3 },
+            Frame::Heartbeat { sequence_id: 10 },
+            Frame::checked_entry(11, b"checked".to_vec()),
+            Frame::Corrupt { sequence_id: 12 },
+        ];
+        let mut buffer = Vec::new();
+        frames.iter().for_each(|frame| frame.encode(&mut buffer));
+
+        let mut decoded = Vec::new();
+        let mut bytes = buffer.as_slice();
+        while let Ok(Some((frame, used))) = Frame::decode(bytes) {
+            decoded.push(frame);
+            bytes = &bytes[used..];
+        }
+        assert_eq!(frames.to_vec(), decoded);
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn partial_and_bad_input() {
+        let mut buffer = Vec::new();
+        Frame::Lagged { count: 3 }.encode(&mut buffer);
+        for end in 0..buffer.len() {
+            assert_eq!(
+                Ok(None),
+                Frame::decode(&buffer[..end]),
+                "{end} bytes is partial"
+            );
+        }
+
+        assert_eq!(
+            Err(DecodeError::UnsupportedVersion(WIRE_VERSION + 1)),
+            Frame::decode(&[WIRE_VERSION + 1, 0, 0, 0, 0, 0])
+        );
+        assert_eq!(
+            Err(DecodeError::UnknownKind(9)),
+            Frame::decode(&[WIRE_VERSION, 9, 0, 0, 0, 0])
+        );
+        assert_eq!(
+            Err(DecodeError::TooLong(u32::MAX as usize)),
+            Frame::decode(&[WIRE_VERSION, 0, 0xff, 0xff, 0xff, 0xff])
+        );
+        assert_eq!(
+            Err(DecodeError::Malformed),
+            Frame::decode(&[WIRE_VERSION, 1, 0, 0, 0, 1, 0])
+        );
+    }
+
+    #[test]
+    fn corrupt_entry() {
+        let mut buffer = Vec::new();
+        Frame::checked_entry(7, b"hello".to_vec()).encode(&mut buffer);
+        Frame::Lagged { count: 3 }.encode(&mut buffer);
+        let last = buffer.len() - 15; // The last byte of "hello", before the Lagged frame.
+        buffer[last] ^= 1;
+
+        let (frame, used) = Frame::decode(&buffer)
+            .expect("framing is intact")
+            .expect("a whole frame");
+        assert_eq!(Frame::Corrupt { sequence_id: 7 }, frame);
+        assert_eq!(
+            Ok(Some((Frame::Lagged { count: 3 }, 14))),
+            Frame::decode(&buffer[used..]),
+            "the stream carries on after a corrupt entry"
+        );
+
+        assert_eq!(
+            Err(DecodeError::Malformed),
+            Frame::decode(&[WIRE_VERSION, 3, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 7]),
+            "a checked entry needs room for its checksum"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::time::{Duration, Instant};
 
-use super::{BufferInstruction, BufferPolicy};
+use super::{BufferInstruction, BufferPolicy, PolicyContext};
 
 /// A buffer policy that limits the buffer to a certain age.
 #[derive(Debug, Clone, Copy)]
@@ -25,7 +25,7 @@ impl<T, F: Fn(&T) -> Instant> BufferAgePolicy<T, F> {
 }
 
 impl<T, F: Fn(&T) -> Instant> BufferPolicy<T> for BufferAgePolicy<T, F> {
-    fn buffer_tail_policy(&mut self, tail_item: &T) -> BufferInstruction {
+    fn buffer_tail_policy(&mut self, tail_item: &T, _context: &PolicyContext) -> BufferInstruction {
         if self.age_limit < (self.get_timestamp)(tail_item).elapsed() {
             log::debug!("Popping item due to age limit");
             BufferInstruction::Pop
@@ -39,7 +39,7 @@ impl<T, F: Fn(&T) -> Instant> BufferPolicy<T> for BufferAgePolicy<T, F> {
         // No bookkeeping needed.
     }
 
-    fn on_after_pop(&mut self, _popped_item: &mut T) {
+    fn on_after_pop(&mut self, _popped_item: &T) {
         // No bookkeeping needed.
     }
 }
@@ -48,18 +48,24 @@ impl<T, F: Fn(&T) -> Instant> BufferPolicy<T> for BufferAgePolicy<T, F> {
 mod test {
     use std::time::{Duration, Instant};
 
-    use crate::buffer_policy::{BufferAgePolicy, BufferInstruction, BufferPolicy};
+    use crate::buffer_policy::{BufferAgePolicy, BufferInstruction, BufferPolicy, PolicyContext};
 
     #[test]
     fn test() {
         let time = Instant::now();
         let mut policy = BufferAgePolicy::new(Duration::from_secs(1), |_: &usize| time);
 
-        assert_eq!(policy.buffer_tail_policy(&0), BufferInstruction::Retain);
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &PolicyContext::default()),
+            BufferInstruction::Retain
+        );
 
         let time = time - Duration::from_secs(2);
         let mut policy = BufferAgePolicy::new(Duration::from_secs(1), |_: &usize| time);
 
-        assert_eq!(policy.buffer_tail_policy(&0), BufferInstruction::Pop);
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &PolicyContext::default()),
+            BufferInstruction::Pop
+        );
     }
 }
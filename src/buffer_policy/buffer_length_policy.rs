@@ -1,4 +1,4 @@
-use super::{BufferInstruction, BufferPolicy};
+use super::{BufferInstruction, BufferPolicy, PolicyContext};
 
 /// A buffer policy that limits the buffer to a certain length.
 #[derive(Debug, Clone, Copy)]
@@ -15,7 +15,11 @@ impl BufferLengthPolicy {
 }
 
 impl<T> BufferPolicy<T> for BufferLengthPolicy {
-    fn buffer_tail_policy(&mut self, _tail_item: &T) -> BufferInstruction {
+    fn buffer_tail_policy(
+        &mut self,
+        _tail_item: &T,
+        _context: &PolicyContext,
+    ) -> BufferInstruction {
         if self.limit <= self.count {
             log::debug!("Popping item due to length limit");
             BufferInstruction::Pop
@@ -30,7 +34,7 @@ impl<T> BufferPolicy<T> for BufferLengthPolicy {
         log::debug!("length increased: new_length: {}", self.count);
     }
 
-    fn on_after_pop(&mut self, _popped_item: &mut T) {
+    fn on_after_pop(&mut self, _popped_item: &T) {
         self.count -= 1;
         log::debug!("length decreased: new_length: {}", self.count);
     }
@@ -38,25 +42,42 @@ impl<T> BufferPolicy<T> for BufferLengthPolicy {
 
 #[cfg(test)]
 mod test {
-    use crate::buffer_policy::{BufferInstruction, BufferLengthPolicy, BufferPolicy};
+    use crate::buffer_policy::{
+        BufferInstruction, BufferLengthPolicy, BufferPolicy, PolicyContext,
+    };
 
     #[test]
     fn test() {
         let mut policy = BufferLengthPolicy::new(2);
 
-        assert_eq!(policy.buffer_tail_policy(&0), BufferInstruction::Retain);
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &PolicyContext::default()),
+            BufferInstruction::Retain
+        );
         policy.on_before_send(&mut 0);
 
-        assert_eq!(policy.buffer_tail_policy(&0), BufferInstruction::Retain);
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &PolicyContext::default()),
+            BufferInstruction::Retain
+        );
         policy.on_before_send(&mut 0);
 
-        assert_eq!(policy.buffer_tail_policy(&0), BufferInstruction::Pop);
-        policy.on_after_pop(&mut 0);
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &PolicyContext::default()),
+            BufferInstruction::Pop
+        );
+        policy.on_after_pop(&0);
         policy.on_before_send(&mut 0);
 
-        assert_eq!(policy.buffer_tail_policy(&0), BufferInstruction::Pop);
-        policy.on_after_pop(&mut 0);
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &PolicyContext::default()),
+            BufferInstruction::Pop
+        );
+        policy.on_after_pop(&0);
 
-        assert_eq!(policy.buffer_tail_policy(&0), BufferInstruction::Retain);
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &PolicyContext::default()),
+            BufferInstruction::Retain
+        );
     }
 }
@@ -1,4 +1,4 @@
-use super::{BufferInstruction, BufferPolicy};
+use super::{BufferInstruction, BufferPolicy, PolicyContext};
 
 /// A buffer policy that limits the buffer to a certain weight.
 #[derive(Debug, Clone, Copy)]
@@ -27,7 +27,11 @@ impl<T, F: Fn(&T) -> usize> BufferWeightPolicy<T, F> {
 }
 
 impl<T, F: Fn(&T) -> usize> BufferPolicy<T> for BufferWeightPolicy<T, F> {
-    fn buffer_tail_policy(&mut self, _tail_item: &T) -> BufferInstruction {
+    fn buffer_tail_policy(
+        &mut self,
+        _tail_item: &T,
+        _context: &PolicyContext,
+    ) -> BufferInstruction {
         if self.weight_limit < self.weight {
             log::debug!("Popping item due to weight limit");
             BufferInstruction::Pop
@@ -42,7 +46,7 @@ impl<T, F: Fn(&T) -> usize> BufferPolicy<T> for BufferWeightPolicy<T, F> {
         log::debug!("weight increased: new_weight: {}", self.weight);
     }
 
-    fn on_after_pop(&mut self, popped_item: &mut T) {
+    fn on_after_pop(&mut self, popped_item: &T) {
         self.weight = self.weight.saturating_sub((self.get_weight)(popped_item));
         log::debug!("weight decreased: new_weight: {}", self.weight);
     }
@@ -50,7 +54,9 @@ impl<T, F: Fn(&T) -> usize> BufferPolicy<T> for BufferWeightPolicy<T, F> {
 
 #[cfg(test)]
 mod test {
-    use crate::buffer_policy::{BufferInstruction, BufferPolicy, BufferWeightPolicy};
+    use crate::buffer_policy::{
+        BufferInstruction, BufferPolicy, BufferWeightPolicy, PolicyContext,
+    };
 
     #[test]
     fn test() {
@@ -58,18 +64,33 @@ mod test {
         let mut policy = BufferWeightPolicy::new(2, |item: &usize| *item);
 
         policy.on_before_send(&mut 0);
-        assert_eq!(policy.buffer_tail_policy(&0), BufferInstruction::Retain);
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &PolicyContext::default()),
+            BufferInstruction::Retain
+        );
 
         policy.on_before_send(&mut 1);
-        assert_eq!(policy.buffer_tail_policy(&1), BufferInstruction::Retain);
+        assert_eq!(
+            policy.buffer_tail_policy(&1, &PolicyContext::default()),
+            BufferInstruction::Retain
+        );
 
         policy.on_before_send(&mut 2);
-        assert_eq!(policy.buffer_tail_policy(&2), BufferInstruction::Pop);
+        assert_eq!(
+            policy.buffer_tail_policy(&2, &PolicyContext::default()),
+            BufferInstruction::Pop
+        );
 
-        policy.on_after_pop(&mut 1);
-        assert_eq!(policy.buffer_tail_policy(&3), BufferInstruction::Retain);
+        policy.on_after_pop(&1);
+        assert_eq!(
+            policy.buffer_tail_policy(&3, &PolicyContext::default()),
+            BufferInstruction::Retain
+        );
 
         policy.on_before_send(&mut 1);
-        assert_eq!(policy.buffer_tail_policy(&4), BufferInstruction::Pop);
+        assert_eq!(
+            policy.buffer_tail_policy(&4, &PolicyContext::default()),
+            BufferInstruction::Pop
+        );
     }
 }
@@ -1,9 +1,10 @@
-use super::{BufferInstruction, BufferPolicy};
+use super::{BufferInstruction, BufferPolicy, PinInstruction, PolicyContext};
 
 /// A buffer policy for joining buffer policies.
 ///
 /// Composite policies only retain items that all policies agree to retain.
-/// If any policy says to pop, the item is popped.
+/// If any policy says to pop, the item is popped. Otherwise, if any policy says
+/// to defer, the item's pop is deferred.
 ///
 /// The upper policy is checked first. If it says to pop, the item is popped.
 #[derive(Debug, Clone, Copy)]
@@ -17,11 +18,11 @@ where
     PUpper: BufferPolicy<T>,
     PLower: BufferPolicy<T>,
 {
-    fn buffer_tail_policy(&mut self, tail_item: &T) -> BufferInstruction {
-        match self.upper.buffer_tail_policy(tail_item) {
+    fn buffer_tail_policy(&mut self, tail_item: &T, context: &PolicyContext) -> BufferInstruction {
+        match self.upper.buffer_tail_policy(tail_item, context) {
             BufferInstruction::Retain => {
                 log::debug!("Upper policy retained tail - checking lower policy");
-                match self.lower.buffer_tail_policy(tail_item) {
+                match self.lower.buffer_tail_policy(tail_item, context) {
                     BufferInstruction::Retain => {
                         log::debug!("Lower policy retained tail - composite policy retains tail");
                         BufferInstruction::Retain
@@ -30,12 +31,29 @@ where
                         log::debug!("Lower policy pops tail - composite policy pops tail");
                         BufferInstruction::Pop
                     }
+                    BufferInstruction::Defer => {
+                        log::debug!("Lower policy defers tail - composite policy defers tail");
+                        BufferInstruction::Defer
+                    }
                 }
             }
             BufferInstruction::Pop => {
                 log::debug!("Upper policy pops tail - composite policy pops tail");
                 BufferInstruction::Pop
             }
+            BufferInstruction::Defer => {
+                log::debug!("Upper policy defers tail - checking lower policy");
+                match self.lower.buffer_tail_policy(tail_item, context) {
+                    BufferInstruction::Pop => {
+                        log::debug!("Lower policy pops tail - composite policy pops tail");
+                        BufferInstruction::Pop
+                    }
+                    BufferInstruction::Retain | BufferInstruction::Defer => {
+                        log::debug!("Composite policy defers tail");
+                        BufferInstruction::Defer
+                    }
+                }
+            }
         }
     }
 
@@ -45,11 +63,33 @@ where
         self.lower.on_before_send(new_item);
     }
 
-    fn on_after_pop(&mut self, popped_item: &mut T) {
+    fn on_after_pop(&mut self, popped_item: &T) {
         log::debug!("notifying policies of popped item");
         self.upper.on_after_pop(popped_item);
         self.lower.on_after_pop(popped_item);
     }
+
+    fn fold_summary(&mut self, summary: &mut Option<T>, popped_item: &T) {
+        self.upper.fold_summary(summary, popped_item);
+        self.lower.fold_summary(summary, popped_item);
+    }
+
+    fn pin_policy(&mut self, new_item: &T) -> PinInstruction {
+        match (
+            self.upper.pin_policy(new_item),
+            self.lower.pin_policy(new_item),
+        ) {
+            (PinInstruction::Replace, _) | (_, PinInstruction::Replace) => {
+                log::debug!("A policy replaces pins - composite policy replaces pins");
+                PinInstruction::Replace
+            }
+            (PinInstruction::Pin, _) | (_, PinInstruction::Pin) => {
+                log::debug!("A policy pins - composite policy pins");
+                PinInstruction::Pin
+            }
+            (PinInstruction::Unpinned, PinInstruction::Unpinned) => PinInstruction::Unpinned,
+        }
+    }
 }
 
 /// Extension trait for building composite buffer policies.
@@ -74,3 +114,77 @@ where
         CompositeBufferPolicy { upper: self, lower }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::buffer_policy::{
+        BufferInstruction, BufferLengthPolicy, BufferPolicy, BufferPolicyExtension, PinInstruction,
+        PolicyContext,
+    };
+
+    struct PinEvens;
+    impl BufferPolicy<usize> for PinEvens {
+        fn buffer_tail_policy(
+            &mut self,
+            _tail_item: &usize,
+            _context: &PolicyContext,
+        ) -> BufferInstruction {
+            BufferInstruction::Retain
+        }
+
+        fn on_before_send(&mut self, _new_item: &mut usize) {}
+
+        fn on_after_pop(&mut self, _popped_item: &usize) {}
+
+        fn pin_policy(&mut self, new_item: &usize) -> PinInstruction {
+            if new_item.is_multiple_of(2) {
+                PinInstruction::Pin
+            } else {
+                PinInstruction::Unpinned
+            }
+        }
+    }
+
+    #[test]
+    fn pin() {
+        let mut policy = BufferLengthPolicy::new(2).wrap(PinEvens);
+
+        assert_eq!(policy.pin_policy(&1), PinInstruction::Unpinned);
+        assert_eq!(policy.pin_policy(&2), PinInstruction::Pin);
+
+        let mut policy = PinEvens.wrap(BufferLengthPolicy::new(2));
+        assert_eq!(policy.pin_policy(&2), PinInstruction::Pin);
+    }
+
+    struct AlwaysDefer;
+    impl BufferPolicy<usize> for AlwaysDefer {
+        fn buffer_tail_policy(
+            &mut self,
+            _tail_item: &usize,
+            _context: &PolicyContext,
+        ) -> BufferInstruction {
+            BufferInstruction::Defer
+        }
+
+        fn on_before_send(&mut self, _new_item: &mut usize) {}
+
+        fn on_after_pop(&mut self, _popped_item: &usize) {}
+    }
+
+    #[test]
+    fn defer() {
+        let context = PolicyContext::default();
+        let mut policy = AlwaysDefer.wrap(BufferLengthPolicy::new(1));
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &context),
+            BufferInstruction::Defer
+        );
+
+        policy.on_before_send(&mut 0);
+        assert_eq!(
+            policy.buffer_tail_policy(&0, &context),
+            BufferInstruction::Pop,
+            "pop wins over defer"
+        );
+    }
+}
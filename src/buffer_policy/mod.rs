@@ -8,4 +8,4 @@ pub use buffer_age_policy::BufferAgePolicy;
 pub use buffer_length_policy::BufferLengthPolicy;
 pub use buffer_weight_policy::BufferWeightPolicy;
 pub use composite_buffer_policy::{BufferPolicyExtension, CompositeBufferPolicy};
-pub use policy_trait::{BufferInstruction, BufferPolicy};
+pub use policy_trait::{BufferInstruction, BufferPolicy, PinInstruction, PolicyContext};
@@ -15,6 +15,46 @@ pub enum BufferInstruction {
     /// After after_pop() disposes of the tail item, before_send() is called again to
     /// determine what to do with the new item.
     Pop,
+    /// Remove the tail item, but leave it visible to subscribers until the next buffer swap.
+    ///
+    /// As far as the policy is concerned, this is a Pop: after_pop() is called with a clone
+    /// of the tail item, and the next item is considered the tail. The entry itself lingers
+    /// until the Engine next publishes, which smooths the retention boundary for subscribers
+    /// that are in the middle of reading the tail.
+    Defer,
+}
+
+/// The state of the channel, for policies that need more than the tail item to decide.
+///
+/// This lets you write policies like "retain more while there are many subscribers" or
+/// "drop aggressively when nobody is listening."
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PolicyContext {
+    /// How many Receivers are currently subscribed. This is the same reasonable-effort
+    /// counter as [`crate::Splaycast::subscriber_count`].
+    pub subscriber_count: usize,
+    /// The sequence id of the newest entry in the buffer, or 0 if nothing has been sent.
+    pub tip_id: u64,
+    /// How many entries are currently in the buffer, including pinned entries.
+    pub buffer_len: usize,
+}
+
+/// Whether a new item should be protected from buffer pops.
+///
+/// Pinned entries are skipped over when the buffer looks for a tail to pop, so they stay
+/// available to subscribers (e.g., a snapshot or keyframe that late joiners need) until
+/// they are unpinned or replaced. They still count toward your policy's bookkeeping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PinInstruction {
+    /// The item is subject to the buffer policy like any other item.
+    #[default]
+    Unpinned,
+    /// Pin the item, alongside any entries that are already pinned.
+    Pin,
+    /// Pin the item, and unpin any entries that are already pinned.
+    ///
+    /// This is what you want for "the latest snapshot must always be available."
+    Replace,
 }
 
 /// Determines when the buffer should pop or retain items.
@@ -27,7 +67,9 @@ pub enum BufferInstruction {
 /// and implement your own policy as is appropriate for your own channel.
 pub trait BufferPolicy<T> {
     /// This method is called to determine how the channel buffer should be managed.
-    fn buffer_tail_policy(&mut self, tail_item: &T) -> BufferInstruction;
+    ///
+    /// The context describes the channel as it stands, right before the new item is added.
+    fn buffer_tail_policy(&mut self, tail_item: &T, context: &PolicyContext) -> BufferInstruction;
 
     /// Called to notify when a new item is committed to the buffer.
     ///
@@ -42,10 +84,36 @@ pub trait BufferPolicy<T> {
     /// Called to notify when an item is removed from the buffer.
     ///
     /// This happens after the item is removed from the buffer. It is called from the synchronous
-    /// Engine context, so you get `&mut` access to the item that was removed. It is a clone of
-    /// the original, however.
+    /// Engine context. Receivers may still be reading the item, e.g., for a deferred pop, so you
+    /// only get to look at it.
     ///
     /// Policies that do bookkeeping on items should do it here. This is called once for each item.
-    /// Policies may alter the item in place, but remember that this is just a clone of the original.
-    fn on_after_pop(&mut self, popped_item: &mut T);
+    fn on_after_pop(&mut self, popped_item: &T);
+
+    /// Called to decide whether a new item should be pinned in the buffer.
+    ///
+    /// This happens right after `on_before_send()`. Pinned entries are never offered to
+    /// `buffer_tail_policy()`; the next unpinned entry is considered the tail instead.
+    /// By default, nothing is pinned.
+    fn pin_policy(&mut self, _new_item: &T) -> PinInstruction {
+        PinInstruction::Unpinned
+    }
+
+    /// Called to fold a popped item into the buffer's summary entry.
+    ///
+    /// This happens right after `on_after_pop()`, for items that are popped (not deferred).
+    /// Set `summary` to keep a rolling aggregate of everything that fell off the buffer,
+    /// e.g., a merged order book or a count by type. The summary is retained at the tail of
+    /// the buffer, in place of the entries it summarizes, so late joiners and laggards
+    /// receive it as an ordinary entry instead of just a Lagged count. It takes the
+    /// sequence id of the last item folded into it.
+    ///
+    /// Since the summary is a rolling aggregate, it repeats what a Receiver read before it
+    /// fell behind. So only Receivers that haven't yielded an entry yet, or since a Reset,
+    /// receive it. The others get a Lagged in its place, and so do Receivers that start
+    /// from a sequence id.
+    ///
+    /// The summary is never offered to `buffer_tail_policy()`. Setting it to None drops it.
+    /// By default, there is no summary.
+    fn fold_summary(&mut self, _summary: &mut Option<T>, _popped_item: &T) {}
 }
@@ -0,0 +1,74 @@
+use std::{any::Any, sync::Arc};
+
+/// Immutable data describing a channel, attached when it is created with
+/// [`crate::wrap_with_metadata`].
+///
+/// Any Receiver can read it with [`crate::Receiver::channel_metadata`], so generic consumer
+/// code can tell what it is subscribed to without a side table keyed by channel.
+#[derive(Clone, Default)]
+pub struct ChannelMetadata {
+    name: Option<String>,
+    schema_id: Option<u64>,
+    value: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl std::fmt::Debug for ChannelMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChannelMetadata")
+            .field("name", &self.name)
+            .field("schema_id", &self.schema_id)
+            .field("value", &self.value.is_some())
+            .finish()
+    }
+}
+
+impl ChannelMetadata {
+    /// Metadata with nothing in it. This is what channels get by default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name the channel, e.g., for logs and metrics.
+    pub fn with_name(self, name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+
+    /// Identify the schema of the channel's items.
+    pub fn with_schema_id(self, schema_id: u64) -> Self {
+        Self {
+            schema_id: Some(schema_id),
+            ..self
+        }
+    }
+
+    /// Attach any other value.
+    pub fn with_value(self, value: Arc<dyn Any + Send + Sync>) -> Self {
+        Self {
+            value: Some(value),
+            ..self
+        }
+    }
+
+    /// The channel's name, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The channel's schema id, if it has one.
+    pub fn schema_id(&self) -> Option<u64> {
+        self.schema_id
+    }
+
+    /// The attached value, if there is one.
+    pub fn value(&self) -> Option<&Arc<dyn Any + Send + Sync>> {
+        self.value.as_ref()
+    }
+
+    /// The attached value, if there is one and it is a `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.as_deref()?.downcast_ref()
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::{buffer_policy::BufferPolicy, Engine};
+
+/// How an Engine compresses [`Payload`] entries, for [`Engine::set_compression`].
+#[derive(Debug, Clone, Copy)]
+pub struct Compression {
+    threshold: usize,
+    cache_decompressed: bool,
+}
+
+impl Compression {
+    /// Compress payloads of `threshold` bytes or more, with lz4. A payload stays plain if
+    /// compressing doesn't make it smaller.
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            cache_decompressed: false,
+        }
+    }
+
+    /// Keep each entry's decompressed bytes once the first receiver reads them, so that
+    /// the others share them instead of decompressing their own copy. The entry then holds
+    /// both forms for as long as anything holds a clone of it.
+    pub fn cache_decompressed(mut self, cache_decompressed: bool) -> Self {
+        self.cache_decompressed = cache_decompressed;
+        self
+    }
+
+    /// Compress `payload` if it's big enough. Returns the bytes in and out, if it was a
+    /// candidate.
+    fn compress(&self, payload: &mut Payload) -> Option<(usize, usize)> {
+        let Repr::Plain(bytes) = payload.repr.as_ref() else {
+            return None;
+        };
+        if bytes.len() < self.threshold {
+            return None;
+        }
+        let compressed = lz4_flex::compress(bytes);
+        if compressed.len() < bytes.len() {
+            log::trace!("compressed {} bytes to {}", bytes.len(), compressed.len());
+            *payload = Payload {
+                repr: Arc::new(Repr::Compressed {
+                    compressed,
+                    len: bytes.len(),
+                    cache: self.cache_decompressed.then(OnceLock::new),
+                }),
+            };
+        }
+        Some((payload.len(), payload.stored_len()))
+    }
+}
+
+impl<Upstream, Policy> Engine<Upstream, Payload, Policy>
+where
+    Upstream: futures::Stream<Item = Payload> + Unpin,
+    Policy: BufferPolicy<Payload>,
+{
+    /// Compress large entries once, as they are absorbed, and decompress them as receivers
+    /// read them. This trades CPU for buffer memory: see [`crate::Stats::compression_ratio`]
+    /// for how much it saves.
+    ///
+    /// Entries are compressed before the buffer policy sees them, so a
+    /// [`crate::buffer_policy::BufferWeightPolicy`] weighing [`Payload::stored_len`] bounds
+    /// the memory actually held.
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.set_compress_hook(Box::new(move |payload| compression.compress(payload)));
+    }
+}
+
+enum Repr {
+    Plain(Arc<[u8]>),
+    Compressed {
+        compressed: Vec<u8>,
+        len: usize,
+        cache: Option<OnceLock<Arc<[u8]>>>,
+    },
+}
+
+/// Bytes that an Engine may compress in its buffer, with [`Engine::set_compression`].
+///
+/// Clones are cheap, and share the same bytes. Read them with [`Payload::bytes`], which
+/// decompresses them if they are compressed.
+#[derive(Clone)]
+pub struct Payload {
+    repr: Arc<Repr>,
+}
+
+impl Payload {
+    /// A plain payload. The Engine decides whether to compress it.
+    pub fn new(bytes: impl Into<Arc<[u8]>>) -> Self {
+        Self {
+            repr: Arc::new(Repr::Plain(bytes.into())),
+        }
+    }
+
+    /// The payload's bytes, decompressed if need be.
+    #[allow(clippy::expect_used)] // Only this module compresses payloads.
+    pub fn bytes(&self) -> Arc<[u8]> {
+        match self.repr.as_ref() {
+            Repr::Plain(bytes) => bytes.clone(),
+            Repr::Compressed {
+                compressed,
+                len,
+                cache,
+            } => {
+                let decompress = || -> Arc<[u8]> {
+                    lz4_flex::decompress(compressed, *len)
+                        .expect("payloads are compressed by lz4_flex")
+                        .into()
+                };
+                match cache {
+                    Some(cache) => cache.get_or_init(decompress).clone(),
+                    None => decompress(),
+                }
+            }
+        }
+    }
+
+    /// How many bytes the payload has, decompressed.
+    pub fn len(&self) -> usize {
+        match self.repr.as_ref() {
+            Repr::Plain(bytes) => bytes.len(),
+            Repr::Compressed { len, .. } => *len,
+        }
+    }
+
+    /// Whether the payload has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many bytes the payload holds in the buffer: its compressed size, if it's
+    /// compressed.
+    pub fn stored_len(&self) -> usize {
+        match self.repr.as_ref() {
+            Repr::Plain(bytes) => bytes.len(),
+            Repr::Compressed { compressed, .. } => compressed.len(),
+        }
+    }
+
+    /// Whether the Engine compressed this payload.
+    pub fn is_compressed(&self) -> bool {
+        matches!(self.repr.as_ref(), Repr::Compressed { .. })
+    }
+}
+
+impl From<Vec<u8>> for Payload {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<&[u8]> for Payload {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl PartialEq for Payload {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.repr, &other.repr) || self.bytes() == other.bytes()
+    }
+}
+
+impl Eq for Payload {}
+
+impl std::fmt::Debug for Payload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Payload")
+            .field("len", &self.len())
+            .field("stored_len", &self.stored_len())
+            .finish()
+    }
+}
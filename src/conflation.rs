@@ -0,0 +1,193 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+use crate::SplaycastEntry;
+
+/// Whether a channel is delivering every entry, or only the latest entry for each key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fidelity {
+    /// Every entry is delivered.
+    Full,
+    /// A buffered entry is dropped when a newer entry with the same key is published, so
+    /// subscribers that are behind skip straight to the latest value for each key.
+    Conflated,
+}
+
+/// When an Engine switches a channel to keyed conflation under pressure, and back.
+///
+/// Pressure is checked on Engine passes that publish something. The channel is conflated
+/// when the slowest subscriber falls `enter_backlog` entries behind the head, and goes back
+/// to full fidelity once every subscriber is within `exit_backlog`. Keep `exit_backlog`
+/// below `enter_backlog`, so the channel doesn't flap.
+///
+/// Finding the slowest subscriber visits every subscriber, so the backlog is sampled at
+/// most once per [`AdaptiveConflation::sample_interval`], and passes in between reuse the
+/// last sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveConflation {
+    enter_backlog: usize,
+    exit_backlog: usize,
+    enter_lag_events: Option<u64>,
+    sample_interval: Duration,
+}
+
+impl AdaptiveConflation {
+    /// Conflate when the slowest subscriber's backlog reaches `enter_backlog`, and stop once
+    /// it is down to `exit_backlog`.
+    pub fn backlog(enter_backlog: usize, exit_backlog: usize) -> Self {
+        Self {
+            enter_backlog,
+            exit_backlog,
+            enter_lag_events: None,
+            sample_interval: Duration::from_millis(10),
+        }
+    }
+
+    /// Also conflate when subscribers report `count` or more lag events between Engine
+    /// passes. The channel only goes back to full fidelity after a pass with no lag.
+    pub fn with_lag_events(self, count: u64) -> Self {
+        Self {
+            enter_lag_events: Some(count),
+            ..self
+        }
+    }
+
+    /// Measure the slowest subscriber's backlog at most once per `interval`. The default is
+    /// 10ms. A shorter interval reacts sooner, and costs more with many subscribers.
+    pub fn sample_interval(self, interval: Duration) -> Self {
+        Self {
+            sample_interval: interval,
+            ..self
+        }
+    }
+}
+
+/// The latest buffered entry id for each key, while the channel is conflated.
+trait KeyIndex<Item>: Send {
+    /// Record `id` as the latest entry for `item`'s key, returning the id it supersedes.
+    fn supersede(&mut self, item: &Item, id: u64) -> Option<u64>;
+    fn clear(&mut self);
+}
+
+struct Keyed<K, F> {
+    key: F,
+    latest: HashMap<K, u64>,
+}
+
+impl<Item, K, F> KeyIndex<Item> for Keyed<K, F>
+where
+    K: Hash + Eq + Send,
+    F: Fn(&Item) -> K + Send,
+{
+    fn supersede(&mut self, item: &Item, id: u64) -> Option<u64> {
+        self.latest.insert((self.key)(item), id)
+    }
+
+    fn clear(&mut self) {
+        self.latest.clear();
+    }
+}
+
+type Marker<Item> = Box<dyn Fn(Fidelity) -> Item + Send>;
+
+/// The adaptive conflation stage of an Engine.
+pub(crate) struct Conflation<Item> {
+    thresholds: AdaptiveConflation,
+    fidelity: Fidelity,
+    index: Box<dyn KeyIndex<Item>>,
+    marker: Marker<Item>,
+    /// The splaycast's lag event count as of the last check.
+    lag_events: u64,
+    /// The last backlog sample, and when it was taken.
+    backlog: Option<(Instant, usize)>,
+}
+
+impl<Item> Conflation<Item> {
+    pub fn new<K>(
+        thresholds: AdaptiveConflation,
+        key: impl Fn(&Item) -> K + Send + 'static,
+        marker: impl Fn(Fidelity) -> Item + Send + 'static,
+    ) -> Self
+    where
+        K: Hash + Eq + Send + 'static,
+        Item: 'static,
+    {
+        Self {
+            thresholds,
+            fidelity: Fidelity::Full,
+            index: Box::new(Keyed {
+                key,
+                latest: HashMap::new(),
+            }),
+            marker: Box::new(marker),
+            lag_events: 0,
+            backlog: None,
+        }
+    }
+
+    /// Switch fidelity if the pressure calls for it, returning the marker to publish.
+    /// `backlog` is only called if the last sample is stale.
+    pub fn check(&mut self, backlog: impl FnOnce() -> usize, lag_events: u64) -> Option<Item> {
+        let now = Instant::now();
+        let backlog = match self.backlog {
+            Some((sampled_at, backlog))
+                if now.duration_since(sampled_at) < self.thresholds.sample_interval =>
+            {
+                backlog
+            }
+            _ => {
+                let backlog = backlog();
+                self.backlog = Some((now, backlog));
+                backlog
+            }
+        };
+        let new_lag_events = lag_events.saturating_sub(self.lag_events);
+        self.lag_events = lag_events;
+        let lagging = self
+            .thresholds
+            .enter_lag_events
+            .is_some_and(|count| count <= new_lag_events);
+        let fidelity = match self.fidelity {
+            Fidelity::Full if self.thresholds.enter_backlog <= backlog || lagging => {
+                Fidelity::Conflated
+            }
+            Fidelity::Conflated
+                if backlog <= self.thresholds.exit_backlog
+                    && (self.thresholds.enter_lag_events.is_none() || new_lag_events == 0) =>
+            {
+                self.index.clear();
+                Fidelity::Full
+            }
+            _ => return None,
+        };
+        log::debug!("backlog {backlog}, {new_lag_events} lag events: now {fidelity:?}");
+        self.fidelity = fidelity;
+        Some((self.marker)(fidelity))
+    }
+
+    /// Forget the buffered ids, e.g., because the sequence starts over.
+    pub fn reset(&mut self) {
+        self.index.clear();
+    }
+
+    /// Remove the buffered entry that `item` supersedes, if the channel is conflated.
+    pub fn supersede(
+        &mut self,
+        queue: &mut VecDeque<SplaycastEntry<Item>>,
+        item: &Item,
+        id: u64,
+    ) -> Option<SplaycastEntry<Item>> {
+        if self.fidelity == Fidelity::Full {
+            return None;
+        }
+        let previous = self.index.supersede(item, id)?;
+        let index = crate::receiver::find(previous, queue).ok()?;
+        if queue[index].is_pinned() || queue[index].is_summary() {
+            return None;
+        }
+        queue.remove(index)
+    }
+}
@@ -1,33 +1,148 @@
 use futures::Stream;
 use std::{
-    collections::{hash_map::Entry, HashMap, VecDeque},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+    panic::AssertUnwindSafe,
     pin::{pin, Pin},
     sync::Arc,
-    task::{Context, Poll},
+    task::{Context, Poll, Waker},
+    time::Instant,
 };
 
+use crate::sync::Ordering;
+
+#[cfg(feature = "tokio")]
+use std::{cmp::Reverse, collections::BinaryHeap, future::Future, time::Duration};
+
 use crate::{
-    buffer_policy::{BufferInstruction, BufferPolicy},
-    shared::{Shared, WakeHandle},
-    SplaycastEntry,
+    buffer_policy::{BufferInstruction, BufferPolicy, PinInstruction, PolicyContext},
+    conflation::{AdaptiveConflation, Conflation, Fidelity},
+    fallible_growth::GrowthFailure,
+    publish_confirmations::{ConfirmationSlot, PublishConfirmations},
+    receiver_close::ReceiverClosed,
+    receiver_directory::ReceiverRoster,
+    relay::RelayControl,
+    reorder::{ReorderBuffer, ReorderWindow},
+    shared::Shared,
+    termination_report::{TerminationReason, TerminationReport},
+    EntryMetadata, SplaycastEntry,
 };
 
+type MetadataHook<Item> = Box<dyn FnMut(&Item) -> Option<EntryMetadata> + Send>;
+type OnTerminate = Box<dyn FnOnce(TerminationReport) + Send>;
+/// Compresses an item in place, returning the bytes in and out if it was a candidate.
+#[cfg(feature = "compression")]
+type CompressHook<Item> = Box<dyn FnMut(&mut Item) -> Option<(usize, usize)> + Send>;
+
 /// An Engine is an api-less plugin to an event loop. It is an adapter between an
 /// upstream Stream and downstream subscriber Streams.
 ///
 /// Engine can do its work without blocking or synchronizing with the receivers.
 /// This is true because Engine uses the raw `poll` affordance of Future, which
 /// vends an &mut view of self.
+///
+/// # Shutdown
+/// An Engine always terminates its splaycast when it stops, whether it completes or is
+/// dropped. Runtimes never cancel a task in the middle of a poll: when a tokio runtime
+/// shuts down, it drops its tasks between polls. The Engine notices that its runtime is
+/// shutting down as it is dropped, marks the channel dead and wakes every parked receiver,
+/// so receivers on other, still-running runtimes observe a clean end-of-stream rather than
+/// hanging. Its [`TerminationReport`] says [`TerminationReason::RuntimeShutdown`].
 pub struct Engine<Upstream, Item: Clone, Policy> {
     next_message_id: u64,
+    epoch: u64,
     upstream: Upstream,
     // TODO: buffer the buffers
     shared: Arc<Shared<Item>>,
     buffer_policy: Policy,
     park_queue: Vec<u64>,
     wake_queue: Vec<u64>,
-    parked_wakers: HashMap<u64, WakeHandle>,
+    /// Parked receivers only need their Waker: they are all woken on the next publish.
+    parked_wakers: HashMap<u64, Waker>,
+    receivers: ReceiverRoster<Item>,
     wake_limit: usize,
+    reorder: Option<ReorderBuffer<Item>>,
+    confirmations: Option<Arc<ConfirmationSlot>>,
+    metadata_hook: Option<MetadataHook<Item>>,
+    conflation: Option<Conflation<Item>>,
+    growth_failure: Option<GrowthFailure>,
+    /// Scratch space for absorbing the upstream, reused between polls.
+    absorbed: Vec<Item>,
+    items_absorbed: u64,
+    on_terminate: Option<OnTerminate>,
+    /// Set when the upstream is a [`crate::Relay`].
+    relay: Option<Arc<RelayControl>>,
+    /// Sequence ids the upstream skipped, which the next entry skips too.
+    relay_gap: u64,
+    #[cfg(feature = "compression")]
+    compress: Option<CompressHook<Item>>,
+    #[cfg(feature = "tokio")]
+    liveness_tick: Option<LivenessTick>,
+    #[cfg(feature = "tokio")]
+    expiries: Expiries,
+    /// What receivers wake instead of the Engine's task, while it is driven inline.
+    receiver_waker: Option<Waker>,
+}
+
+/// Wakes the Engine periodically, so it notices an upstream that ended without waking it.
+#[cfg(feature = "tokio")]
+struct LivenessTick {
+    period: Duration,
+    sleep: Pin<Box<tokio::time::Sleep>>,
+}
+
+#[cfg(feature = "tokio")]
+impl LivenessTick {
+    fn poll_tick(&mut self, context: &mut Context<'_>) {
+        // Registers the Engine's waker with the timer, rescheduling as long as ticks are due.
+        while self.sleep.as_mut().poll(context).is_ready() {
+            let next = tokio::time::Instant::now() + self.period;
+            self.sleep.as_mut().reset(next);
+            context.waker().wake_by_ref();
+        }
+    }
+}
+
+/// Deadlines of expiring receivers, so the Engine releases them on time even if nothing
+/// polls them.
+#[cfg(feature = "tokio")]
+#[derive(Default)]
+struct Expiries {
+    deadlines: BinaryHeap<Reverse<(tokio::time::Instant, u64)>>,
+    timer: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+#[cfg(feature = "tokio")]
+impl Expiries {
+    /// The receivers whose deadline passed. Registers the Engine's waker for the next one.
+    fn poll_expired<Item: Clone>(
+        &mut self,
+        shared: &Shared<Item>,
+        context: &mut Context<'_>,
+    ) -> Vec<u64> {
+        while let Some(expiry) = shared.take_expiry() {
+            self.deadlines.push(Reverse(expiry));
+        }
+        let now = tokio::time::Instant::now();
+        let mut expired = Vec::new();
+        while let Some(Reverse((deadline, receiver_id))) = self.deadlines.peek().copied() {
+            if now < deadline {
+                let timer = self
+                    .timer
+                    .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline)));
+                if timer.deadline() != deadline {
+                    timer.as_mut().reset(deadline);
+                }
+                if timer.as_mut().poll(context).is_ready() {
+                    context.waker().wake_by_ref();
+                }
+                return expired;
+            }
+            self.deadlines.pop();
+            expired.push(receiver_id);
+        }
+        self.timer = None;
+        expired
+    }
 }
 
 impl<Upstream, Item, Policy> std::fmt::Debug for Engine<Upstream, Item, Policy>
@@ -55,13 +170,32 @@ where
     ) -> Self {
         Self {
             next_message_id: 1,
+            epoch: 0,
             upstream,
             shared,
             buffer_policy,
             park_queue: Default::default(),
             wake_queue: Default::default(),
             parked_wakers: Default::default(),
+            receivers: ReceiverRoster::new(),
             wake_limit: 32,
+            reorder: None,
+            confirmations: None,
+            metadata_hook: None,
+            conflation: None,
+            growth_failure: None,
+            absorbed: Vec::new(),
+            items_absorbed: 0,
+            on_terminate: None,
+            relay: None,
+            relay_gap: 0,
+            #[cfg(feature = "compression")]
+            compress: None,
+            #[cfg(feature = "tokio")]
+            liveness_tick: None,
+            #[cfg(feature = "tokio")]
+            expiries: Default::default(),
+            receiver_waker: None,
         }
     }
 
@@ -71,68 +205,554 @@ where
         self.wake_limit = wake_limit.max(1)
     }
 
+    /// Hold upstream items back in a reorder buffer, and release them sorted by `key`
+    /// before they are assigned sequence ids.
+    ///
+    /// This is for upstreams that deliver slightly out of order, e.g., over UDP. Subscribers
+    /// always observe items in key order, as long as no item arrives later than the window
+    /// allows. An item that misses its window is published when it is released, out of order.
+    pub fn set_reorder_window<K: Ord>(
+        &mut self,
+        window: ReorderWindow,
+        key: impl Fn(&Item) -> K + Send + 'static,
+    ) {
+        self.reorder = Some(ReorderBuffer::new(window, key))
+    }
+
+    /// Poll the upstream at least once per `period`, even when nothing has woken the Engine.
+    ///
+    /// A well-behaved upstream wakes the Engine when it ends. Some Stream implementations
+    /// do not wake their consumer when they are dropped or closed, which would leave the
+    /// Engine, and every Receiver, waiting for data that never comes. With a liveness
+    /// tick, the end is noticed within `period` instead.
+    #[cfg(feature = "tokio")]
+    pub fn set_liveness_tick(&mut self, period: Duration) {
+        self.liveness_tick = Some(LivenessTick {
+            period,
+            sleep: Box::pin(tokio::time::sleep(period)),
+        })
+    }
+
+    /// Have receivers wake `receiver_waker` instead of whatever polls the Engine, or undo
+    /// that with None.
+    pub(crate) fn set_receiver_waker(&mut self, receiver_waker: Option<Waker>) {
+        self.shared.set_inline(receiver_waker.is_some());
+        self.receiver_waker = receiver_waker;
+    }
+
+    pub(crate) fn shared(&self) -> &Arc<Shared<Item>> {
+        &self.shared
+    }
+
+    pub(crate) fn set_reorder_buffer(&mut self, reorder: ReorderBuffer<Item>) {
+        self.reorder = Some(reorder)
+    }
+
+    /// Attach [`EntryMetadata`] to entries as they are absorbed, e.g., a tracing span
+    /// context, so distributed traces can follow each entry through the fan-out.
+    ///
+    /// `hook` runs once per upstream item, on the Engine's task. Receivers read the result
+    /// with [`crate::Receiver::metadata`].
+    pub fn set_metadata_hook(
+        &mut self,
+        hook: impl FnMut(&Item) -> Option<EntryMetadata> + Send + 'static,
+    ) {
+        self.metadata_hook = Some(Box::new(hook))
+    }
+
+    /// Switch to keyed conflation when subscribers fall behind, and back to full fidelity
+    /// when they catch up. See [`AdaptiveConflation`] for the thresholds.
+    ///
+    /// While the channel is conflated, publishing an entry drops the buffered entry with
+    /// the same `key`, so slow subscribers skip superseded values: they see the skip as a
+    /// `Lagged`, like any other gap. Caught-up subscribers still see every entry. Whenever
+    /// the fidelity changes, the Engine publishes `marker(fidelity)` first, so subscribers
+    /// know what they are getting from then on. Markers themselves are never conflated.
+    pub fn set_adaptive_conflation<K>(
+        &mut self,
+        thresholds: AdaptiveConflation,
+        key: impl Fn(&Item) -> K + Send + 'static,
+        marker: impl Fn(Fidelity) -> Item + Send + 'static,
+    ) where
+        K: std::hash::Hash + Eq + Send + 'static,
+        Item: 'static,
+    {
+        self.conflation = Some(Conflation::new(thresholds, key, marker));
+    }
+
+    /// Grow the buffer with `try_reserve`, and respond to allocation failure with
+    /// `response` instead of aborting the process.
+    ///
+    /// This is for memory-constrained deployments, where a broadcast buffer must never be
+    /// what takes the service down. It covers the buffer itself: cloning items, and your
+    /// upstream, can still allocate infallibly.
+    pub fn set_fallible_growth(&mut self, response: GrowthFailure) {
+        self.growth_failure = Some(response);
+    }
+
+    /// Call `on_receiver_closed` for each Receiver that is closed with
+    /// [`crate::Receiver::close`], with its reason. Receivers that are just dropped aren't
+    /// reported.
+    ///
+    /// This is for telling, e.g., a client that navigated away from a handler that crashed,
+    /// in logs or metrics. It runs on the closing Receiver's task.
+    pub fn set_on_receiver_closed(
+        &mut self,
+        on_receiver_closed: impl Fn(ReceiverClosed) + Send + Sync + 'static,
+    ) {
+        self.shared
+            .set_on_receiver_closed(Box::new(on_receiver_closed));
+    }
+
+    /// Call `on_terminate` once, when the splaycast terminates, with a summary of its life.
+    ///
+    /// This is for lifecycle owners that log or emit one authoritative record per channel.
+    /// It runs on the Engine's task as the Engine completes, or in its `Drop`, after every
+    /// receiver has been woken.
+    pub fn set_on_terminate(
+        &mut self,
+        on_terminate: impl FnOnce(TerminationReport) + Send + 'static,
+    ) {
+        self.on_terminate = Some(Box::new(on_terminate));
+    }
+
+    /// Get a stream of `(sequence_id, publish_instant)` confirmations as this Engine
+    /// publishes upstream items.
+    ///
+    /// This is for producers that want to bound how far they run ahead of the Engine, e.g.,
+    /// with a [`crate::channel`]. Calling this again replaces the previous stream, which
+    /// then ends.
+    pub fn publish_confirmations(&mut self) -> PublishConfirmations {
+        let slot = Arc::new(ConfirmationSlot::default());
+        if let Some(previous) = self.confirmations.replace(slot.clone()) {
+            previous.close();
+        }
+        PublishConfirmations::new(slot)
+    }
+
+    pub(crate) fn set_relay(&mut self, control: Arc<RelayControl>) {
+        self.relay = Some(control);
+    }
+
+    #[cfg(feature = "compression")]
+    pub(crate) fn set_compress_hook(&mut self, compress: CompressHook<Item>) {
+        self.compress = Some(compress);
+    }
+
+    /// Publish `initial` before the Engine starts, as if it came first from the upstream.
+    pub(crate) fn preload(&mut self, initial: Vec<Item>) {
+        let mut queue = VecDeque::with_capacity(initial.len());
+        for item in initial {
+            self.publish(&mut queue, item, 0);
+        }
+        // Nothing has subscribed yet, so deferred pops can happen right away.
+        queue.retain(|entry| !entry.is_deferred());
+        self.shared.swap_queue(queue);
+    }
+
     fn absorb_upstream(
         mut self: Pin<&mut Self>,
         context: &mut Context<'_>,
     ) -> (bool, Option<Poll<()>>) {
         let mut new_queue: Option<VecDeque<SplaycastEntry<Item>>> = None;
+        if self.shared.take_flush_request() {
+            self.flush_buffer();
+        }
+        if self.shared.take_reset_request() {
+            new_queue = Some(self.reset_sequence());
+        }
+        if let Some((gap, reset)) = self.relay.as_ref().map(|relay| relay.take()) {
+            if reset {
+                self.relay_gap = 0;
+                new_queue = Some(self.reset_sequence());
+            }
+            self.relay_gap += gap;
+        }
+        let unpinned_through = self.shared.unpinned_through();
+        let mut upstream_items = std::mem::take(&mut self.absorbed);
+        // Items are only left over when intake was paused because the buffer couldn't grow.
+        let paused = !upstream_items.is_empty();
+        // Announcements bypass the reorder buffer: they are published as they arrive.
+        while let Some(announcement) = self.shared.take_announcement() {
+            upstream_items.push(announcement);
+        }
 
         let result = loop {
+            if paused {
+                log::debug!("retrying {} held items before intake", upstream_items.len());
+                break None;
+            }
             let next = pin!(&mut self.upstream).poll_next(context);
             match next {
                 Poll::Ready(state) => match state {
                     Some(item) => {
-                        let new_queue = new_queue.get_or_insert_with(|| {
-                            let shared_queue = self.shared.load_queue();
-                            let mut new_queue = VecDeque::new();
-                            new_queue.clone_from(shared_queue.as_ref());
-                            new_queue
-                        });
-                        while BufferInstruction::Pop
-                            == new_queue
-                                .front()
-                                .map(|buffer_tail| {
-                                    self.buffer_policy.buffer_tail_policy(&buffer_tail.item)
-                                })
-                                .unwrap_or(BufferInstruction::Retain)
-                        {
-                            #[allow(clippy::expect_used)]
-                            let mut oldest = new_queue
-                                .pop_front()
-                                .expect("front was checked above; this is removing the value");
-                            self.buffer_policy.on_after_pop(&mut oldest.item);
+                        self.items_absorbed += 1;
+                        match &mut self.reorder {
+                            Some(reorder) => {
+                                reorder.push(item);
+                                upstream_items.extend(reorder.release());
+                            }
+                            None => upstream_items.push(item),
                         }
-                        let id = self.next_message_id;
-                        self.next_message_id += 1;
-
-                        let mut entry = SplaycastEntry { id, item };
-                        log::trace!("new entry id {}", entry.id);
-                        self.buffer_policy.on_before_send(&mut entry.item);
-
-                        new_queue.push_back(entry);
                     }
                     None => {
                         log::debug!("upstream closed");
+                        if let Some(reorder) = &mut self.reorder {
+                            upstream_items.extend(reorder.release_all());
+                        }
                         break Some(Poll::Ready(()));
                     }
                 },
                 Poll::Pending => {
                     log::trace!("nothing more upstream. Let's continue to send to downstreams");
+                    if let Some(reorder) = &mut self.reorder {
+                        upstream_items.extend(reorder.release());
+                        #[cfg(feature = "tokio")]
+                        reorder.poll_deadline(context);
+                    }
                     break None;
                 }
             }
         };
 
+        let Self {
+            conflation,
+            shared,
+            receivers,
+            ..
+        } = &mut *self;
+        let marker = match conflation {
+            Some(conflation) if !upstream_items.is_empty() => conflation.check(
+                || receivers.slowest_backlog(shared.subscribe_sequence_number()),
+                shared.stats().lag_events.load(Ordering::Relaxed),
+            ),
+            _ => None,
+        };
+        let marker = marker.map(|marker| (marker, false));
+        let mut held = Vec::new();
+        let mut items = marker
+            .into_iter()
+            .chain(upstream_items.drain(..).map(|item| (item, true)));
+        while let Some((item, conflate)) = items.next() {
+            if !self.make_room(&mut new_queue) {
+                if self.growth_failure == Some(GrowthFailure::Pause) {
+                    held.push(item);
+                    held.extend(items.by_ref().map(|(item, _)| item));
+                    break;
+                }
+                log::warn!("dropping an item: the buffer can't grow");
+                self.shared.stats().unhealthy.store(true, Ordering::Relaxed);
+                continue;
+            }
+            let Some(new_queue) = &mut new_queue else {
+                continue;
+            };
+            if conflate {
+                self.conflate(new_queue, &item);
+            }
+            self.publish(new_queue, item, unpinned_through);
+        }
+        drop(items);
+        if paused && held.is_empty() {
+            log::debug!("the buffer has room again, resuming intake");
+            context.waker().wake_by_ref();
+        }
+        upstream_items.append(&mut held);
+        self.absorbed = upstream_items;
+
         if let Some(new_queue) = new_queue {
             // TODO: buffer the buffers
             // This new queue process is too expensive per message, but sharing will require some clever
             // or optimistic arc swapping.
             let _to_buffer = self.shared.swap_queue(new_queue);
+            if let Some(confirmations) = &self.confirmations {
+                confirmations.confirm(self.next_message_id - 1, Instant::now());
+            }
             (true, result)
         } else {
             (false, result)
         }
     }
+
+    /// Make sure `queue` has room for one more entry, copying it from the shared queue if
+    /// this is the first entry of the pass. Without fallible growth, this always succeeds.
+    fn make_room(&mut self, queue: &mut Option<VecDeque<SplaycastEntry<Item>>>) -> bool {
+        let Some(response) = self.growth_failure else {
+            queue.get_or_insert_with(|| {
+                let shared_queue = self.shared.load_queue();
+                let mut new_queue = VecDeque::new();
+                new_queue.clone_from(shared_queue.as_ref());
+                // Pops that were deferred at the last swap happen now.
+                new_queue.retain(|entry| !entry.is_deferred());
+                new_queue
+            });
+            return true;
+        };
+        let Some(queue) = queue else {
+            *queue = self.try_copy_queue(response);
+            return queue.is_some();
+        };
+        if queue.try_reserve(1).is_ok() {
+            return true;
+        }
+        self.count_allocation_failure();
+        response == GrowthFailure::Shed && self.shed(queue)
+    }
+
+    /// Copy the shared queue with room for one more entry, or None if it can't be
+    /// allocated. Shedding copies fewer entries instead.
+    fn try_copy_queue(
+        &mut self,
+        response: GrowthFailure,
+    ) -> Option<VecDeque<SplaycastEntry<Item>>> {
+        let shared_queue = self.shared.load_queue();
+        let unpinned_through = self.shared.unpinned_through();
+        // Pops that were deferred at the last swap happen now.
+        let live = shared_queue.iter().filter(|entry| !entry.is_deferred());
+        let sheddable = live
+            .clone()
+            .filter(|entry| !is_held(entry, unpinned_through))
+            .count();
+        let mut attempts = vec![0];
+        if response == GrowthFailure::Shed {
+            attempts.extend([sheddable / 2, sheddable]);
+            attempts.dedup();
+        }
+        for shed in attempts {
+            let mut new_queue = VecDeque::new();
+            if new_queue
+                .try_reserve(live.clone().count() - shed + 1)
+                .is_err()
+            {
+                self.count_allocation_failure();
+                continue;
+            }
+            let mut to_shed = shed;
+            for entry in live.clone() {
+                if 0 < to_shed && !is_held(entry, unpinned_through) {
+                    to_shed -= 1;
+                    self.evict(&entry.item);
+                } else {
+                    new_queue.push_back(entry.clone());
+                }
+            }
+            return Some(new_queue);
+        }
+        None
+    }
+
+    /// Pop the older half of the entries that aren't pinned, returning whether any were.
+    fn shed(&mut self, queue: &mut VecDeque<SplaycastEntry<Item>>) -> bool {
+        let unpinned_through = self.shared.unpinned_through();
+        let sheddable = |entry: &SplaycastEntry<Item>| !is_held(entry, unpinned_through);
+        let count = queue.iter().filter(|entry| sheddable(entry)).count();
+        let mut to_shed = count.div_ceil(2);
+        log::warn!("shedding {to_shed} entries: the buffer can't grow");
+        let mut index = 0;
+        while 0 < to_shed && index < queue.len() {
+            if !sheddable(&queue[index]) {
+                index += 1;
+                continue;
+            }
+            to_shed -= 1;
+            if let Some(entry) = queue.remove(index) {
+                self.evict(&entry.item);
+            }
+        }
+        0 < count
+    }
+
+    fn evict(&mut self, item: &Item) {
+        self.shared
+            .stats()
+            .evictions
+            .fetch_add(1, Ordering::Relaxed);
+        self.buffer_policy.on_after_pop(item);
+    }
+
+    fn count_allocation_failure(&self) {
+        self.shared
+            .stats()
+            .allocation_failures
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Drop the buffered entry that `item` supersedes, if the channel is conflated.
+    fn conflate(&mut self, queue: &mut VecDeque<SplaycastEntry<Item>>, item: &Item) {
+        let Some(conflation) = &mut self.conflation else {
+            return;
+        };
+        if let Some(superseded) = conflation.supersede(queue, item, self.next_message_id) {
+            log::trace!("conflating entry {}", superseded.id);
+            if !superseded.is_deferred() {
+                self.buffer_policy.on_after_pop(&superseded.item);
+            }
+        }
+    }
+
+    /// Drop everything in the buffer. Sequence ids carry on where they were.
+    fn flush_buffer(&mut self) {
+        log::debug!("flushing buffer");
+        for entry in self.shared.flush_queue().iter() {
+            if !entry.is_deferred() && !entry.is_summary() {
+                self.buffer_policy.on_after_pop(&entry.item);
+            }
+        }
+    }
+
+    /// Start a new epoch with an empty buffer. Sequence ids start over from 1.
+    fn reset_sequence(&mut self) -> VecDeque<SplaycastEntry<Item>> {
+        self.epoch += 1;
+        log::debug!("resetting sequence, starting epoch {}", self.epoch);
+        for entry in self.shared.load_queue().iter() {
+            if !entry.is_deferred() && !entry.is_summary() {
+                self.buffer_policy.on_after_pop(&entry.item);
+            }
+        }
+        self.next_message_id = 1;
+        if let Some(conflation) = &mut self.conflation {
+            conflation.reset();
+        }
+        self.shared.start_epoch(self.epoch);
+        VecDeque::new()
+    }
+
+    /// Assign the next sequence id to an upstream item and push it onto the queue.
+    fn publish(
+        &mut self,
+        queue: &mut VecDeque<SplaycastEntry<Item>>,
+        item: Item,
+        unpinned_through: u64,
+    ) {
+        self.apply_buffer_policy(queue, unpinned_through);
+        // Receivers see the skipped ids as a gap, and yield the upstream's lag.
+        self.next_message_id += std::mem::take(&mut self.relay_gap);
+        let id = self.next_message_id;
+        self.next_message_id += 1;
+        self.shared
+            .stats()
+            .published
+            .fetch_add(1, Ordering::Relaxed);
+
+        let mut entry = SplaycastEntry {
+            id,
+            epoch: self.epoch,
+            item,
+            flags: 0,
+            metadata: None,
+        };
+        log::trace!("new entry id {}", entry.id);
+        #[cfg(feature = "compression")]
+        if let Some((input, output)) = self
+            .compress
+            .as_mut()
+            .and_then(|hook| hook(&mut entry.item))
+        {
+            let stats = self.shared.stats();
+            stats.compressed_entries.fetch_add(1, Ordering::Relaxed);
+            stats
+                .compression_input_bytes
+                .fetch_add(input as u64, Ordering::Relaxed);
+            stats
+                .compression_output_bytes
+                .fetch_add(output as u64, Ordering::Relaxed);
+        }
+        self.buffer_policy.on_before_send(&mut entry.item);
+        if let Some(hook) = &mut self.metadata_hook {
+            entry.metadata = hook(&entry.item).map(Arc::new);
+        }
+        match self.buffer_policy.pin_policy(&entry.item) {
+            PinInstruction::Unpinned => (),
+            PinInstruction::Pin => entry.set_pinned(true),
+            PinInstruction::Replace => {
+                log::trace!("entry {id} replaces pinned entries");
+                queue.iter_mut().for_each(|entry| entry.set_pinned(false));
+                entry.set_pinned(true);
+            }
+        }
+
+        queue.push_back(entry);
+    }
+
+    /// Let the buffer policy fold a popped entry into the summary entry, which is kept just
+    /// ahead of the tail.
+    fn fold_into_summary(
+        &mut self,
+        queue: &mut VecDeque<SplaycastEntry<Item>>,
+        popped: SplaycastEntry<Item>,
+    ) {
+        // Only pinned and deferred entries can be ahead of the summary.
+        let summary_index = queue
+            .iter()
+            .position(|entry| entry.is_summary() || !(entry.is_pinned() || entry.is_deferred()))
+            .filter(|index| queue[*index].is_summary());
+        let mut summary = summary_index
+            .and_then(|index| queue.remove(index))
+            .map(|summary| summary.item);
+        self.buffer_policy.fold_summary(&mut summary, &popped.item);
+        if let Some(item) = summary {
+            let index = queue.partition_point(|entry| entry.id < popped.id);
+            queue.insert(
+                index,
+                SplaycastEntry {
+                    id: popped.id,
+                    epoch: popped.epoch,
+                    item,
+                    flags: SplaycastEntry::<Item>::SUMMARY,
+                    metadata: None,
+                },
+            );
+        }
+    }
+
+    /// Pop tails until the buffer policy is satisfied. Pinned entries are not tails: the
+    /// oldest entry that is not pinned is offered to the policy instead. Entries whose pop
+    /// is deferred are already popped as far as the policy is concerned.
+    fn apply_buffer_policy(
+        &mut self,
+        queue: &mut VecDeque<SplaycastEntry<Item>>,
+        unpinned_through: u64,
+    ) {
+        let subscriber_count = self.shared.subscriber_count();
+        while let Some(tail_index) = queue
+            .iter()
+            .position(|entry| !entry.is_deferred() && !is_held(entry, unpinned_through))
+        {
+            let context = PolicyContext {
+                subscriber_count,
+                tip_id: self.next_message_id - 1,
+                buffer_len: queue.len(),
+            };
+            match self
+                .buffer_policy
+                .buffer_tail_policy(&queue[tail_index].item, &context)
+            {
+                BufferInstruction::Retain => break,
+                BufferInstruction::Pop => {
+                    self.shared
+                        .stats()
+                        .evictions
+                        .fetch_add(1, Ordering::Relaxed);
+                    #[allow(clippy::expect_used)]
+                    let oldest = queue
+                        .remove(tail_index)
+                        .expect("index was found above; this is removing the value");
+                    self.buffer_policy.on_after_pop(&oldest.item);
+                    self.fold_into_summary(queue, oldest);
+                }
+                BufferInstruction::Defer => {
+                    self.shared
+                        .stats()
+                        .evictions
+                        .fetch_add(1, Ordering::Relaxed);
+                    let tail = &mut queue[tail_index];
+                    log::trace!("deferring pop of {}", tail.id);
+                    tail.defer();
+                    self.buffer_policy.on_after_pop(&tail.item);
+                }
+            }
+        }
+    }
 }
 
 /// Safety: I don't use unsafe for this type
@@ -149,20 +769,49 @@ where
     fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
         log::trace!("poll: {self:?}");
         if self.shared.is_dead() {
-            self.wake_everybody_because_i_am_dead();
+            self.wake_everybody_because_i_am_dead(TerminationReason::Closed);
             return Poll::Ready(());
         }
 
-        self.shared.register_wake_interest(context); // In case we woke from a new waker, let's make sure it happens again
+        let Self {
+            shared,
+            receiver_waker,
+            ..
+        } = &*self;
+        // In case we woke from a new waker, let's make sure it happens again
+        shared.register_wake_interest(context, receiver_waker.as_ref().unwrap_or(context.waker()));
+        let Self {
+            shared, receivers, ..
+        } = &mut *self;
+        receivers.catch_up(shared);
+        #[cfg(feature = "tokio")]
+        {
+            let Self {
+                shared,
+                receivers,
+                expiries,
+                ..
+            } = &mut *self;
+            for receiver_id in expiries.poll_expired(shared, context) {
+                if receivers.expire(shared, receiver_id) {
+                    log::debug!("receiver {} expired", receivers.describe(receiver_id));
+                    shared.forget_parked_waker(receiver_id);
+                }
+            }
+        }
 
         let (dirty, early_out) = self.as_mut().absorb_upstream(context);
         if let Some(early_out) = early_out {
             log::trace!("upstream died - terminating the splaycast"); // this happens when the upstream is closed
             self.shared.set_dead();
-            self.wake_everybody_because_i_am_dead();
+            self.wake_everybody_because_i_am_dead(TerminationReason::UpstreamEnded);
             return early_out;
         }
         // Upstream is Pending here.
+        #[cfg(feature = "tokio")]
+        if let Some(liveness_tick) = &mut self.liveness_tick {
+            liveness_tick.poll_tick(context);
+        }
 
         if dirty {
             log::trace!("notifying parked: {}", self.parked_wakers.len());
@@ -178,10 +827,18 @@ where
             }
         }
         if !self.wake_queue.is_empty() {
-            for _ in 0..self.wake_limit {
-                if let Some(id) = self.wake_queue.pop() {
-                    if let Some(waker) = self.parked_wakers.remove(&id) {
-                        waker.wake();
+            let Self {
+                shared,
+                wake_queue,
+                parked_wakers,
+                receivers,
+                wake_limit,
+                ..
+            } = &mut *self;
+            for _ in 0..*wake_limit {
+                if let Some(id) = wake_queue.pop() {
+                    if let Some(waker) = parked_wakers.remove(&id) {
+                        wake_isolated(shared, receivers, id, waker);
                     } else {
                         log::warn!("wake id {id} not found");
                     }
@@ -202,24 +859,29 @@ where
             shared,
             park_queue,
             parked_wakers,
+            receivers,
             ..
         } = &mut *self;
         for (serviced, (id, waker)) in shared.drain_wakelist().enumerate() {
             if tip < waker.next_message_id() {
-                log::trace!("tip at {tip}, parking at {}", waker.next_message_id());
+                log::trace!(
+                    "tip at {tip}, parking receiver {} at {}",
+                    receivers.describe(id),
+                    waker.next_message_id()
+                );
                 let entry = parked_wakers.entry(id);
                 match entry {
                     Entry::Occupied(mut occupied_entry) => {
-                        if !occupied_entry.get().will_wake(&waker) {
+                        if !waker.will_wake(occupied_entry.get()) {
                             log::trace!("new waker for the same task id");
-                            occupied_entry.insert(waker);
+                            occupied_entry.insert(waker.into_waker());
                         } else {
                             log::trace!("duplicate wake registration");
                         }
                     }
                     Entry::Vacant(vacant_entry) => {
                         park_queue.push(id);
-                        vacant_entry.insert(waker);
+                        vacant_entry.insert(waker.into_waker());
                     }
                 }
 
@@ -229,8 +891,12 @@ where
                 }
                 continue; // this waker does not need to be woken. We parked it waiting new data
             }
-            log::trace!("waking at {}", waker.next_message_id());
-            waker.wake();
+            log::trace!(
+                "waking receiver {} at {}",
+                receivers.describe(id),
+                waker.next_message_id()
+            );
+            wake_isolated(shared, receivers, id, waker.into_waker());
 
             if wake_limit == serviced {
                 context.waker().wake_by_ref();
@@ -238,6 +904,8 @@ where
             }
         }
 
+        self.forget_closed_receivers();
+
         // Awaiting an upstream message, for which we are already Pending, and we've woken what we need to
         log::trace!("parked pending");
         Poll::Pending
@@ -245,22 +913,101 @@ where
 }
 
 impl<Upstream, Item: Clone, Policy> Engine<Upstream, Item, Policy> {
-    fn wake_everybody_because_i_am_dead(&mut self) {
+    /// Drop the parked wakers of closed receivers, instead of holding them until the next
+    /// publish.
+    fn forget_closed_receivers(&mut self) {
+        let mut closed = HashSet::new();
+        while let Some(id) = self.shared.take_closed_receiver() {
+            if self.parked_wakers.remove(&id).is_some() {
+                log::trace!("forgetting the parked waker of closed receiver {id}");
+                closed.insert(id);
+            }
+        }
+        if !closed.is_empty() {
+            self.park_queue.retain(|id| !closed.contains(id));
+            self.wake_queue.retain(|id| !closed.contains(id));
+        }
+    }
+
+    fn wake_everybody_because_i_am_dead(&mut self, reason: TerminationReason) {
         log::trace!("is dead - waking everyone");
-        for (_, waker) in std::mem::take(&mut self.parked_wakers) {
-            waker.wake();
+        for (id, waker) in std::mem::take(&mut self.parked_wakers) {
+            wake_isolated(&self.shared, &mut self.receivers, id, waker);
+        }
+        for (id, waker) in self.shared.drain_wakelist() {
+            wake_isolated(&self.shared, &mut self.receivers, id, waker.into_waker());
         }
-        for (_, waker) in self.shared.drain_wakelist() {
-            waker.wake();
+        if let Some(confirmations) = &self.confirmations {
+            confirmations.close();
         }
+        self.shared
+            .stats()
+            .engine_alive
+            .store(false, Ordering::Release);
         log::trace!("all all wake handles have been notified. Completing the Engine task");
+        if let Some(on_terminate) = self.on_terminate.take() {
+            on_terminate(TerminationReport {
+                reason,
+                items_absorbed: self.items_absorbed,
+                pops: self.shared.stats().evictions.load(Ordering::Relaxed),
+                peak_subscriber_count: self.shared.peak_subscriber_count(),
+                final_sequence_id: self.next_message_id - 1,
+            });
+        }
+    }
+}
+
+/// Whether `entry` can't be popped or shed: it is the summary, or pinned and not yet
+/// unpinned through [`crate::Splaycast::unpin_through`].
+fn is_held<Item>(entry: &SplaycastEntry<Item>, unpinned_through: u64) -> bool {
+    entry.is_summary() || (entry.is_pinned() && unpinned_through < entry.id)
+}
+
+/// Wake a receiver's task. Wakers from custom executors can panic, so a panic is
+/// contained to the receiver that owns the waker: it is disconnected instead of taking the
+/// Engine, and every other receiver, down with it.
+fn wake_isolated<Item: Clone>(
+    shared: &Shared<Item>,
+    receivers: &mut ReceiverRoster<Item>,
+    id: u64,
+    waker: Waker,
+) {
+    if std::panic::catch_unwind(AssertUnwindSafe(|| waker.wake())).is_err() {
+        receivers.poison(shared, id);
+        log::error!(
+            "waker for receiver {} panicked, disconnecting it",
+            receivers.describe(id)
+        );
     }
 }
 
+/// Whether the current tokio runtime is shutting down. Tokio doesn't say so directly, but a
+/// runtime that is shutting down cancels a task as soon as it is spawned.
+#[cfg(feature = "tokio")]
+fn runtime_is_shutting_down() -> bool {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return false;
+    };
+    let probe = runtime.spawn(futures::future::pending::<()>());
+    let cancelled = probe.is_finished();
+    probe.abort();
+    cancelled
+}
+
 impl<Upstream, Item: Clone, Policy> Drop for Engine<Upstream, Item, Policy> {
     fn drop(&mut self) {
+        // This is also how runtime shutdown reaches the receivers: the runtime drops its tasks.
         log::trace!("dropping splaycast Engine");
+        #[cfg(feature = "tokio")]
+        let reason = if runtime_is_shutting_down() {
+            log::debug!("the runtime is shutting down - terminating the splaycast");
+            TerminationReason::RuntimeShutdown
+        } else {
+            TerminationReason::EngineDropped
+        };
+        #[cfg(not(feature = "tokio"))]
+        let reason = TerminationReason::EngineDropped;
         self.shared.set_dead();
-        self.wake_everybody_because_i_am_dead()
+        self.wake_everybody_because_i_am_dead(reason)
     }
 }
@@ -0,0 +1,39 @@
+use tokio::task::JoinHandle;
+
+/// The task running a splaycast's Engine, from [`crate::spawn`].
+///
+/// Dropping the handle aborts the Engine, which terminates the splaycast just like
+/// dropping an Engine does. Use [`EngineHandle::detach`] to let it run on its own.
+#[derive(Debug)]
+pub struct EngineHandle {
+    task: Option<JoinHandle<()>>,
+}
+
+impl EngineHandle {
+    pub(crate) fn new(task: JoinHandle<()>) -> Self {
+        Self { task: Some(task) }
+    }
+
+    /// Whether the Engine has completed, e.g., because its upstream ended.
+    pub fn is_finished(&self) -> bool {
+        self.task.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Stop the Engine now, instead of when the handle is dropped.
+    pub fn abort(&self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+
+    /// Let the Engine run until its upstream ends, without holding on to the handle.
+    pub fn detach(mut self) {
+        drop(self.task.take());
+    }
+}
+
+impl Drop for EngineHandle {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
@@ -0,0 +1,31 @@
+use std::any::Any;
+
+/// Small out-of-band data carried alongside an entry, like a tracing span context.
+///
+/// Metadata is attached by the Engine's [metadata hook](crate::Engine::set_metadata_hook)
+/// as it absorbs each item, and receivers can read it with [`crate::Receiver::metadata`].
+/// It is shared rather than cloned, so every receiver sees the same value. Your item type
+/// does not need to change to carry it.
+pub struct EntryMetadata {
+    value: Box<dyn Any + Send + Sync>,
+}
+
+impl std::fmt::Debug for EntryMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EntryMetadata").finish_non_exhaustive()
+    }
+}
+
+impl EntryMetadata {
+    /// Wrap any value as metadata.
+    pub fn new(value: impl Any + Send + Sync) -> Self {
+        Self {
+            value: Box::new(value),
+        }
+    }
+
+    /// Get the value back, if it is a `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref()
+    }
+}
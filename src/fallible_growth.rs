@@ -0,0 +1,20 @@
+/// What an Engine does when its buffer can't grow, with [`crate::Engine::set_fallible_growth`].
+///
+/// Whatever the response, every failure is counted in [`crate::Stats::allocation_failures`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GrowthFailure {
+    /// Pop the older half of the buffer's entries to make room, whatever the buffer policy
+    /// says. Pinned and summary entries are kept. Receivers that were reading the popped
+    /// entries get a `Lagged`. If there is nothing left to pop, the item is dropped as with
+    /// [`GrowthFailure::MarkUnhealthy`].
+    Shed,
+    /// Stop taking items from the upstream, and hold the items that didn't fit. Publishing
+    /// resumes the next time the Engine is polled, e.g., when a receiver parks or the
+    /// [liveness tick](crate::Engine::set_liveness_tick) fires.
+    Pause,
+    /// Drop the items that didn't fit, and mark the splaycast unhealthy in its
+    /// [`crate::Stats`]. Dropped items never get a sequence id, so receivers don't see them
+    /// as lag.
+    MarkUnhealthy,
+}
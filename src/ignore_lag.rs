@@ -0,0 +1,102 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+
+use crate::{Message, Receiver, Replay, SubscriptionMode};
+
+/// A Receiver as a plain stream of items, from [`Receiver::ignore_lag`] or
+/// [`Receiver::ignore_lag_with`].
+///
+/// This is for subscribers that treat a `Lagged` as "log and continue": lag is passed to a
+/// callback instead of the stream, and resets are skipped. Each skip is logged at debug.
+pub struct IgnoreLag<Item, Mode = Replay, F = fn(usize)>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    receiver: Receiver<Item, Mode>,
+    on_lag: F,
+}
+
+impl<Item, Mode, F> std::fmt::Debug for IgnoreLag<Item, Mode, F>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IgnoreLag")
+            .field("receiver", &self.receiver)
+            .finish()
+    }
+}
+
+impl<Item, Mode> Receiver<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Convert this Receiver into a plain stream of items, which skips lag and resets.
+    pub fn ignore_lag(self) -> IgnoreLag<Item, Mode> {
+        self.ignore_lag_with(|_| {})
+    }
+
+    /// Convert this Receiver into a plain stream of items, which passes the count of each
+    /// lag to `on_lag`, e.g., to bump a metric, and skips resets.
+    pub fn ignore_lag_with<F>(self, on_lag: F) -> IgnoreLag<Item, Mode, F>
+    where
+        F: FnMut(usize),
+    {
+        IgnoreLag {
+            receiver: self,
+            on_lag,
+        }
+    }
+}
+
+impl<Item, Mode, F> IgnoreLag<Item, Mode, F>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Get back the Receiver.
+    pub fn into_inner(self) -> Receiver<Item, Mode> {
+        self.receiver
+    }
+}
+
+/// Safety: I don't use unsafe for this type
+impl<Item, Mode, F> Unpin for IgnoreLag<Item, Mode, F>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+}
+
+impl<Item, Mode, F> Stream for IgnoreLag<Item, Mode, F>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+    F: FnMut(usize),
+{
+    type Item = Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.receiver).poll_next(context) {
+                Poll::Ready(Some(Message::Entry { item })) => return Poll::Ready(Some(item)),
+                Poll::Ready(Some(Message::Lagged { count })) => {
+                    log::debug!("skipping lag of {count}");
+                    (self.on_lag)(count);
+                }
+                Poll::Ready(Some(Message::Reset { epoch })) => {
+                    log::debug!("skipping reset to epoch {epoch}");
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
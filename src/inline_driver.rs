@@ -0,0 +1,156 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError, Weak},
+    task::{Context, Poll},
+};
+
+use crate::sync::{AtomicBool, Ordering};
+
+use futures::{
+    future::BoxFuture,
+    task::{waker, waker_ref, ArcWake},
+};
+
+use crate::{buffer_policy::BufferPolicy, shared::Shared, Engine, SenderStream};
+
+/// How an inline channel hands its Engine to a task when it is promoted.
+pub(crate) type Spawn = Box<dyn FnOnce(BoxFuture<'static, ()>) + Send>;
+
+/// Runs an Engine on whichever thread wakes it, instead of on its own task.
+///
+/// The driver is the Engine's waker. A send wakes the Engine, which polls it right there
+/// in the sender's call, so the fan-out happens synchronously. Receivers only flag the
+/// Engine for the next poll, so a receiver's poll never does Engine work or waits on the
+/// engine lock. Once the splaycast has enough subscribers, the next wake hands the Engine
+/// to `spawn` instead, and from then on it runs like any other Engine.
+pub(crate) struct InlineDriver<Item, Policy>
+where
+    Item: Clone,
+{
+    /// None once the Engine completes or is promoted.
+    engine: Mutex<Option<Engine<SenderStream<Item>, Item, Policy>>>,
+    /// Set on every wake. Whoever holds the engine lock polls again until it is clear.
+    pending: Arc<Pending>,
+    /// Weak, because the splaycast holds the driver as the Engine's waker.
+    shared: Weak<Shared<Item>>,
+    promote_at: usize,
+    spawn: Mutex<Option<Spawn>>,
+}
+
+impl<Item, Policy> InlineDriver<Item, Policy>
+where
+    Item: Clone + Send + Sync + 'static,
+    Policy: BufferPolicy<Item> + Send + 'static,
+{
+    /// Start driving `engine` inline. It is polled once right away, so it is woken from
+    /// then on.
+    pub fn start(
+        engine: Engine<SenderStream<Item>, Item, Policy>,
+        promote_at: usize,
+        spawn: Spawn,
+    ) {
+        let driver = Arc::new(Self {
+            shared: Arc::downgrade(engine.shared()),
+            engine: Mutex::new(Some(engine)),
+            pending: Default::default(),
+            promote_at,
+            spawn: Mutex::new(Some(spawn)),
+        });
+        if let Some(engine) = driver
+            .engine
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+        {
+            engine.set_receiver_waker(Some(waker(driver.pending.clone())));
+        }
+        // The driver lives on in the wakers the Engine registers with its upstream and
+        // its splaycast. It is released when the Engine completes.
+        driver.drive();
+    }
+
+    fn drive(self: &Arc<Self>) {
+        self.pending.flag();
+        // Somebody may wake the driver after we stop polling but before we unlock, and
+        // they'll find it locked. Checking again after unlocking makes sure it runs.
+        while self.pending.is_flagged() {
+            let Ok(mut slot) = self.engine.try_lock() else {
+                return; // The lock holder polls again.
+            };
+            while self.pending.take() {
+                let Some(engine) = slot.as_mut() else {
+                    return; // Completed or promoted.
+                };
+                let subscriber_count = self
+                    .shared
+                    .upgrade()
+                    .map(|shared| shared.subscriber_count())
+                    .unwrap_or_default();
+                if self.promote_at <= subscriber_count {
+                    self.promote(slot.take());
+                    return;
+                }
+                let waker = waker_ref(self);
+                if let Poll::Ready(()) = Pin::new(engine).poll(&mut Context::from_waker(&waker)) {
+                    log::debug!("inline engine completed");
+                    *slot = None;
+                }
+            }
+        }
+    }
+
+    fn promote(&self, mut engine: Option<Engine<SenderStream<Item>, Item, Policy>>) {
+        if let Some(engine) = &mut engine {
+            engine.set_receiver_waker(None);
+        }
+        let spawn = self
+            .spawn
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let (Some(engine), Some(spawn)) = (engine, spawn) {
+            log::debug!("promoting inline engine to a task");
+            spawn(Box::pin(engine));
+        }
+    }
+}
+
+impl<Item, Policy> ArcWake for InlineDriver<Item, Policy>
+where
+    Item: Clone + Send + Sync + 'static,
+    Policy: BufferPolicy<Item> + Send + 'static,
+{
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.drive()
+    }
+}
+
+/// A wake that only asks for another poll of an inline Engine.
+#[derive(Default)]
+struct Pending {
+    // sync(Release, Acquire, AcqRel): a wake stores it before trying the engine lock, if
+    // it tries at all. The lock holder swaps it clear before each poll, and checks it again
+    // after unlocking so a wake that lost the lock race is never dropped.
+    flagged: AtomicBool,
+}
+
+impl Pending {
+    fn flag(&self) {
+        self.flagged.store(true, Ordering::Release);
+    }
+
+    fn is_flagged(&self) -> bool {
+        self.flagged.load(Ordering::Acquire)
+    }
+
+    fn take(&self) -> bool {
+        self.flagged.swap(false, Ordering::AcqRel)
+    }
+}
+
+impl ArcWake for Pending {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.flag();
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+
+use crate::{Message, Receiver, Replay, SubscriptionMode};
+
+/// A Receiver skipped `count` entries because it fell behind, from [`LagAsError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lagged {
+    /// How many entries were skipped.
+    pub count: usize,
+}
+
+impl std::fmt::Display for Lagged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "receiver lagged and skipped {} entries", self.count)
+    }
+}
+
+impl std::error::Error for Lagged {}
+
+/// A Receiver as a stream of `Result<Item, Lagged>`, from [`Receiver::lag_as_error`].
+///
+/// This composes with `TryStreamExt`, and with servers that expect a stream of results,
+/// like tonic's `Result<T, Status>` with a `map_err`. Lag is an error, but not the end:
+/// keep polling to carry on after it. Resets are skipped, and logged at debug.
+pub struct LagAsError<Item, Mode = Replay>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    receiver: Receiver<Item, Mode>,
+}
+
+impl<Item, Mode> std::fmt::Debug for LagAsError<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LagAsError")
+            .field("receiver", &self.receiver)
+            .finish()
+    }
+}
+
+impl<Item, Mode> Receiver<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Convert this Receiver into a stream of `Result<Item, Lagged>`.
+    pub fn lag_as_error(self) -> LagAsError<Item, Mode> {
+        LagAsError { receiver: self }
+    }
+}
+
+impl<Item, Mode> LagAsError<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Get back the Receiver.
+    pub fn into_inner(self) -> Receiver<Item, Mode> {
+        self.receiver
+    }
+}
+
+/// Safety: I don't use unsafe for this type
+impl<Item, Mode> Unpin for LagAsError<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+}
+
+impl<Item, Mode> Stream for LagAsError<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    type Item = Result<Item, Lagged>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            return match Pin::new(&mut self.receiver).poll_next(context) {
+                Poll::Ready(Some(Message::Entry { item })) => Poll::Ready(Some(Ok(item))),
+                Poll::Ready(Some(Message::Lagged { count })) => {
+                    Poll::Ready(Some(Err(Lagged { count })))
+                }
+                Poll::Ready(Some(Message::Reset { epoch })) => {
+                    log::debug!("skipping reset to epoch {epoch}");
+                    continue;
+                }
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            };
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use crate::sync::{AtomicBool, AtomicU64, Ordering};
+
+use crossbeam_queue::ArrayQueue;
+use futures::{stream::FusedStream, task::AtomicWaker, Stream};
+
+/// How many events a [`LagEvents`] stream holds before it drops the oldest.
+pub(crate) const CAPACITY: usize = 1024;
+
+/// One receiver yielding a [`crate::Message::Lagged`], from [`LagEvents`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LagEvent {
+    /// The receiver that lagged. See [`crate::Receiver::id`].
+    pub receiver_id: u64,
+    /// The receiver's label, if it has one. See [`crate::Receiver::label`].
+    pub label: Option<Arc<str>>,
+    /// How many entries it skipped.
+    pub count: usize,
+    /// When the receiver yielded the `Lagged`.
+    pub at: Instant,
+}
+
+/// The receiving end of one [`LagEvents`] stream, fed by every receiver of the splaycast.
+#[derive(Debug)]
+pub(crate) struct LagMonitor {
+    events: ArrayQueue<LagEvent>,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    missed: AtomicU64,
+    waker: AtomicWaker,
+    // sync(Release, Acquire): close stores it after the last event is pushed, and the
+    // stream Acquires it only once the queue is empty.
+    closed: AtomicBool,
+}
+
+impl LagMonitor {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: ArrayQueue::new(capacity.max(1)),
+            missed: Default::default(),
+            waker: Default::default(),
+            closed: Default::default(),
+        }
+    }
+
+    pub fn report(&self, event: LagEvent) {
+        if self.events.force_push(event).is_some() {
+            self.missed.fetch_add(1, Ordering::Relaxed);
+        }
+        self.waker.wake();
+    }
+
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+}
+
+/// A stream of every subscriber's lag, from [`crate::Splaycast::lag_events`].
+///
+/// This is for a monitor task that watches fan-out health for the whole channel, instead of
+/// instrumenting every consumer loop. Up to 1024 events are queued: if the monitor falls
+/// behind, the oldest events are dropped and counted in [`LagEvents::missed`].
+///
+/// The stream ends when the splaycast terminates.
+#[derive(Debug)]
+pub struct LagEvents {
+    monitor: Arc<LagMonitor>,
+    terminated: bool,
+}
+
+impl LagEvents {
+    pub(crate) fn new(monitor: Arc<LagMonitor>) -> Self {
+        Self {
+            monitor,
+            terminated: false,
+        }
+    }
+
+    /// How many events were dropped because this stream wasn't keeping up.
+    pub fn missed(&self) -> u64 {
+        self.monitor.missed.load(Ordering::Relaxed)
+    }
+
+    fn check(&mut self) -> Poll<Option<LagEvent>> {
+        if let Some(event) = self.monitor.events.pop() {
+            Poll::Ready(Some(event))
+        } else if self.monitor.closed.load(Ordering::Acquire) {
+            self.terminated = true;
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Stream for LagEvents {
+    type Item = LagEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        if let Poll::Ready(next) = self.check() {
+            return Poll::Ready(next);
+        }
+        self.monitor.waker.register(context.waker());
+        // An event may have arrived before we registered, so check once more.
+        self.check()
+    }
+}
+
+impl FusedStream for LagEvents {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
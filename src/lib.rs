@@ -20,8 +20,8 @@
 //! 2. The [`splaycast::Receiver`]. It's just a stream. You use it or compose it how you need to.
 //! 3. The [`Splaycast`] itself. This is how you subscribe new receivers. It is not a sender, and you cannot send to it.
 //!
-//! If you drop [1] the Upstream Stream, [2] the Splaycast, or [3] the Engine, the
-//! splaycast is terminated and everything is dropped. Your Receivers *_will receive
+//! If you drop [1] the Upstream Stream, [2] the Splaycast (all of its clones), or [3] the
+//! Engine, the splaycast is terminated and everything is dropped. Your Receivers *_will receive
 //! prompt notification_* of the termination of any critical upstream resource.
 //!
 //! ## Engine
@@ -79,19 +79,101 @@
 //!
 //! Some basic examples can be found under `src/benches`.
 //!
+//! # Sequence ids
+//! Each entry gets a sequence id, counting up from 1. Ids are u64, so they do not wrap
+//! in practice: at a billion entries per second, that's over 500 years.
+//!
+//! Sequences can be reset on purpose with [`Splaycast::reset_sequence`], e.g., after
+//! restoring upstream state. Each reset starts a new epoch. Receivers detect the new
+//! epoch, yield a [`Message::Reset`], and re-synchronize at the start of the new sequence
+//! rather than misinterpreting lag across the reset boundary.
+//!
 //! # Feature Flags
+//! * `bridge`: The [`bridge`] wire format, for carrying a splaycast across processes.
+//! * `bytes`: [`VectoredReceiver`], for flushing buffered `Bytes` entries with vectored writes.
+//! * `compression`: [`Engine::set_compression`], which compresses large [`Payload`] entries
+//!   in the buffer.
+//! * `registry`: A process-wide `registry` of named splaycasts, for debug endpoints.
+//! * `simulate`: A `simulate` module for sizing buffers and policies in virtual time.
+//! * `tokio`: Timer-based apis, like [`Sender::send_timeout`], [`Sender::send_with_retry`],
+//!   [`Engine::set_liveness_tick`], [`Receiver::with_activity_markers`],
+//!   [`Receiver::next_timeout`] and [`Splaycast::subscribe_throttled`].
+//!   These use the tokio timer. Also [`spawn`] and [`Receiver::sharded_process`], which
+//!   spawn tokio tasks.
 //!
+//! # Memory ordering audit
+//! Every atomic is annotated with the orderings it may use and what it pairs with, and
+//! `ATOMICS.md` is generated from those annotations. Build with `--cfg splaycast_audit` to
+//! route the atomics through recording wrappers, e.g., alongside `-Zsanitizer=thread`: the
+//! `audit` module then reports every access site, and the atomics audit test checks them
+//! against the annotations.
 
+#[cfg(feature = "tokio")]
+mod activity_markers;
+#[cfg(splaycast_audit)]
+pub mod audit;
+mod await_subscribers;
+#[cfg(feature = "bridge")]
+pub mod bridge;
 pub mod buffer_policy;
+mod channel_metadata;
+#[cfg(feature = "compression")]
+mod compression;
+mod conflation;
 mod engine;
+#[cfg(feature = "tokio")]
+mod engine_handle;
+mod entry_metadata;
+mod fallible_growth;
+mod ignore_lag;
+mod inline_driver;
+mod lag_as_error;
+mod lag_events;
+mod mapped_receiver;
+mod mirror;
+mod multi_engine;
+mod multi_receiver;
+mod next_matching;
+mod position_stream;
+mod publish_confirmations;
+mod quiesced;
 mod receiver;
+mod receiver_close;
+mod receiver_directory;
+mod receiver_group;
+#[cfg(feature = "registry")]
+pub mod registry;
+mod relay;
+mod reorder;
+#[cfg(feature = "tokio")]
+mod retry_policy;
 mod sender;
+#[cfg(feature = "tokio")]
+mod sharded;
 mod shared;
+#[cfg(feature = "simulate")]
+pub mod simulate;
+mod snapshot;
 mod splaycast;
+mod splaycast_template;
+mod subscribe_options;
+mod subscribe_permit;
+mod subscriber_count_changes;
+mod subscription_mode;
+mod sync;
+mod termination_report;
+#[cfg(feature = "tokio")]
+mod throttled;
+mod timestamped;
+#[cfg(feature = "bytes")]
+mod vectored;
 
 /// Messages on a Splaycast Receiver are either an Entry or a Lagged. If you
 /// lag, you'll get a count of how many messages were skipped, and then you'll
 /// resume Entries from that point on.
+///
+/// If the splaycast's sequence is reset, you'll get a Reset, and then you'll
+/// resume Entries from the start of the new sequence.
 #[derive(Debug, PartialEq)]
 pub enum Message<T> {
     /// The item is cloned from the upstream stream.
@@ -99,14 +181,71 @@ pub enum Message<T> {
     /// From splaycast, this tells you how many messages you missed.
     /// Consume faster, publish slower, or possibly buffer more to reduce these!
     Lagged { count: usize },
+    /// The sequence was reset with [`Splaycast::reset_sequence`]. Sequence ids start
+    /// over from 1 in the new epoch, so positions from before the reset don't apply.
+    Reset { epoch: u64 },
 }
 
-use buffer_policy::{BufferLengthPolicy, BufferPolicy};
+use std::{pin::Pin, sync::Arc, time::Duration};
+
+use futures::future::BoxFuture;
+use inline_driver::InlineDriver;
+
+#[cfg(feature = "tokio")]
+pub use activity_markers::{Activity, ActivityMarkers};
+pub use await_subscribers::AwaitSubscribers;
+use buffer_policy::{BufferAgePolicy, BufferLengthPolicy, BufferPolicy, BufferPolicyExtension};
+pub use channel_metadata::ChannelMetadata;
+#[cfg(feature = "compression")]
+pub use compression::{Compression, Payload};
+pub use conflation::{AdaptiveConflation, Fidelity};
 pub use engine::Engine;
-pub use receiver::Receiver;
-pub use sender::{Sender, SenderStream};
-pub use shared::SubscriberCountHandle;
-pub use splaycast::Splaycast;
+#[cfg(feature = "tokio")]
+pub use engine_handle::EngineHandle;
+pub use entry_metadata::EntryMetadata;
+pub use fallible_growth::GrowthFailure;
+pub use ignore_lag::IgnoreLag;
+pub use lag_as_error::{LagAsError, Lagged};
+pub use lag_events::{LagEvent, LagEvents};
+pub use mapped_receiver::MappedReceiver;
+pub use mirror::SplaycastMirror;
+pub use multi_engine::{MultiEngine, MultiEngineHandle};
+pub use multi_receiver::MultiReceiver;
+pub use next_matching::NextMatching;
+pub use position_stream::PositionStream;
+pub use publish_confirmations::PublishConfirmations;
+pub use quiesced::Quiesced;
+#[cfg(feature = "tokio")]
+pub use receiver::NextTimeout;
+pub use receiver::{Receiver, SubscriptionState};
+pub use receiver_close::{CloseReason, ReceiverClosed};
+pub use receiver_group::{GroupReceiver, ReceiverGroup, WaitAllAt};
+pub use relay::Relay;
+pub use reorder::ReorderWindow;
+#[cfg(feature = "tokio")]
+pub use retry_policy::RetryPolicy;
+pub use sender::{SendError, Sender, SenderStream};
+pub use shared::{AbandonedBacklog, ReceiverCursor, Stats, StatsHandle, SubscriberCountHandle};
+pub use snapshot::Snapshot;
+pub use splaycast::{Splaycast, SubscribeError, WeakSplaycast};
+pub use splaycast_template::SplaycastTemplate;
+pub use subscribe_options::{ResumeAt, StartAt, SubscribeOptions};
+pub use subscribe_permit::{SubscribePermit, SubscribePermits};
+pub use subscriber_count_changes::SubscriberCountChanges;
+pub use subscription_mode::{Replay, SubscriptionMode, TipOnly};
+pub use termination_report::{TerminationReason, TerminationReport};
+#[cfg(feature = "tokio")]
+pub use throttled::Throttled;
+pub use timestamped::{Timestamped, TimestampedSender};
+#[cfg(feature = "bytes")]
+pub use vectored::VectoredReceiver;
+
+/// An Engine to spawn, and the Splaycast to subscribe with.
+type WrapParts<Upstream, Item, Policy> = (Engine<Upstream, Item, Policy>, Splaycast<Item>);
+
+/// What you send with, an Engine to spawn, and the Splaycast to subscribe with.
+type ChannelParts<Sender, Upstream, Item, Policy> =
+    (Sender, Engine<Upstream, Item, Policy>, Splaycast<Item>);
 
 /// Wrap a stream with a Splaycast - a broadcast channel for streams.
 ///
@@ -164,6 +303,195 @@ where
     Splaycast::new(upstream, buffer_policy)
 }
 
+/// Wrap a stream with a Splaycast whose receivers can all see `channel_metadata`.
+///
+/// This is [`wrap_with_policy`], plus metadata that describes the channel. Receivers read
+/// it with [`Receiver::channel_metadata`]. It is fixed for the life of the channel.
+/// ```
+/// # use std::sync::Arc;
+/// # use splaycast::{buffer_policy::BufferLengthPolicy, ChannelMetadata};
+/// let metadata = ChannelMetadata::new()
+///     .with_name("prices")
+///     .with_schema_id(7)
+///     .with_value(Arc::new("USD"));
+/// let (_engine, splaycast) = splaycast::wrap_with_metadata(
+///     futures::stream::pending::<u64>(),
+///     BufferLengthPolicy::new(16),
+///     metadata,
+/// );
+///
+/// let receiver = splaycast.subscribe();
+/// assert_eq!(Some("prices"), receiver.channel_metadata().name());
+/// assert_eq!(Some(&"USD"), receiver.channel_metadata().downcast_ref::<&str>());
+/// ```
+pub fn wrap_with_metadata<Item, Upstream>(
+    upstream: Upstream,
+    buffer_policy: impl BufferPolicy<Item>,
+    channel_metadata: ChannelMetadata,
+) -> (
+    Engine<Upstream, Item, impl BufferPolicy<Item>>,
+    Splaycast<Item>,
+)
+where
+    Item: Clone + Send + Unpin,
+    Upstream: futures::Stream<Item = Item> + Unpin,
+{
+    Splaycast::new_with_metadata(upstream, buffer_policy, channel_metadata)
+}
+
+/// Wrap a stream of [`Message`]s, like a [`Receiver`] of another splaycast, with a
+/// Splaycast that carries the upstream's lag and resets through.
+///
+/// Relaying a Receiver with [`wrap`] would publish its `Lagged` and `Reset` messages as
+/// data. Here, an upstream `Lagged { count }` skips `count` sequence ids, so this
+/// splaycast's Receivers yield a `Lagged` of the same size in the same place, and an
+/// upstream `Reset` starts a new epoch here too. Lag semantics hold end to end, through
+/// any number of hops.
+/// ```
+/// # use futures::StreamExt;
+/// # use splaycast::Message;
+/// # use splaycast::buffer_policy::BufferLengthPolicy;
+/// # tokio_test::block_on(async {
+/// let (sender, engine, origin) = splaycast::channel(16);
+/// tokio::spawn(engine);
+/// let (relay_engine, relay) =
+///     splaycast::wrap_relay(origin.subscribe(), BufferLengthPolicy::new(16));
+/// tokio::spawn(relay_engine);
+///
+/// let mut receiver = relay.subscribe();
+/// sender.send("hello").expect("room in the send buffer");
+/// assert_eq!(Some(Message::Entry { item: "hello" }), receiver.next().await);
+/// # })
+/// ```
+pub fn wrap_relay<Item, Upstream>(
+    upstream: Upstream,
+    buffer_policy: impl BufferPolicy<Item>,
+) -> WrapParts<Relay<Upstream>, Item, impl BufferPolicy<Item>>
+where
+    Item: Clone + Send + Unpin,
+    Upstream: futures::Stream<Item = Message<Item>> + Unpin,
+{
+    let control = Arc::new(relay::RelayControl::default());
+    let (mut engine, splaycast) =
+        Splaycast::new(Relay::new(upstream, control.clone()), buffer_policy);
+    engine.set_relay(control);
+    (engine, splaycast)
+}
+
+/// Wrap a stream that isn't `Unpin` with a Splaycast, by pinning it in a Box.
+///
+/// This is for upstreams like `async_stream::stream!` generators and `async fn` streams,
+/// which are `!Unpin` and would otherwise need a `Box::pin` at the call site. Dropping
+/// the Engine drops the generator, which cancels it at its current `.await`.
+/// ```
+/// # use futures::StreamExt;
+/// # use splaycast::Message;
+/// # use splaycast::buffer_policy::BufferLengthPolicy;
+/// # tokio_test::block_on(async {
+/// let (sender, mut words) = tokio::sync::mpsc::unbounded_channel();
+/// let upstream = async_stream::stream! {
+///     while let Some(word) = words.recv().await {
+///         yield word;
+///     }
+/// };
+/// let (engine, splaycast) = splaycast::wrap_boxed(upstream, BufferLengthPolicy::new(16));
+/// let mut receiver = splaycast.subscribe();
+/// tokio::spawn(engine);
+///
+/// sender.send("hello").expect("the generator is running");
+/// assert_eq!(Some(Message::Entry { item: "hello" }), receiver.next().await);
+/// # })
+/// ```
+pub fn wrap_boxed<Item, Upstream>(
+    upstream: Upstream,
+    buffer_policy: impl BufferPolicy<Item>,
+) -> WrapParts<Pin<Box<Upstream>>, Item, impl BufferPolicy<Item>>
+where
+    Item: Clone + Send + Unpin,
+    Upstream: futures::Stream<Item = Item>,
+{
+    Splaycast::new(Box::pin(upstream), buffer_policy)
+}
+
+/// Wrap a stream with a Splaycast, and spawn its Engine on the current tokio runtime.
+///
+/// This is [`wrap_with_policy`] for the common case. The Engine runs until its upstream
+/// ends or the returned [`EngineHandle`] is dropped, whichever comes first. The upstream
+/// is pinned in a Box, so it doesn't need to be `Unpin`, as with [`wrap_boxed`].
+/// ```
+/// # use futures::StreamExt;
+/// # use splaycast::Message;
+/// # use splaycast::buffer_policy::BufferLengthPolicy;
+/// # tokio_test::block_on(async {
+/// let (splaycast, engine) = splaycast::spawn(
+///     futures::stream::iter(["hello"]).chain(futures::stream::pending()),
+///     BufferLengthPolicy::new(16),
+/// );
+///
+/// let mut receiver = splaycast.subscribe_from(1).expect("not sealed");
+/// assert_eq!(Some(Message::Entry { item: "hello" }), receiver.next().await);
+///
+/// drop(engine);
+/// assert_eq!(None, receiver.next().await);
+/// # })
+/// ```
+///
+/// This must be called on a tokio runtime.
+#[cfg(feature = "tokio")]
+pub fn spawn<Item, Upstream, Policy>(
+    upstream: Upstream,
+    buffer_policy: Policy,
+) -> (Splaycast<Item>, EngineHandle)
+where
+    Item: Clone + Send + Sync + Unpin + 'static,
+    Upstream: futures::Stream<Item = Item> + Send + 'static,
+    Policy: BufferPolicy<Item> + Send + 'static,
+{
+    let (engine, splaycast) = wrap_boxed(upstream, buffer_policy);
+    (splaycast, EngineHandle::new(tokio::spawn(engine)))
+}
+
+/// Wrap a stream with a Splaycast whose buffer starts out holding `initial`.
+///
+/// The initial items get sequence ids 1 through `initial.len()`, and the upstream's
+/// items follow on directly, so there is no gap between preloaded history and the live
+/// stream. Use this to warm up the buffer, e.g., with history loaded from a database,
+/// so that receivers from [`Splaycast::subscribe_from`] or [`Splaycast::subscribe_at_tail`]
+/// can replay it. The buffer policy applies to the initial items like any others.
+/// ```
+/// # use futures::StreamExt;
+/// # use splaycast::Message;
+/// # use splaycast::buffer_policy::BufferLengthPolicy;
+/// # tokio_test::block_on(async {
+/// let (engine, splaycast) = splaycast::wrap_with_initial(
+///     futures::stream::pending(),
+///     vec!["older", "old"],
+///     BufferLengthPolicy::new(16),
+/// );
+/// tokio::spawn(engine);
+///
+/// let mut receiver = splaycast.subscribe_from(1).expect("not sealed");
+/// assert_eq!(Some(Message::Entry { item: "older" }), receiver.next().await);
+/// assert_eq!(Some(Message::Entry { item: "old" }), receiver.next().await);
+/// # })
+/// ```
+pub fn wrap_with_initial<Item, Upstream>(
+    upstream: Upstream,
+    initial: Vec<Item>,
+    buffer_policy: impl BufferPolicy<Item>,
+) -> (
+    Engine<Upstream, Item, impl BufferPolicy<Item>>,
+    Splaycast<Item>,
+)
+where
+    Item: Clone + Send + Unpin,
+    Upstream: futures::Stream<Item = Item> + Unpin,
+{
+    let (mut engine, splaycast) = Splaycast::new(upstream, buffer_policy);
+    engine.preload(initial);
+    (engine, splaycast)
+}
+
 /// Get a channel to splay out to streaming receivers.
 ///
 /// A channel has send(item), while a wrap(upstream)'d splaycast has no
@@ -188,11 +516,7 @@ where
 /// ```
 pub fn channel<Item>(
     buffer_length: usize,
-) -> (
-    Sender<Item>,
-    Engine<SenderStream<Item>, Item, impl BufferPolicy<Item>>,
-    Splaycast<Item>,
-)
+) -> ChannelParts<Sender<Item>, SenderStream<Item>, Item, impl BufferPolicy<Item>>
 where
     Item: Clone + Send + Unpin,
 {
@@ -249,11 +573,7 @@ where
 pub fn channel_with_policy<Item>(
     send_buffer_length: usize,
     buffer_policy: impl BufferPolicy<Item>,
-) -> (
-    Sender<Item>,
-    Engine<SenderStream<Item>, Item, impl BufferPolicy<Item>>,
-    Splaycast<Item>,
-)
+) -> ChannelParts<Sender<Item>, SenderStream<Item>, Item, impl BufferPolicy<Item>>
 where
     Item: Clone + Send + Unpin,
 {
@@ -262,14 +582,183 @@ where
     (sender, engine, splaycast)
 }
 
+/// Get a channel whose Sender never runs out of room.
+///
+/// This is for low-rate channels, like a control plane, where you would rather not handle
+/// a full send buffer. `send` only fails once the channel is closed. The send buffer grows
+/// as needed, so the buffer policy alone controls what is retained for receivers.
+///
+/// ```
+/// # use futures::StreamExt;
+/// # use splaycast::Message;
+/// # use splaycast::buffer_policy::BufferLengthPolicy;
+/// # tokio_test::block_on(async {
+/// let (sender, engine, splaycast) = splaycast::unbounded_channel(BufferLengthPolicy::new(16));
+/// tokio::spawn(engine);
+///
+/// let mut receiver = splaycast.subscribe();
+/// sender.send("hello").expect("unbounded senders are never full");
+///
+/// let hello = receiver.next().await;
+/// assert_eq!(Some(Message::Entry { item: "hello" }), hello);
+/// # })
+/// ```
+pub fn unbounded_channel<Item>(
+    buffer_policy: impl BufferPolicy<Item>,
+) -> ChannelParts<Sender<Item>, SenderStream<Item>, Item, impl BufferPolicy<Item>>
+where
+    Item: Clone + Send + Unpin,
+{
+    let (sender, stream) = Sender::new_unbounded();
+    let (engine, splaycast) = Splaycast::new(stream, buffer_policy);
+    (sender, engine, splaycast)
+}
+
+/// Get a channel that needs no Engine task while it has few subscribers.
+///
+/// Each send fans out right away, on the sending thread: the Engine's work happens inside
+/// [`Sender::send`]. For services with thousands of tiny channels, this saves a task per
+/// channel. Once there are `promote_at` subscribers, the next send hands the Engine to
+/// `spawn`, and the channel works like any other from then on. It is never demoted.
+///
+/// Because sends do the Engine's work, a send costs more while the channel is inline, and
+/// with more subscribers, more so.
+/// ```
+/// # use futures::StreamExt;
+/// # use splaycast::Message;
+/// # use splaycast::buffer_policy::BufferLengthPolicy;
+/// # tokio_test::block_on(async {
+/// let (sender, splaycast) = splaycast::inline_channel(
+///     16,
+///     BufferLengthPolicy::new(16),
+///     8,
+///     |engine| {
+///         tokio::spawn(engine);
+///     },
+/// );
+///
+/// let mut receiver = splaycast.subscribe();
+/// sender.send("hello").expect("room in the send buffer");
+///
+/// let hello = receiver.next().await;
+/// assert_eq!(Some(Message::Entry { item: "hello" }), hello);
+/// # })
+/// ```
+pub fn inline_channel<Item, Policy>(
+    send_buffer_length: usize,
+    buffer_policy: Policy,
+    promote_at: usize,
+    spawn: impl FnOnce(BoxFuture<'static, ()>) + Send + 'static,
+) -> (Sender<Item>, Splaycast<Item>)
+where
+    Item: Clone + Send + Sync + Unpin + 'static,
+    Policy: BufferPolicy<Item> + Send + 'static,
+{
+    let (sender, stream) = Sender::new(send_buffer_length);
+    let (engine, splaycast) = Splaycast::new(stream, buffer_policy);
+    InlineDriver::start(engine, promote_at, Box::new(spawn));
+    (sender, splaycast)
+}
+
+/// Get a channel whose items are stamped with the instant they were sent.
+///
+/// Entries are retained up to `buffer_length`, and are popped once they are older
+/// than `age_limit`. This is a [`BufferAgePolicy`] out of the box, without writing
+/// your own timestamp wrapper type.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use futures::StreamExt;
+/// # use splaycast::Message;
+/// # tokio_test::block_on(async {
+/// let (sender, engine, splaycast) = splaycast::channel_timestamped(128, Duration::from_secs(5));
+/// tokio::spawn(engine);
+///
+/// let mut receiver = splaycast.subscribe();
+/// sender.send("hello");
+///
+/// let Some(Message::Entry { item }) = receiver.next().await else {
+///     panic!("expected an entry");
+/// };
+/// assert_eq!("hello", item.item);
+/// assert!(item.timestamp.elapsed() < Duration::from_secs(5));
+/// # })
+/// ```
+pub fn channel_timestamped<Item>(
+    buffer_length: usize,
+    age_limit: Duration,
+) -> ChannelParts<
+    TimestampedSender<Item>,
+    SenderStream<Timestamped<Item>>,
+    Timestamped<Item>,
+    impl BufferPolicy<Timestamped<Item>>,
+>
+where
+    Item: Clone + Send + Unpin,
+{
+    let (sender, stream) = Sender::new(buffer_length);
+    let policy = BufferLengthPolicy::new(buffer_length).wrap(BufferAgePolicy::new(
+        age_limit,
+        |item: &Timestamped<Item>| item.timestamp,
+    ));
+    let (engine, splaycast) = Splaycast::new(stream, policy);
+    (TimestampedSender::new(sender), engine, splaycast)
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct SplaycastEntry<T> {
     pub id: u64,
+    /// Sequence ids start over in each epoch.
+    pub epoch: u64,
     pub item: T,
+    /// Packed, since there is one of these per buffered item.
+    pub flags: u8,
+    /// From the Engine's metadata hook, if it has one.
+    pub metadata: Option<Arc<EntryMetadata>>,
 }
 
 impl<T> SplaycastEntry<T> {
+    /// Pinned entries are skipped over by the buffer policy.
+    pub const PINNED: u8 = 1;
+    /// Deferred entries have been popped as far as the buffer policy is concerned, but
+    /// they remain visible until the next buffer swap.
+    pub const DEFERRED: u8 = 1 << 1;
+    /// The summary entry holds what the buffer policy folded from popped entries. It is
+    /// never offered to the buffer policy, and Receivers that already read some of those
+    /// entries skip it.
+    pub const SUMMARY: u8 = 1 << 2;
+
     pub fn id(&self) -> u64 {
         self.id
     }
+
+    pub fn is_pinned(&self) -> bool {
+        self.flags & Self::PINNED != 0
+    }
+
+    pub fn set_pinned(&mut self, pinned: bool) {
+        if pinned {
+            self.flags |= Self::PINNED;
+        } else {
+            self.flags &= !Self::PINNED;
+        }
+    }
+
+    pub fn is_deferred(&self) -> bool {
+        self.flags & Self::DEFERRED != 0
+    }
+
+    pub fn defer(&mut self) {
+        self.flags |= Self::DEFERRED;
+    }
+
+    pub fn is_summary(&self) -> bool {
+        self.flags & Self::SUMMARY != 0
+    }
 }
+
+// There is one entry per buffered item. Beyond the id and the item, an entry carries its
+// epoch, a pointer for its metadata and a byte of flags, padded to a word: 24 bytes.
+const _: () = assert!(std::mem::size_of::<SplaycastEntry<u64>>() <= 16 + 24);
+const _: () =
+    assert!(std::mem::size_of::<Option<Arc<EntryMetadata>>>() == std::mem::size_of::<usize>());
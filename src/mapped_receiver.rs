@@ -0,0 +1,56 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{Message, Receiver};
+
+/// A Receiver that projects each entry through a function instead of cloning it, from
+/// [`crate::Splaycast::subscribe_map`].
+///
+/// This is for subscribers that only need a small or owned part of a large item. The
+/// function sees a reference to the entry in the shared buffer, so the whole item is
+/// never cloned for this subscriber. Lag and resets are reported just like a [`Receiver`].
+pub struct MappedReceiver<Item, F>
+where
+    Item: Clone,
+{
+    receiver: Receiver<Item>,
+    map: F,
+}
+
+impl<Item, F> std::fmt::Debug for MappedReceiver<Item, F>
+where
+    Item: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedReceiver")
+            .field("receiver", &self.receiver)
+            .finish()
+    }
+}
+
+impl<Item, F> MappedReceiver<Item, F>
+where
+    Item: Clone,
+{
+    pub(crate) fn new(receiver: Receiver<Item>, map: F) -> Self {
+        Self { receiver, map }
+    }
+}
+
+/// Safety: I don't use unsafe for this type
+impl<Item, F> Unpin for MappedReceiver<Item, F> where Item: Clone {}
+
+impl<Item, U, F> futures::Stream for MappedReceiver<Item, F>
+where
+    Item: Clone,
+    F: FnMut(&Item) -> U,
+{
+    type Item = Message<U>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Self { receiver, map } = &mut *self;
+        receiver.poll_map(context, map)
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::{Arc, Weak};
+
+use crate::{
+    shared::{AbandonedBacklog, ReceiverCursor, Shared, StatsHandle, SubscriberCountHandle},
+    snapshot::Snapshot,
+};
+
+/// A read-only view of a splaycast, for observability tooling.
+///
+/// A mirror can inspect the splaycast, but it cannot subscribe or otherwise affect the
+/// channel. It does not keep the channel alive, and dropping it does not terminate the
+/// channel. You can hand these out to a central observability service to enumerate and
+/// inspect all of the splaycasts in your process.
+///
+/// Once the splaycast is gone, a mirror reports an empty channel: no subscribers, an empty
+/// buffer and zeroed totals, and handles that return None.
+pub struct SplaycastMirror<Item>
+where
+    Item: Clone,
+{
+    shared: Weak<Shared<Item>>,
+}
+
+impl<Item> std::fmt::Debug for SplaycastMirror<Item>
+where
+    Item: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SplaycastMirror")
+            .field("shared", &self.shared)
+            .finish()
+    }
+}
+
+impl<Item> Clone for SplaycastMirror<Item>
+where
+    Item: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<Item> SplaycastMirror<Item>
+where
+    Item: Clone,
+{
+    pub(crate) fn new(shared: &Arc<Shared<Item>>) -> Self {
+        Self {
+            shared: Arc::downgrade(shared),
+        }
+    }
+
+    /// See [`crate::Splaycast::subscriber_count`].
+    pub fn subscriber_count(&self) -> usize {
+        self.shared
+            .upgrade()
+            .map_or(0, |shared| shared.subscriber_count())
+    }
+
+    /// See [`crate::Splaycast::subscriber_count_handle`].
+    pub fn subscriber_count_handle(&self) -> SubscriberCountHandle {
+        self.shared
+            .upgrade()
+            .map_or_else(SubscriberCountHandle::dangling, |shared| {
+                shared.subscriber_count_handle()
+            })
+    }
+
+    /// See [`crate::Splaycast::stats_handle`].
+    pub fn stats_handle(&self) -> StatsHandle {
+        self.shared
+            .upgrade()
+            .map_or_else(StatsHandle::dangling, |shared| shared.stats_handle())
+    }
+
+    /// See [`crate::Splaycast::buffer_len`].
+    pub fn buffer_len(&self) -> usize {
+        self.shared
+            .upgrade()
+            .map_or(0, |shared| shared.buffer_len())
+    }
+
+    /// See [`crate::Splaycast::buffer_sequence_range`].
+    pub fn buffer_sequence_range(&self) -> Option<(u64, u64)> {
+        self.shared.upgrade()?.buffer_sequence_range()
+    }
+
+    /// See [`crate::Splaycast::receiver_cursors`].
+    pub fn receiver_cursors(&self) -> Vec<ReceiverCursor> {
+        self.shared
+            .upgrade()
+            .map(|shared| shared.receiver_cursors())
+            .unwrap_or_default()
+    }
+
+    /// See [`crate::Splaycast::abandoned_backlog`].
+    pub fn abandoned_backlog(&self) -> AbandonedBacklog {
+        self.shared
+            .upgrade()
+            .map(|shared| shared.abandoned_backlog())
+            .unwrap_or_default()
+    }
+
+    /// See [`crate::Splaycast::poisoned_receivers`].
+    pub fn poisoned_receivers(&self) -> u64 {
+        self.shared
+            .upgrade()
+            .map_or(0, |shared| shared.poisoned_receivers())
+    }
+
+    /// See [`crate::Splaycast::snapshot`].
+    pub fn snapshot(&self) -> Snapshot<Item> {
+        let queue = self
+            .shared
+            .upgrade()
+            .map(|shared| shared.load_queue_full())
+            .unwrap_or_default();
+        Snapshot::new(queue)
+    }
+
+    #[cfg(feature = "registry")]
+    pub(crate) fn downgrade_shared(&self) -> Weak<Shared<Item>> {
+        self.shared.clone()
+    }
+}
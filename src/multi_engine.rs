@@ -0,0 +1,223 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use crate::sync::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+use crossbeam_queue::SegQueue;
+use futures::task::{waker, ArcWake, AtomicWaker};
+
+use crate::Engine;
+
+type BoxedEngine = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// What the MultiEngine task shares with its handles and its engines' wakers.
+#[derive(Default)]
+struct MultiEngineState {
+    // sync(Relaxed): a unique id counter; nothing is published through it.
+    next_id: AtomicU64,
+    added: SegQueue<(u64, BoxedEngine)>,
+    removed: SegQueue<u64>,
+    /// Which engines have been woken since they were last polled.
+    ready: SegQueue<u64>,
+    // sync(Release, AcqRel, Acquire): handles count themselves in and out with AcqRel, and
+    // the task Acquires the count before it decides that nobody can add an engine.
+    handles: AtomicUsize,
+    task: AtomicWaker,
+}
+
+struct EngineWaker {
+    id: u64,
+    // sync(AcqRel, Release): a wake swaps it to queue the engine at most once. The task
+    // stores it clear with Release before polling, so a later wake queues it again.
+    queued: AtomicBool,
+    state: Arc<MultiEngineState>,
+}
+
+impl EngineWaker {
+    /// Put the engine on the ready queue, unless it is already there.
+    fn enqueue(&self) {
+        if !self.queued.swap(true, Ordering::AcqRel) {
+            self.state.ready.push(self.id);
+        }
+    }
+}
+
+impl ArcWake for EngineWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.enqueue();
+        arc_self.state.task.wake();
+    }
+}
+
+struct Member {
+    engine: BoxedEngine,
+    wake: Arc<EngineWaker>,
+    waker: Waker,
+}
+
+/// One task that drives many Engines, for services with thousands of topics where a task
+/// per splaycast is the dominant overhead.
+///
+/// Spawn the MultiEngine once, and add and remove Engines through its
+/// [`MultiEngineHandle`]. Each Engine gets its own waker, so only Engines that have work
+/// are polled. They are polled round-robin, each at most once per pass, and the
+/// MultiEngine yields to the runtime between passes, so a busy splaycast cannot starve the
+/// others.
+///
+/// The MultiEngine completes once every handle is dropped and every Engine has completed.
+pub struct MultiEngine {
+    members: HashMap<u64, Member>,
+    state: Arc<MultiEngineState>,
+}
+
+impl std::fmt::Debug for MultiEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultiEngine")
+            .field("engines", &self.members.len())
+            .field("ready", &self.state.ready.len())
+            .finish()
+    }
+}
+
+impl MultiEngine {
+    /// A MultiEngine with no Engines yet, and the handle for adding them.
+    pub fn new() -> (Self, MultiEngineHandle) {
+        let state = Arc::new(MultiEngineState::default());
+        state.handles.store(1, Ordering::Release);
+        (
+            Self {
+                members: HashMap::new(),
+                state: state.clone(),
+            },
+            MultiEngineHandle { state },
+        )
+    }
+
+    fn take_changes(&mut self) {
+        while let Some((id, engine)) = self.state.added.pop() {
+            let wake = Arc::new(EngineWaker {
+                id,
+                queued: AtomicBool::new(false),
+                state: self.state.clone(),
+            });
+            // A new engine has not been polled yet, so it starts out ready.
+            wake.enqueue();
+            self.members.insert(
+                id,
+                Member {
+                    engine,
+                    waker: waker(wake.clone()),
+                    wake,
+                },
+            );
+        }
+        // After adding, so an engine removed right after it was added is found.
+        while let Some(id) = self.state.removed.pop() {
+            if self.members.remove(&id).is_some() {
+                log::debug!("removed engine {id}");
+            }
+        }
+    }
+}
+
+/// Safety: I don't use unsafe for this type
+impl Unpin for MultiEngine {}
+
+impl Future for MultiEngine {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        self.state.task.register(context.waker());
+        self.take_changes();
+
+        // Each engine that is ready now gets one poll. Engines that wake themselves during
+        // this pass go to the back of the line for the next one.
+        for _ in 0..self.state.ready.len() {
+            let Some(id) = self.state.ready.pop() else {
+                break;
+            };
+            let Some(member) = self.members.get_mut(&id) else {
+                continue; // Removed since it was woken.
+            };
+            // Clear the flag before polling, so a wake during the poll queues it again.
+            member.wake.queued.store(false, Ordering::Release);
+            let waker = member.waker.clone();
+            if member
+                .engine
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_ready()
+            {
+                log::debug!("engine {id} completed");
+                self.members.remove(&id);
+            }
+        }
+        if !self.state.ready.is_empty() {
+            context.waker().wake_by_ref();
+        }
+
+        if self.members.is_empty()
+            && self.state.added.is_empty()
+            && self.state.handles.load(Ordering::Acquire) == 0
+        {
+            log::debug!("no engines and no handles left");
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
+
+/// Adds Engines to, and removes them from, a running [`MultiEngine`].
+pub struct MultiEngineHandle {
+    state: Arc<MultiEngineState>,
+}
+
+impl std::fmt::Debug for MultiEngineHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultiEngineHandle").finish()
+    }
+}
+
+impl MultiEngineHandle {
+    /// Drive `engine` on the MultiEngine's task, instead of spawning it. Returns an id for
+    /// [`MultiEngineHandle::remove`].
+    pub fn add<Upstream, Item, Policy>(&self, engine: Engine<Upstream, Item, Policy>) -> u64
+    where
+        Item: Clone,
+        Engine<Upstream, Item, Policy>: Future<Output = ()> + Send + 'static,
+    {
+        let id = self.state.next_id.fetch_add(1, Ordering::Relaxed);
+        log::debug!("adding engine {id}");
+        self.state.added.push((id, Box::pin(engine)));
+        self.state.task.wake();
+        id
+    }
+
+    /// Drop the Engine with this id, which terminates its splaycast just like dropping a
+    /// spawned Engine does.
+    pub fn remove(&self, id: u64) {
+        self.state.removed.push(id);
+        self.state.task.wake();
+    }
+}
+
+impl Clone for MultiEngineHandle {
+    fn clone(&self) -> Self {
+        self.state.handles.fetch_add(1, Ordering::AcqRel);
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl Drop for MultiEngineHandle {
+    fn drop(&mut self) {
+        self.state.handles.fetch_sub(1, Ordering::AcqRel);
+        self.state.task.wake();
+    }
+}
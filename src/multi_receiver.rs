@@ -0,0 +1,200 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use crate::sync::{AtomicBool, Ordering};
+
+use crossbeam_queue::SegQueue;
+use futures::{
+    task::{waker, ArcWake, AtomicWaker},
+    Stream,
+};
+
+use crate::{Message, Receiver};
+
+/// Which members have something to look at, shared with the members' wakers.
+#[derive(Default)]
+struct ReadyState {
+    ready: SegQueue<usize>,
+    task: AtomicWaker,
+}
+
+struct MemberWaker {
+    index: usize,
+    // sync(AcqRel, Release): a wake swaps it to queue the member at most once. The stream
+    // stores it clear with Release before polling, so a later wake queues it again.
+    queued: AtomicBool,
+    state: Arc<ReadyState>,
+}
+
+impl MemberWaker {
+    /// Put the member on the ready queue, unless it is already there.
+    fn enqueue(&self) {
+        if !self.queued.swap(true, Ordering::AcqRel) {
+            self.state.ready.push(self.index);
+        }
+    }
+}
+
+impl ArcWake for MemberWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.enqueue();
+        arc_self.state.task.wake();
+    }
+}
+
+struct Member<Item>
+where
+    Item: Clone,
+{
+    receiver: Receiver<Item>,
+    wake: Arc<MemberWaker>,
+    waker: Waker,
+}
+
+/// A Stream over many Receivers, for a task that consumes dozens or hundreds of splaycasts.
+///
+/// Each Receiver gets its own waker, so when an Engine publishes, only that Receiver is
+/// marked ready. Ready Receivers are polled round-robin, and idle ones are not polled at
+/// all. `futures::stream::select_all` re-polls every stream each time any of them wakes
+/// the task, which adds up with many mostly-idle subscriptions.
+///
+/// This yields `(index, message)`, where `index` is what [`MultiReceiver::push`] returned
+/// for the Receiver. A Receiver is removed when its splaycast terminates, and the stream
+/// ends once no Receivers are left.
+pub struct MultiReceiver<Item>
+where
+    Item: Clone,
+{
+    members: Vec<Option<Member<Item>>>,
+    live: usize,
+    state: Arc<ReadyState>,
+}
+
+impl<Item> std::fmt::Debug for MultiReceiver<Item>
+where
+    Item: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MultiReceiver")
+            .field("len", &self.live)
+            .field("ready", &self.state.ready.len())
+            .finish()
+    }
+}
+
+impl<Item> Default for MultiReceiver<Item>
+where
+    Item: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Item> FromIterator<Receiver<Item>> for MultiReceiver<Item>
+where
+    Item: Clone,
+{
+    fn from_iter<T: IntoIterator<Item = Receiver<Item>>>(receivers: T) -> Self {
+        let mut multi = Self::new();
+        receivers.into_iter().for_each(|receiver| {
+            multi.push(receiver);
+        });
+        multi
+    }
+}
+
+impl<Item> MultiReceiver<Item>
+where
+    Item: Clone,
+{
+    /// An empty MultiReceiver.
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            live: 0,
+            state: Default::default(),
+        }
+    }
+
+    /// Add a Receiver. Its messages are yielded with the returned index.
+    pub fn push(&mut self, receiver: Receiver<Item>) -> usize {
+        let index = self.members.len();
+        let wake = Arc::new(MemberWaker {
+            index,
+            queued: AtomicBool::new(false),
+            state: self.state.clone(),
+        });
+        // A new member has not been polled yet, so it starts out ready.
+        wake.enqueue();
+        self.members.push(Some(Member {
+            receiver,
+            waker: waker(wake.clone()),
+            wake,
+        }));
+        self.live += 1;
+        self.state.task.wake();
+        index
+    }
+
+    /// Remove the Receiver at `index`, if it is still here.
+    pub fn remove(&mut self, index: usize) -> Option<Receiver<Item>> {
+        let member = self.members.get_mut(index)?.take()?;
+        self.live -= 1;
+        Some(member.receiver)
+    }
+
+    /// How many Receivers are in this MultiReceiver.
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    /// Whether this MultiReceiver has no Receivers left.
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+}
+
+impl<Item> Stream for MultiReceiver<Item>
+where
+    Item: Clone,
+{
+    type Item = (usize, Message<Item>);
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.live == 0 {
+            return Poll::Ready(None);
+        }
+        self.state.task.register(context.waker());
+
+        while let Some(index) = self.state.ready.pop() {
+            let Some(Some(member)) = self.members.get_mut(index) else {
+                continue; // Removed since it was woken.
+            };
+            // Clear the flag before polling, so a wake during the poll queues it again.
+            member.wake.queued.store(false, Ordering::Release);
+            let next =
+                Pin::new(&mut member.receiver).poll_next(&mut Context::from_waker(&member.waker));
+            match next {
+                Poll::Ready(Some(message)) => {
+                    // There may be more, but go to the back of the line for fairness.
+                    member.wake.enqueue();
+                    return Poll::Ready(Some((index, message)));
+                }
+                Poll::Ready(None) => {
+                    log::trace!("member {index} terminated");
+                    self.members[index] = None;
+                    self.live -= 1;
+                    if self.live == 0 {
+                        return Poll::Ready(None);
+                    }
+                }
+                Poll::Pending => (),
+            }
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use crate::{
+    receiver::find,
+    shared::{Shared, WakeHandle},
+};
+
+/// A one-shot Future that resolves with the first upcoming entry that matches a predicate.
+///
+/// This is cheaper than a [`crate::Receiver`] for "wait until X shows up" coordination.
+/// It does not count as a subscriber, it never yields lag, and it only clones the one
+/// entry that it resolves with. While it waits, it is parked with the Engine exactly
+/// like a caught-up Receiver, so it is only woken when new entries arrive.
+///
+/// Entries that fall off the buffer before this future gets to inspect them are skipped.
+/// If the splaycast terminates first, this resolves with `None`.
+pub struct NextMatching<Item, F>
+where
+    Item: Clone,
+{
+    id: u64,
+    shared: Arc<Shared<Item>>,
+    next_message_id: u64,
+    epoch: u64,
+    predicate: F,
+}
+
+impl<Item, F> std::fmt::Debug for NextMatching<Item, F>
+where
+    Item: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NextMatching")
+            .field("id", &self.id)
+            .field("next", &self.next_message_id)
+            .finish()
+    }
+}
+
+impl<Item, F> NextMatching<Item, F>
+where
+    Item: Clone,
+    F: FnMut(&Item) -> bool,
+{
+    pub(crate) fn new(id: u64, shared: Arc<Shared<Item>>, predicate: F) -> Self {
+        Self {
+            id,
+            next_message_id: shared.subscribe_sequence_number(),
+            epoch: shared.epoch(),
+            shared,
+            predicate,
+        }
+    }
+}
+
+/// Safety: I don't use unsafe for this type
+impl<Item, F> Unpin for NextMatching<Item, F> where Item: Clone {}
+
+impl<Item, F> futures::Future for NextMatching<Item, F>
+where
+    Item: Clone,
+    F: FnMut(&Item) -> bool,
+{
+    type Output = Option<Item>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        log::trace!("poll {self:?}");
+        if self.shared.is_dead() {
+            return Poll::Ready(None);
+        }
+
+        let shared_queue_snapshot = self.shared.load_queue();
+        let epoch = match shared_queue_snapshot.front() {
+            Some(front) => front.epoch,
+            None => self.shared.epoch(),
+        };
+        if epoch != self.epoch {
+            // Everything in a new epoch is upcoming, as far as this future is concerned.
+            self.epoch = epoch;
+            self.next_message_id = 1;
+        }
+        let (Ok(start) | Err(start)) = find(self.next_message_id, &shared_queue_snapshot);
+
+        let Self {
+            next_message_id,
+            predicate,
+            ..
+        } = &mut *self;
+        for entry in shared_queue_snapshot.range(start..) {
+            *next_message_id = entry.id + 1;
+            if predicate(&entry.item) {
+                log::trace!("matched at {}", entry.id);
+                return Poll::Ready(Some(entry.item.clone()));
+            }
+        }
+
+        // Nothing matched yet - park with the Engine until there's something new to look at.
+        self.shared.register_waker(
+            self.id,
+            WakeHandle::new(self.next_message_id, context.waker().clone()),
+        );
+        Poll::Pending
+    }
+}
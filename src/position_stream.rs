@@ -0,0 +1,64 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use futures::{stream::FusedStream, Stream};
+
+use crate::publish_confirmations::ConfirmationSlot;
+
+/// A stream of `(sequence_id, instant)` as a Receiver advances, from
+/// [`crate::Receiver::position_stream`].
+///
+/// `sequence_id` is the newest entry the Receiver has moved past, whether it yielded it or
+/// skipped it in a lag. That is what you want for client acknowledgements and progress
+/// bars: a lag jump is one step, not a count of entries. Positions are coalesced like
+/// [`crate::PublishConfirmations`]: if the Receiver moves several times before you poll,
+/// you only see the latest. Entries the Receiver has prefetched don't count until they
+/// are yielded.
+///
+/// Sequence ids start over from 1 after [`crate::Splaycast::reset_sequence`].
+///
+/// The stream ends when the Receiver is dropped or expires.
+pub struct PositionStream {
+    slot: Arc<ConfirmationSlot>,
+    terminated: bool,
+}
+
+impl std::fmt::Debug for PositionStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PositionStream")
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+impl PositionStream {
+    pub(crate) fn new(slot: Arc<ConfirmationSlot>) -> Self {
+        Self {
+            slot,
+            terminated: false,
+        }
+    }
+}
+
+impl Stream for PositionStream {
+    type Item = (u64, Instant);
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        let next = self.slot.poll_latest(context);
+        self.terminated = matches!(next, Poll::Ready(None));
+        next
+    }
+}
+
+impl FusedStream for PositionStream {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
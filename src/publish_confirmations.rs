@@ -0,0 +1,109 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use crate::sync::{AtomicBool, Ordering};
+
+use arc_swap::ArcSwapOption;
+use futures::{stream::FusedStream, task::AtomicWaker, Stream};
+
+/// The producing side of a [`PublishConfirmations`] or [`crate::PositionStream`]. It holds
+/// only the latest confirmation, so a slow reader sees coalesced progress rather than a
+/// growing backlog.
+#[derive(Default)]
+pub(crate) struct ConfirmationSlot {
+    latest: ArcSwapOption<(u64, Instant)>,
+    waker: AtomicWaker,
+    // sync(Release, Acquire): close stores it after the last confirmation, and the stream
+    // Acquires it before it reports the end.
+    closed: AtomicBool,
+}
+
+impl ConfirmationSlot {
+    pub fn confirm(&self, sequence_id: u64, published_at: Instant) {
+        self.latest
+            .store(Some(Arc::new((sequence_id, published_at))));
+        self.waker.wake();
+    }
+
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+
+    fn take(&self) -> Option<(u64, Instant)> {
+        self.latest.swap(None).map(|latest| *latest)
+    }
+
+    /// The latest confirmation, or None once the slot is closed and drained.
+    pub fn poll_latest(&self, context: &mut Context<'_>) -> Poll<Option<(u64, Instant)>> {
+        if let Some(latest) = self.take() {
+            return Poll::Ready(Some(latest));
+        }
+        self.waker.register(context.waker());
+        // The producer may have confirmed or closed before we registered, so check once more.
+        if let Some(latest) = self.take() {
+            return Poll::Ready(Some(latest));
+        }
+        if self.closed.load(Ordering::Acquire) {
+            return Poll::Ready(None);
+        }
+        Poll::Pending
+    }
+}
+
+/// A stream of `(sequence_id, publish_instant)` confirmations, from
+/// [`crate::Engine::publish_confirmations`].
+///
+/// Each confirmation means that every item up to and including `sequence_id` has been
+/// absorbed by the Engine and is visible to receivers. Confirmations are coalesced: if
+/// the Engine publishes several times before you poll, you only see the latest one. That
+/// is all you need for a windowed in-flight limit, e.g., "no more than 10k unconfirmed
+/// items".
+///
+/// Sequence ids start over from 1 after [`crate::Splaycast::reset_sequence`].
+///
+/// The stream ends when the Engine completes.
+pub struct PublishConfirmations {
+    slot: Arc<ConfirmationSlot>,
+    terminated: bool,
+}
+
+impl std::fmt::Debug for PublishConfirmations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PublishConfirmations")
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+impl PublishConfirmations {
+    pub(crate) fn new(slot: Arc<ConfirmationSlot>) -> Self {
+        Self {
+            slot,
+            terminated: false,
+        }
+    }
+}
+
+impl Stream for PublishConfirmations {
+    type Item = (u64, Instant);
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        let next = self.slot.poll_latest(context);
+        self.terminated = matches!(next, Poll::Ready(None));
+        next
+    }
+}
+
+impl FusedStream for PublishConfirmations {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
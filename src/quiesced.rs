@@ -0,0 +1,63 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use crate::shared::Shared;
+
+/// A Future that resolves once every subscriber has caught up, from
+/// [`crate::Splaycast::quiesced`].
+///
+/// It resolves with `true` once no subscriber has a backlog, or with `false` if the
+/// splaycast terminates first.
+pub struct Quiesced<Item>
+where
+    Item: Clone,
+{
+    shared: Arc<Shared<Item>>,
+}
+
+impl<Item> std::fmt::Debug for Quiesced<Item>
+where
+    Item: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Quiesced").finish_non_exhaustive()
+    }
+}
+
+impl<Item> Quiesced<Item>
+where
+    Item: Clone,
+{
+    pub(crate) fn new(shared: Arc<Shared<Item>>) -> Self {
+        Self { shared }
+    }
+
+    fn check(&self) -> Poll<bool> {
+        if self.shared.slowest_backlog() == 0 {
+            Poll::Ready(true)
+        } else if self.shared.is_dead() {
+            Poll::Ready(false)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<Item> futures::Future for Quiesced<Item>
+where
+    Item: Clone,
+{
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(quiesced) = self.check() {
+            return Poll::Ready(quiesced);
+        }
+        self.shared.register_position_waiter(context.waker());
+        // A receiver may have moved before we registered, so check once more.
+        self.check()
+    }
+}
@@ -1,15 +1,38 @@
 use std::{
     collections::VecDeque,
+    marker::PhantomData,
     pin::Pin,
     sync::Arc,
-    task::{Context, Poll},
+    task::{Context, Poll, Waker},
+    time::Instant,
 };
 
+use futures::StreamExt;
+
+#[cfg(feature = "tokio")]
+use std::future::Future;
+
 use crate::{
-    shared::{Shared, WakeHandle},
-    Message, SplaycastEntry,
+    publish_confirmations::ConfirmationSlot,
+    receiver_close::{CloseReason, ReceiverClosed},
+    shared::{ReceiverControl, Shared, WakeHandle},
+    subscription_mode::{Replay, SubscriptionMode},
+    ChannelMetadata, EntryMetadata, Message, PositionStream, ResumeAt, SplaycastEntry,
+    SubscribeError, SubscribePermit,
 };
 
+/// The outcome of [`Receiver::next_timeout`].
+#[cfg(feature = "tokio")]
+#[derive(Debug, PartialEq)]
+pub enum NextTimeout<T> {
+    /// The next message arrived in time.
+    Item(Message<T>),
+    /// Nothing arrived before the timeout.
+    Timeout,
+    /// The splaycast has terminated, and there is nothing left to receive.
+    Closed,
+}
+
 /// This is a cloned view of the upstream Stream you wrapped with a Splaycast.
 /// You receive [`crate::Message`]s on this stream. If you'd like to get back
 /// to your `Item` type, you can `.map()` this stream and handle `Message::Lagged`
@@ -27,143 +50,1031 @@ use crate::{
 /// For few Receivers, the `tokio::sync::broadcast` may outperform Splaycast. But as
 /// Receiver count grows and as publish queue depth grows, Splaycast more gracefully
 /// loads up.
-pub struct Receiver<Item>
+pub struct Receiver<Item, Mode = Replay>
 where
     Item: Clone,
+    Mode: SubscriptionMode,
 {
     id: u64,
     shared: Arc<Shared<Item>>,
     next_message_id: u64,
+    epoch: u64,
+    prefetch_limit: usize,
+    resume_at: ResumeAt,
+    /// Entries cloned ahead, with their ids: the filter may have skipped some in between.
+    prefetched: VecDeque<(u64, Item, Option<Arc<EntryMetadata>>)>,
+    metadata: Option<Arc<EntryMetadata>>,
+    filter: Option<Filter<Item>>,
+    control: Arc<ReceiverControl<Item>>,
+    mode: PhantomData<fn() -> Mode>,
+    /// For the [`PositionStream`], if there is one.
+    positions: Option<Arc<ConfirmationSlot>>,
+    /// The stream ends when this fires.
+    #[cfg(feature = "tokio")]
+    expiry: Option<Pin<Box<tokio::time::Sleep>>>,
+    /// Set once the Receiver has given up its place in the splaycast, before it is dropped.
+    released: bool,
+    /// Until it yields an entry of this epoch, a summary entry only covers entries this
+    /// Receiver never saw. After that, a summary would repeat some, so it is a `Lagged`.
+    takes_summary: bool,
+    /// While paused, the last poll's waker is kept here instead of registered for a wake.
+    paused: Option<Option<Waker>>,
+    label: Option<Arc<str>>,
 }
 
-impl<Item> std::fmt::Debug for Receiver<Item>
+/// Entries that don't pass the filter are skipped without being cloned.
+pub(crate) type Filter<Item> = Arc<dyn Fn(&Item) -> bool + Send + Sync>;
+
+impl<Item, Mode> std::fmt::Debug for Receiver<Item, Mode>
 where
     Item: Clone,
+    Mode: SubscriptionMode,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Receiver")
             .field("id", &self.id)
+            .field("label", &self.label)
             .field("next", &self.next_message_id)
+            .field("prefetched", &self.prefetched.len())
             .finish()
     }
 }
 
-impl<Item> Receiver<Item>
+impl<Item, Mode> Receiver<Item, Mode>
 where
     Item: Clone,
+    Mode: SubscriptionMode,
 {
-    pub(crate) fn new(id: u64, shared: Arc<Shared<Item>>) -> Self {
+    pub(crate) fn new_at(id: u64, shared: Arc<Shared<Item>>, next_message_id: u64) -> Self {
         shared.increment_subscriber_count();
-        Self {
+        let control = shared.register_receiver(id, next_message_id);
+        Self::with_control(id, shared, control, next_message_id)
+    }
+
+    /// A Receiver whose stream is already over, for a subscription that was refused. It
+    /// never counted as a subscriber, so it has nothing to give up.
+    pub(crate) fn refused(id: u64, shared: Arc<Shared<Item>>) -> Self {
+        let next_message_id = shared.subscribe_sequence_number();
+        let mut receiver = Self::with_control(
             id,
-            next_message_id: shared.subscribe_sequence_number(),
             shared,
-        }
+            Arc::new(ReceiverControl::default()),
+            next_message_id,
+        );
+        receiver.released = true;
+        receiver
     }
 
-    pub(crate) fn new_at_buffer_start(id: u64, shared: Arc<Shared<Item>>) -> Self {
-        shared.increment_subscriber_count();
+    fn with_control(
+        id: u64,
+        shared: Arc<Shared<Item>>,
+        control: Arc<ReceiverControl<Item>>,
+        next_message_id: u64,
+    ) -> Self {
         Self {
             id,
-            next_message_id: shared.subscribe_tail_sequence_number(),
+            control,
+            next_message_id,
+            epoch: shared.epoch(),
             shared,
+            prefetch_limit: 0,
+            resume_at: ResumeAt::Tail,
+            prefetched: VecDeque::new(),
+            metadata: None,
+            filter: None,
+            mode: PhantomData,
+            positions: None,
+            #[cfg(feature = "tokio")]
+            expiry: None,
+            released: false,
+            takes_summary: true,
+            paused: None,
+            label: None,
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn set_expiry(&mut self, deadline: Instant) {
+        let deadline = tokio::time::Instant::from_std(deadline);
+        self.expiry = Some(Box::pin(tokio::time::sleep_until(deadline)));
+        self.shared.expire_at(self.id, deadline);
+    }
+
+    pub(crate) fn set_permit(&mut self, permit: SubscribePermit) {
+        self.control.hold_permit(Arc::new(permit));
+    }
+
+    pub(crate) fn set_takes_summary(&mut self, takes_summary: bool) {
+        self.takes_summary = takes_summary;
+    }
+
+    pub(crate) fn set_filter(&mut self, filter: Filter<Item>) {
+        self.filter = Some(filter);
+    }
+
+    fn skips_summary(&self, entry: &SplaycastEntry<Item>) -> bool {
+        entry.is_summary() && !self.takes_summary
+    }
+
+    fn passes_filter(&self, item: &Item) -> bool {
+        self.filter.as_ref().is_none_or(|filter| filter(item))
+    }
+
+    /// Set the number of entries to clone ahead when this Receiver reads from the buffer.
+    ///
+    /// With a prefetch, each read from the shared buffer also clones up to `count` of the
+    /// following entries into a local queue, in the same snapshot pass. Subsequent polls
+    /// are served from the local queue without touching shared state, which helps consumers
+    /// with spiky processing costs. Prefetched entries are delivered even if the buffer
+    /// drops them in the meantime.
+    ///
+    /// `count` bounds the memory held by this Receiver. The default is 0: no prefetch.
+    /// Entries that were already prefetched are still delivered when the count shrinks.
+    pub fn set_prefetch(&mut self, count: usize) {
+        self.prefetch_limit = count;
+        self.prefetched
+            .reserve(count.saturating_sub(self.prefetched.len()));
+    }
+
+    /// Set where this Receiver picks up after it falls off the buffer. The default is
+    /// [`ResumeAt::Tail`], the oldest buffered entry. The `Lagged` count includes every
+    /// entry that is skipped.
+    pub fn set_resume_at(&mut self, resume_at: ResumeAt) {
+        self.resume_at = resume_at;
+    }
+
+    /// This Receiver's id, for acting on it through the splaycast, e.g., with
+    /// [`crate::Splaycast::disconnect`].
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Label this Receiver for diagnostics, e.g., with a connection id. The label is shown
+    /// next to the id in the Engine's trace logs, [`crate::Splaycast::receiver_cursors`]
+    /// and [`crate::LagEvent`]s, so you can tell which of many subscribers is slow.
+    pub fn set_label(&mut self, label: impl Into<Arc<str>>) {
+        let label = label.into();
+        self.control.set_label(label.clone());
+        self.label = Some(label);
+    }
+
+    /// This Receiver's label, from [`Receiver::set_label`].
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// How many times this Receiver has yielded a `Lagged`, over its life.
+    pub fn lag_events(&self) -> u64 {
+        self.control.lag_events()
+    }
+
+    /// Close this Receiver with a reason, instead of dropping it: the reason goes to the
+    /// [`crate::Engine::set_on_receiver_closed`] hook and the splaycast's
+    /// [`crate::Stats`], and the Engine drops this Receiver's parked waker on its next pass.
+    pub fn close(mut self, reason: CloseReason) {
+        match &self.label {
+            Some(label) => log::debug!("receiver {} ({label}) closed: {reason}", self.id),
+            None => log::debug!("receiver {} closed: {reason}", self.id),
+        }
+        let closed = ReceiverClosed {
+            receiver_id: self.id,
+            label: self.label.clone(),
+            reason,
+            entries_skipped: self.entries_skipped(),
+        };
+        self.release();
+        self.shared.record_close(closed);
+    }
+
+    /// How many entries this Receiver has skipped because it fell behind, over its life:
+    /// the sum of its `Lagged` counts. Entries it skipped with a filter aren't counted.
+    pub fn entries_skipped(&self) -> u64 {
+        self.control.entries_skipped()
+    }
+
+    /// Get a stream of `(sequence_id, instant)` as this Receiver advances, e.g., to send
+    /// client acknowledgements or drive a progress bar without wrapping the data path.
+    /// See [`PositionStream`].
+    ///
+    /// Calling this again replaces the previous stream, which then ends.
+    pub fn position_stream(&mut self) -> PositionStream {
+        let slot = Arc::new(ConfirmationSlot::default());
+        if let Some(previous) = self.positions.replace(slot.clone()) {
+            previous.close();
+        }
+        PositionStream::new(slot)
+    }
+
+    /// Get a new Receiver for the same splaycast, starting at the head, for code that only
+    /// has a Receiver in scope. Like `tokio::sync::broadcast::Receiver::resubscribe`, the
+    /// new Receiver starts fresh: it doesn't share this one's position, filter or prefetch.
+    ///
+    /// This fails once the splaycast is sealed or at its subscriber limit, just like
+    /// [`crate::Splaycast::try_subscribe`].
+    pub fn resubscribe(&self) -> Result<Self, SubscribeError> {
+        self.shared.check_admission(1)?;
+        Ok(Self::new_at(
+            self.shared.next_receiver_id(),
+            self.shared.clone(),
+            self.shared.subscribe_sequence_number(),
+        ))
+    }
+
+    /// Whether the splaycast this Receiver is subscribed to has terminated.
+    ///
+    /// Entries already prefetched are still yielded, but nothing new will arrive.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_dead()
+    }
+
+    /// Whether this Receiver was disconnected with [`crate::Splaycast::disconnect`].
+    ///
+    /// A disconnected Receiver's stream ends, just like when the splaycast terminates. Check
+    /// this after the end of the stream to tell the two apart.
+    pub fn is_disconnected(&self) -> bool {
+        self.control.is_disconnected()
+    }
+
+    /// Whether this Receiver's stream ended because its deadline passed, e.g., from
+    /// [`crate::Splaycast::subscribe_for`].
+    ///
+    /// An expired Receiver no longer counts as a subscriber, even before it is dropped.
+    /// Check this after the end of the stream to tell it apart from a termination.
+    #[cfg(feature = "tokio")]
+    pub fn is_expired(&self) -> bool {
+        // The Engine may have released it without a poll, so ask the control.
+        self.control.is_released()
+            && self
+                .expiry
+                .as_ref()
+                .is_some_and(|expiry| expiry.deadline() <= tokio::time::Instant::now())
+    }
+
+    /// The [`EntryMetadata`] of the entry this Receiver yielded most recently, if the
+    /// Engine's [metadata hook](crate::Engine::set_metadata_hook) attached any.
+    pub fn metadata(&self) -> Option<&EntryMetadata> {
+        self.metadata.as_deref()
+    }
+
+    /// The metadata of the channel this Receiver is subscribed to, from
+    /// [`crate::wrap_with_metadata`].
+    pub fn channel_metadata(&self) -> &ChannelMetadata {
+        self.shared.channel_metadata()
+    }
+
+    /// Fast-forward to the head, discarding the backlog: the next entry this Receiver
+    /// yields is the next one published. The skipped entries, including prefetched ones,
+    /// are not reported as a `Lagged`.
+    ///
+    /// This is for consumers that know they were paused and don't want what they missed.
+    pub fn skip_to_latest(&mut self) {
+        self.prefetched.clear();
+        self.epoch = self.shared.epoch();
+        self.next_message_id = self.shared.subscribe_sequence_number();
+        self.update_position();
+    }
+
+    /// Get a clone of the next entry without consuming it, e.g., to only take entries for
+    /// your shard. The next poll yields the same entry, unless it falls off the buffer first.
+    ///
+    /// This reads the shared buffer at this Receiver's cursor, skipping entries the
+    /// Receiver's filter rejects. None means the next message is not an entry that is
+    /// available right now: there is nothing new yet, the next message is a `Lagged` or a
+    /// `Reset`, an item sent with [`crate::Splaycast::send_to`] is waiting, or the stream
+    /// has ended.
+    pub fn peek(&self) -> Option<Item> {
+        if self.released || self.control.is_disconnected() || self.control.has_direct() {
+            return None;
+        }
+        if let Some((_, item, _)) = self.prefetched.front() {
+            return Some(item.clone());
+        }
+        if self.shared.is_dead() {
+            return None;
+        }
+        let shared_queue_snapshot = self.shared.load_queue();
+        let front = shared_queue_snapshot.front()?;
+        if front.epoch != self.epoch {
+            return None;
+        }
+        let mut next_message_id = self.next_message_id;
+        if Mode::LATEST_ONLY {
+            next_message_id = next_message_id.max(shared_queue_snapshot.back()?.id);
+        }
+        let index = find(next_message_id, &shared_queue_snapshot).ok()?;
+        shared_queue_snapshot
+            .range(index..)
+            .zip(next_message_id..)
+            // Stop at gaps and skipped summaries: the next message is a Lagged.
+            .take_while(|(entry, id)| entry.id == *id && !self.skips_summary(entry))
+            .map(|(entry, _)| &entry.item)
+            .find(|item| self.passes_filter(item))
+            .cloned()
+    }
+
+    /// How many entries are ready for this Receiver without waiting for the Engine: those
+    /// it has prefetched, plus the ones in the buffer ahead of it. Entries in the buffer
+    /// are only counted when they can't be filtered out, and a latest-only Receiver counts
+    /// at most 1 of them.
+    fn buffered_ahead(&self) -> usize {
+        if self.released || self.control.is_disconnected() {
+            return 0;
+        }
+        let prefetched = self.prefetched.len();
+        if self.filter.is_some() || self.shared.is_dead() {
+            return prefetched;
+        }
+        let shared_queue_snapshot = self.shared.load_queue();
+        let ahead = match shared_queue_snapshot.front() {
+            // Behind the buffer, the next message is a Lagged. What follows it depends on
+            // where the Receiver resumes.
+            Some(front) if front.epoch == self.epoch && self.next_message_id < front.id => 1,
+            Some(front) if front.epoch == self.epoch => {
+                let index = match find(self.next_message_id, &shared_queue_snapshot) {
+                    Ok(index) | Err(index) => index,
+                };
+                shared_queue_snapshot.len() - index
+            }
+            _ => 0,
+        };
+        prefetched
+            + if Mode::LATEST_ONLY {
+                ahead.min(1)
+            } else {
+                ahead
+            }
+    }
+
+    /// Whether this Receiver's stream is over: the splaycast has terminated, or this
+    /// Receiver was disconnected. Prefetched messages can still be drained after this.
+    pub fn is_terminated(&self) -> bool {
+        self.released || self.control.is_disconnected() || self.shared.is_dead()
+    }
+
+    /// How many entries this Receiver trails the newest entry in the buffer by, including
+    /// any it has prefetched, e.g., to warn a client before it lags.
+    ///
+    /// This is a snapshot of the shared buffer. It's 0 after a sequence reset until this
+    /// Receiver catches up to the new epoch.
+    pub fn behind_by(&self) -> u64 {
+        let shared_queue_snapshot = self.shared.load_queue();
+        match shared_queue_snapshot.back() {
+            Some(newest) if newest.epoch == self.epoch => {
+                (newest.id + 1).saturating_sub(self.next_message_id())
+            }
+            _ => 0,
+        }
+    }
+
+    /// Stop yielding messages, and stop being woken for them, until [`Receiver::resume`].
+    ///
+    /// This is for flow control, e.g., while a websocket client's window is closed: the
+    /// Receiver keeps its position instead of buffering in your task. While it is paused,
+    /// polls are Pending and don't register with the Engine, but the buffer policy keeps
+    /// running, so a long pause can still end in a `Lagged`. If the splaycast terminates
+    /// while this Receiver is paused, the next poll ends the stream.
+    pub fn pause(&mut self) {
+        if self.paused.is_none() {
+            self.paused = Some(None);
+        }
+    }
+
+    /// Pick up where a [`Receiver::pause`] left off. This wakes the task that last polled
+    /// the paused Receiver.
+    pub fn resume(&mut self) {
+        if let Some(Some(waker)) = self.paused.take() {
+            waker.wake();
+        }
+    }
+
+    /// Whether this Receiver is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// Take the next message if one is available right now, e.g., from a game loop tick.
+    ///
+    /// This never waits and never registers a waker, so nothing wakes you when more
+    /// arrives. None means there is nothing yet, or that the stream has ended: check
+    /// [`Receiver::is_closed`] to tell them apart.
+    pub fn try_next(&mut self) -> Option<Message<Item>> {
+        if self.released {
+            return None;
+        }
+        #[cfg(feature = "tokio")]
+        if self
+            .expiry
+            .as_ref()
+            .is_some_and(|expiry| expiry.is_elapsed())
+        {
+            log::trace!("expired");
+            self.release();
+            return None;
+        }
+        let next = self.poll_shared(None, &mut Item::clone);
+        self.update_position();
+        match next {
+            Poll::Ready(next) => next,
+            Poll::Pending => None,
+        }
+    }
+
+    /// Poll for up to `limit` messages at once, appending them to `buffer`. This is ready
+    /// with however many messages were available, as soon as there is at least one, so a
+    /// consumer can handle a backlog in one pass instead of one poll per message.
+    ///
+    /// A run of entries is read from the shared buffer in one pass, like a prefetch.
+    ///
+    /// Ready with 0 means the stream has ended, unless `limit` is 0.
+    pub fn poll_next_batch(
+        &mut self,
+        context: &mut Context<'_>,
+        buffer: &mut Vec<Message<Item>>,
+        limit: usize,
+    ) -> Poll<usize> {
+        let start = buffer.len();
+        let prefetch_limit = self.prefetch_limit;
+        self.prefetch_limit = prefetch_limit.max(limit.saturating_sub(1));
+        let ready = loop {
+            if limit <= buffer.len() - start {
+                break true;
+            }
+            match self.poll_map(context, &mut Item::clone) {
+                Poll::Ready(Some(message)) => buffer.push(message),
+                Poll::Ready(None) => break true,
+                Poll::Pending => break buffer.len() != start,
+            }
+        };
+        self.prefetch_limit = prefetch_limit;
+        if ready {
+            Poll::Ready(buffer.len() - start)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Wait for up to `limit` messages, appending them to `buffer`. See
+    /// [`Receiver::poll_next_batch`].
+    pub async fn next_batch(&mut self, buffer: &mut Vec<Message<Item>>, limit: usize) -> usize {
+        futures::future::poll_fn(|context| self.poll_next_batch(context, buffer, limit)).await
+    }
+
+    /// Wait for the next burst, and take up to `max` of its messages in one call, for
+    /// high-throughput subscribers. See [`Receiver::poll_next_batch`].
+    ///
+    /// An empty chunk means the stream has ended, unless `max` is 0.
+    pub async fn next_chunk(&mut self, max: usize) -> Vec<Message<Item>> {
+        let mut chunk = Vec::with_capacity(max);
+        self.next_batch(&mut chunk, max).await;
+        chunk
+    }
+
+    /// Wait up to `timeout` for the next message, e.g., to send a keepalive on a quiet
+    /// connection without wrapping every call in `tokio::time::timeout`.
+    ///
+    /// Nothing is lost on a timeout: the next call picks up where this one left off. This
+    /// uses the tokio timer, so it must be awaited on a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub async fn next_timeout(&mut self, timeout: std::time::Duration) -> NextTimeout<Item> {
+        match tokio::time::timeout(timeout, self.next()).await {
+            Ok(Some(message)) => NextTimeout::Item(message),
+            Ok(None) => NextTimeout::Closed,
+            Err(_) => {
+                log::trace!("next timed out");
+                NextTimeout::Timeout
+            }
+        }
+    }
+
+    /// Convert this Receiver into a stream of `Result`s, e.g., for forwarding with
+    /// `SinkExt::send_all` or into a tonic response.
+    ///
+    /// Entries become `Ok(item)`, and lag becomes `Err(lag_to_err(count))`. Resets are
+    /// passed over silently: the stream just continues with the new sequence.
+    pub fn into_results<E>(
+        self,
+        lag_to_err: impl Fn(usize) -> E,
+    ) -> impl futures::Stream<Item = Result<Item, E>> + Unpin {
+        self.filter_map(move |message| {
+            futures::future::ready(match message {
+                Message::Entry { item } => Some(Ok(item)),
+                Message::Lagged { count } => Some(Err(lag_to_err(count))),
+                Message::Reset { .. } => None,
+            })
+        })
+    }
+
+    /// Share this Receiver's position, as the shared cursor and on the [`PositionStream`].
+    fn update_position(&self) {
+        let next_message_id = self.next_message_id();
+        if self.control.set_position(next_message_id) {
+            self.shared.wake_position_waiters();
+            if let Some(positions) = &self.positions {
+                positions.confirm(next_message_id - 1, Instant::now());
+            }
+        }
+    }
+
+    /// Give up this Receiver's place in the splaycast: it no longer counts as a subscriber,
+    /// and its permit goes back. This happens once, on expiry or on drop. The Engine may
+    /// have done it already, if this expired while nothing polled it.
+    fn release(&mut self) {
+        if self.released {
+            return;
+        }
+        self.released = true;
+        if self.control.claim_release() {
+            self.shared.release_receiver(
+                self.id,
+                &self.control,
+                self.epoch,
+                self.next_message_id(),
+            );
+        }
+        if let Some(positions) = &self.positions {
+            positions.close();
         }
     }
 
-    fn mark_clean_and_register_for_wake(&mut self, context: &mut Context<'_>) {
+    /// The id of the next entry this Receiver will yield, accounting for prefetched entries.
+    pub(crate) fn next_message_id(&self) -> u64 {
+        self.prefetched
+            .front()
+            .map_or(self.next_message_id, |(id, ..)| *id)
+    }
+
+    fn lagged<U>(&self, count: usize) -> Poll<Option<Message<U>>> {
+        self.control.record_lag(1, count as u64);
+        self.shared.report_lag(self.id, self.label.clone(), count);
+        Poll::Ready(Some(Message::Lagged { count }))
+    }
+
+    fn park<U>(&mut self, context: Option<&mut Context<'_>>) -> Poll<Option<Message<U>>> {
+        match context {
+            Some(context) => self.mark_clean_and_register_for_wake(context),
+            None => Poll::Pending,
+        }
+    }
+
+    fn mark_clean_and_register_for_wake<U>(
+        &mut self,
+        context: &mut Context<'_>,
+    ) -> Poll<Option<Message<U>>> {
+        self.control.register_waker(context.waker());
         self.shared.register_waker(
             self.id,
             WakeHandle::new(self.next_message_id, context.waker().clone()),
         );
+        // A disconnect may have happened before the control waker was registered.
+        if self.control.is_disconnected() {
+            return Poll::Ready(None);
+        }
+        // So may a send_to: poll again to pick it up. An inline Engine isn't polled when
+        // this parks, so it may also have published past this without seeing it park.
+        if self.control.has_direct()
+            || (self.shared.is_inline() && self.shared.published_past(self.next_message_id))
+        {
+            context.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+}
+
+impl<Item> Receiver<Item, Replay>
+where
+    Item: Clone,
+{
+    /// The id of the next entry this Receiver expects, accounting for prefetched entries.
+    /// It yields that entry next, unless the entry is lost to lag.
+    ///
+    /// Persist this to resume a client where it left off: pass it to
+    /// [`crate::Splaycast::subscribe_from`] when the client reconnects.
+    pub fn position(&self) -> u64 {
+        self.next_message_id()
+    }
+
+    /// Move this Receiver so that the next entry it yields is the entry with sequence id
+    /// `id`, e.g., to replay recent history.
+    ///
+    /// This works like [`crate::Splaycast::subscribe_from`]: if that entry already fell off
+    /// the buffer, the Receiver yields a `Lagged` first, and ids that haven't been published
+    /// yet move to the head. Prefetched entries are discarded.
+    pub fn seek(&mut self, id: u64) {
+        self.prefetched.clear();
+        self.epoch = self.shared.epoch();
+        self.next_message_id = id.clamp(1, self.shared.subscribe_sequence_number());
+        self.takes_summary = false;
+        self.update_position();
     }
 }
 
-impl<Item> Drop for Receiver<Item>
+/// A Receiver's place in a splaycast, in transit from [`Receiver::hand_off`] to
+/// [`crate::Splaycast::adopt`].
+///
+/// While it is in transit, the subscription still counts as a subscriber, so handing off
+/// never looks like an unsubscribe. Dropping it unsubscribes, like dropping the Receiver.
+pub struct SubscriptionState<Item, Mode = Replay>
 where
     Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Boxed, so the state is cheap to hand back from a failed adopt.
+    receiver: Box<Receiver<Item, Mode>>,
+    keep_settings: bool,
+}
+
+impl<Item, Mode> std::fmt::Debug for SubscriptionState<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubscriptionState")
+            .field("receiver", &self.receiver)
+            .field("keep_settings", &self.keep_settings)
+            .finish()
+    }
+}
+
+impl<Item, Mode> Receiver<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Give up this Receiver so a new one can pick up at exactly the same place, e.g., when
+    /// a client roams from one gateway task to another. Pass the state to
+    /// [`crate::Splaycast::adopt`].
+    ///
+    /// The adopting Receiver yields exactly what this one would have yielded next, with no
+    /// gap and no duplicates: prefetched entries and items from
+    /// [`crate::Splaycast::send_to`] come along.
+    pub fn hand_off(self) -> SubscriptionState<Item, Mode> {
+        SubscriptionState {
+            receiver: Box::new(self),
+            keep_settings: true,
+        }
+    }
+}
+
+impl<Item, Mode> SubscriptionState<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Only transfer the position, and give the adopting Receiver default settings, like a
+    /// new subscription. Otherwise, it also gets the filter, prefetch, lag resume position,
+    /// expiry and pause of the Receiver that handed off.
+    pub fn position_only(self) -> Self {
+        Self {
+            keep_settings: false,
+            ..self
+        }
+    }
+
+    pub(crate) fn is_for(&self, shared: &Arc<Shared<Item>>) -> bool {
+        Arc::ptr_eq(&self.receiver.shared, shared)
+    }
+
+    /// Move the subscription to a new Receiver. The subscriber count never dips, and the
+    /// old Receiver is retired without counting as abandoned.
+    pub(crate) fn adopt(self) -> Receiver<Item, Mode> {
+        let Self {
+            receiver: old,
+            keep_settings,
+        } = self;
+        let mut old = *old;
+        let mut new = Receiver::new_at(
+            old.shared.next_receiver_id(),
+            old.shared.clone(),
+            old.next_message_id(),
+        );
+        new.next_message_id = old.next_message_id;
+        new.epoch = old.epoch;
+        new.takes_summary = old.takes_summary;
+        new.prefetched = std::mem::take(&mut old.prefetched);
+        new.metadata = old.metadata.take();
+        if let Some(permit) = old.control.take_permit() {
+            new.control.hold_permit(permit);
+        }
+        if let Some(label) = old.label.take() {
+            new.set_label(label);
+        }
+        new.control
+            .record_lag(old.control.lag_events(), old.control.entries_skipped());
+        if keep_settings {
+            new.prefetch_limit = old.prefetch_limit;
+            new.resume_at = old.resume_at;
+            new.filter = old.filter.take();
+            new.paused = old.paused.take().map(|_| None);
+            #[cfg(feature = "tokio")]
+            if let Some(expiry) = old.expiry.take() {
+                new.set_expiry(expiry.deadline().into_std());
+            }
+        }
+        new.update_position();
+
+        // Deregister first, so nothing more is sent to the old id while its items move. If
+        // the Engine expired the old Receiver already, it gave up its place then.
+        old.released = true;
+        let claimed = old.control.claim_release();
+        if claimed {
+            old.shared.deregister_receiver(old.id);
+        }
+        while let Some(item) = old.control.take_direct() {
+            new.control.send_direct(item);
+        }
+        if claimed {
+            old.shared.decrement_subscriber_count();
+        }
+        if let Some(positions) = &old.positions {
+            positions.close();
+        }
+        new
+    }
+}
+
+/// A clone is an independent Receiver at the same position, e.g., to tee a subscriber's
+/// view into a logger. It has the same filter, prefetch, expiry and label, and the entries
+/// this Receiver has prefetched, but not its permit, its [`PositionStream`], its lag
+/// counters or the items sent to it with [`crate::Splaycast::send_to`]. Like a subscriber
+/// that was admitted already, cloning does not check [`crate::Splaycast::seal`] or the
+/// subscriber limit.
+impl<Item, Mode> Clone for Receiver<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    fn clone(&self) -> Self {
+        let mut clone = Self::new_at(
+            self.shared.next_receiver_id(),
+            self.shared.clone(),
+            self.next_message_id(),
+        );
+        clone.next_message_id = self.next_message_id;
+        clone.epoch = self.epoch;
+        clone.takes_summary = self.takes_summary;
+        clone.prefetch_limit = self.prefetch_limit;
+        clone.resume_at = self.resume_at;
+        clone.prefetched = self.prefetched.clone();
+        clone.metadata = self.metadata.clone();
+        clone.filter = self.filter.clone();
+        if let Some(label) = &self.label {
+            clone.set_label(label.clone());
+        }
+        #[cfg(feature = "tokio")]
+        if let Some(expiry) = &self.expiry {
+            clone.set_expiry(expiry.deadline().into_std());
+        }
+        clone
+    }
+}
+
+impl<Item, Mode> Drop for Receiver<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
 {
     fn drop(&mut self) {
-        self.shared.decrement_subscriber_count();
+        self.release();
     }
 }
 
-impl<Item> futures::Stream for Receiver<Item>
+/// Safety: I don't use unsafe for this type
+impl<Item, Mode> Unpin for Receiver<Item, Mode>
 where
     Item: Clone,
+    Mode: SubscriptionMode,
 {
-    type Item = Message<Item>;
+}
 
-    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+impl<Item, Mode> Receiver<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    /// Poll for the next message, producing the delivered entry with `map` instead of
+    /// cloning it. Prefetched entries are still cloned into the local queue.
+    pub(crate) fn poll_map<U>(
+        &mut self,
+        context: &mut Context<'_>,
+        map: &mut impl FnMut(&Item) -> U,
+    ) -> Poll<Option<Message<U>>> {
+        if self.released {
+            return Poll::Ready(None);
+        }
+        // Polling the timer also registers for a wake when it expires.
+        #[cfg(feature = "tokio")]
+        if let Some(expiry) = &mut self.expiry {
+            if expiry.as_mut().poll(context).is_ready() {
+                log::trace!("expired");
+                self.release();
+                return Poll::Ready(None);
+            }
+        }
+        let next = self.poll_shared(Some(context), map);
+        self.update_position();
+        next
+    }
+
+    /// With no context, the Receiver is not registered for a wake when it parks.
+    fn poll_shared<U>(
+        &mut self,
+        context: Option<&mut Context<'_>>,
+        map: &mut impl FnMut(&Item) -> U,
+    ) -> Poll<Option<Message<U>>> {
         log::trace!("poll {self:?}");
+        if self.control.is_disconnected() {
+            log::trace!("disconnected");
+            return Poll::Ready(None);
+        }
+        if let Some(paused) = &mut self.paused {
+            if self.shared.is_dead() && self.prefetched.is_empty() {
+                return Poll::Ready(None);
+            }
+            log::trace!("paused");
+            if let Some(context) = context {
+                *paused = Some(context.waker().clone());
+            }
+            return Poll::Pending;
+        }
+        if let Some(item) = self.control.take_direct() {
+            log::trace!("ready from direct");
+            self.metadata = None;
+            return Poll::Ready(Some(Message::Entry { item: map(&item) }));
+        }
+        if let Some((_, item, metadata)) = self.prefetched.pop_front() {
+            log::trace!("ready from prefetch");
+            self.metadata = metadata;
+            return Poll::Ready(Some(Message::Entry { item: map(&item) }));
+        }
         if self.shared.is_dead() {
             return Poll::Ready(None); // It's dead
         }
 
         let shared_queue_snapshot = self.shared.load_queue();
-        let tip_id = match shared_queue_snapshot.back() {
-            Some(back) => back.id,
-            None => self.next_message_id,
+        let epoch = match shared_queue_snapshot.front() {
+            Some(front) => front.epoch,
+            None => self.shared.epoch(),
         };
+        if epoch != self.epoch {
+            log::trace!("ready reset - epoch {epoch}");
+            self.epoch = epoch;
+            self.next_message_id = 1;
+            self.takes_summary = true;
+            return Poll::Ready(Some(Message::Reset { epoch }));
+        }
+
+        if Mode::LATEST_ONLY {
+            if let Some(back) = shared_queue_snapshot.back() {
+                // Intermediate entries are discarded quietly: this Receiver only wants the newest.
+                self.next_message_id = self.next_message_id.max(back.id);
+            }
+        }
 
-        let index = match find(self.next_message_id, &shared_queue_snapshot) {
-            Ok(found) => found,
-            Err(missing_at) => {
-                if missing_at == 0 {
-                    if tip_id == 1 {
-                        log::trace!("bootstrapping - no messages yet");
-                        self.mark_clean_and_register_for_wake(context);
-                        return Poll::Pending;
+        let index = loop {
+            let tip_id = match shared_queue_snapshot.back() {
+                Some(back) => back.id,
+                None => self.next_message_id,
+            };
+
+            let index = match find(self.next_message_id, &shared_queue_snapshot) {
+                Ok(found) => found,
+                Err(missing_at) => {
+                    if shared_queue_snapshot.is_empty() {
+                        // Nothing is buffered, e.g., after a flush: catch up to the next entry.
+                        let next = self.shared.subscribe_sequence_number();
+                        if next <= self.next_message_id {
+                            log::trace!("pending clean - nothing buffered");
+                            return self.park(context);
+                        }
+                        let count = (next - self.next_message_id) as usize;
+                        self.next_message_id = next;
+                        log::trace!("ready flushed lag - {count}");
+                        return self.lagged(count);
+                    }
+                    if missing_at == 0 {
+                        if tip_id == 1 {
+                            log::trace!("bootstrapping - no messages yet");
+                            return self.park(context);
+                        }
+                        // We fell off the buffer.
+                        let resume_index = match self.resume_at {
+                            ResumeAt::Tail => 0,
+                            ResumeAt::Head => shared_queue_snapshot.len() - 1,
+                            ResumeAt::Percent(percent) => {
+                                (shared_queue_snapshot.len() - 1) * usize::from(percent.min(100))
+                                    / 100
+                            }
+                        };
+                        let next = match shared_queue_snapshot.get(resume_index) {
+                            Some(entry) if self.skips_summary(entry) => entry.id + 1,
+                            Some(entry) => entry.id,
+                            None => tip_id,
+                        };
+                        let count = (next - self.next_message_id) as usize;
+                        self.next_message_id = next;
+                        log::trace!("ready lag - {count}");
+                        return self.lagged(count);
+                    } else if missing_at == shared_queue_snapshot.len() {
+                        // We're caught up.
+                        log::trace!("pending clean - caught up");
+                        // We're registered for wake on delivery of new items at the next message id.
+                        return self.park(context);
+                    } else {
+                        // There's a gap in the buffer: entries around a pinned entry were popped.
+                        let next = shared_queue_snapshot[missing_at].id;
+                        let count = (next - self.next_message_id) as usize;
+                        self.next_message_id = next;
+                        log::trace!("ready gap lag - {count}");
+                        return self.lagged(count);
                     }
-                    // We fell off the buffer.
-                    let next = shared_queue_snapshot
-                        .front()
-                        .map(|f| f.id)
-                        .unwrap_or(tip_id);
-                    let count = (next - self.next_message_id) as usize;
-                    let lag = Message::Lagged { count };
-                    self.next_message_id = next;
-                    log::trace!("ready lag - {count}");
-                    return Poll::Ready(Some(lag));
-                } else if missing_at == shared_queue_snapshot.len() {
-                    // We're caught up.
-                    log::trace!("pending clean - caught up");
-                    self.mark_clean_and_register_for_wake(context);
-                    return Poll::Pending; // We're registered for wake on delivery of new items at the next message id.
-                } else {
-                    log::error!("ids must be sequential");
-                    return Poll::Ready(None);
                 }
+            };
+            if self.skips_summary(&shared_queue_snapshot[index]) {
+                self.next_message_id = shared_queue_snapshot[index].id + 1;
+                log::trace!("ready summary lag");
+                return self.lagged(1);
+            }
+            if self.passes_filter(&shared_queue_snapshot[index].item) {
+                break index;
             }
+            // Filtered out. Skip it without cloning, and keep looking.
+            self.next_message_id = shared_queue_snapshot[index].id + 1;
         };
 
         let message_id = shared_queue_snapshot[index].id;
         log::trace!("ready at {message_id}");
         self.next_message_id = message_id + 1;
+        self.takes_summary = false;
+
+        for entry in shared_queue_snapshot.range(index + 1..) {
+            if self.prefetch_limit <= self.prefetched.len()
+                || entry.id != self.next_message_id
+                || self.skips_summary(entry)
+            {
+                break; // Stop at gaps, so the lag is reported in order.
+            }
+            if self.passes_filter(&entry.item) {
+                self.prefetched
+                    .push_back((entry.id, entry.item.clone(), entry.metadata.clone()));
+            }
+            self.next_message_id = entry.id + 1;
+        }
+
+        let entry = &shared_queue_snapshot[index];
+        self.metadata = entry.metadata.clone();
         Poll::Ready(Some(Message::Entry {
-            item: shared_queue_snapshot[index].item.clone(),
+            item: map(&entry.item),
         }))
     }
 }
 
+impl<Item, Mode> futures::Stream for Receiver<Item, Mode>
+where
+    Item: Clone,
+    Mode: SubscriptionMode,
+{
+    type Item = Message<Item>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_map(context, &mut Item::clone)
+    }
+
+    /// The lower bound is the number of entries already buffered ahead of this Receiver,
+    /// e.g., for `ready_chunks` to size its allocations. It's a snapshot: the Engine can
+    /// evict some of them in the meantime, and you get a `Lagged` for those instead.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.buffered_ahead(), None)
+    }
+}
+
 /// Since the splaycast Engine increases sequence numbers one by one, we can exploit the
 /// array offset directly. This doesn't really matter for small buffers, but if you wanted
 /// a large buffer, O(log(buffer) * receiver_count) per message can start to add up for
 /// the simplicity of binary search.
+///
+/// Pinned entries can leave gaps in the buffer, so when the offset doesn't line up we fall
+/// back to binary search.
 #[inline]
-fn find<Item>(id: u64, buffer: &VecDeque<SplaycastEntry<Item>>) -> Result<usize, usize> {
-    match buffer.front().map(SplaycastEntry::id) {
-        Some(front_id) => {
+pub(crate) fn find<Item>(id: u64, buffer: &VecDeque<SplaycastEntry<Item>>) -> Result<usize, usize> {
+    match (
+        buffer.front().map(SplaycastEntry::id),
+        buffer.back().map(SplaycastEntry::id),
+    ) {
+        (Some(front_id), Some(back_id)) => {
             if id < front_id {
                 Err(0) // before the start - this is a lag
+            } else if back_id < id {
+                Err(buffer.len()) // hasn't happened yet - this will park the receiver
             } else {
                 let offset = (id - front_id) as usize;
-                if buffer.len() <= offset {
-                    Err(buffer.len()) // hasn't happened yet - this will park the receiver
-                } else {
+                if buffer.get(offset).map(SplaycastEntry::id) == Some(id) {
                     Ok(offset) // hey look, ready to poll at offset
+                } else {
+                    buffer.binary_search_by_key(&id, SplaycastEntry::id)
                 }
             }
         }
-        None => Err(0), // empty buffer
+        _ => Err(0), // empty buffer
     }
 }
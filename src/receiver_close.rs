@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+/// Why a consumer closed its Receiver, for [`crate::Receiver::close`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CloseReason {
+    /// The consumer is done with the subscription, e.g., the client navigated away.
+    Finished,
+    /// The consumer failed, e.g., its handler crashed.
+    Failed,
+    /// An application-specific reason.
+    Other(Arc<str>),
+}
+
+impl std::fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloseReason::Finished => write!(f, "finished"),
+            CloseReason::Failed => write!(f, "failed"),
+            CloseReason::Other(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+/// A Receiver that was closed with a reason, for [`crate::Engine::set_on_receiver_closed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReceiverClosed {
+    /// See [`crate::Receiver::id`].
+    pub receiver_id: u64,
+    /// See [`crate::Receiver::label`].
+    pub label: Option<Arc<str>>,
+    /// Why it was closed.
+    pub reason: CloseReason,
+    /// See [`crate::Receiver::entries_skipped`].
+    pub entries_skipped: u64,
+}
+
+pub(crate) type OnReceiverClosed = Box<dyn Fn(ReceiverClosed) + Send + Sync>;
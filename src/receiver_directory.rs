@@ -0,0 +1,158 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError},
+};
+
+use crossbeam_queue::SegQueue;
+
+use crate::shared::{ReceiverControl, Shared};
+
+pub(crate) type Controls<Item> = HashMap<u64, Arc<ReceiverControl<Item>>>;
+
+/// A receiver joining or leaving, for the maps of live receivers.
+pub(crate) enum Registration<Item> {
+    Joined(u64, Arc<ReceiverControl<Item>>),
+    Left(u64),
+}
+
+impl<Item> Clone for Registration<Item> {
+    fn clone(&self) -> Self {
+        match self {
+            Registration::Joined(receiver_id, control) => {
+                Registration::Joined(*receiver_id, control.clone())
+            }
+            Registration::Left(receiver_id) => Registration::Left(*receiver_id),
+        }
+    }
+}
+
+impl<Item> Registration<Item> {
+    fn apply(self, controls: &mut Controls<Item>) {
+        match self {
+            Registration::Joined(receiver_id, control) => {
+                controls.insert(receiver_id, control);
+            }
+            Registration::Left(receiver_id) => {
+                controls.remove(&receiver_id);
+            }
+        }
+    }
+}
+
+/// Every live receiver's control, by receiver id, for acting on one receiver from a handle.
+///
+/// Receivers never wait on it: they queue their registration as they join and leave, and
+/// only apply the queue if nobody holds the map. Handles lock the map, and catch up with
+/// the queue before they use it.
+pub(crate) struct ReceiverDirectory<Item> {
+    registrations: SegQueue<Registration<Item>>,
+    controls: Mutex<Controls<Item>>,
+}
+
+impl<Item> ReceiverDirectory<Item> {
+    pub fn new() -> Self {
+        Self {
+            registrations: SegQueue::new(),
+            controls: Default::default(),
+        }
+    }
+
+    pub fn register(&self, registration: Registration<Item>) {
+        self.registrations.push(registration);
+        match self.controls.try_lock() {
+            Ok(mut controls) => self.catch_up(&mut controls),
+            Err(TryLockError::Poisoned(poisoned)) => self.catch_up(&mut poisoned.into_inner()),
+            // Whoever holds the map catches up the next time they lock it.
+            Err(TryLockError::WouldBlock) => (),
+        }
+    }
+
+    /// The map, including every receiver that joined or left before the call.
+    pub fn lock(&self) -> MutexGuard<'_, Controls<Item>> {
+        let mut controls = self.controls.lock().unwrap_or_else(PoisonError::into_inner);
+        self.catch_up(&mut controls);
+        controls
+    }
+
+    fn catch_up(&self, controls: &mut Controls<Item>) {
+        while let Some(registration) = self.registrations.pop() {
+            registration.apply(controls);
+        }
+    }
+}
+
+/// The Engine's own map of live receivers, so that it never shares a lock with them.
+///
+/// Receivers announce themselves on a queue as they join and leave, and the Engine catches
+/// up from it before it needs the map.
+pub(crate) struct ReceiverRoster<Item> {
+    controls: Controls<Item>,
+}
+
+impl<Item> ReceiverRoster<Item>
+where
+    Item: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            controls: HashMap::new(),
+        }
+    }
+
+    pub fn catch_up(&mut self, shared: &Shared<Item>) {
+        while let Some(registration) = shared.take_registration() {
+            registration.apply(&mut self.controls);
+        }
+    }
+
+    /// A receiver's id, and its label if it has one, for logs.
+    pub fn describe(&self, receiver_id: u64) -> String {
+        match self
+            .controls
+            .get(&receiver_id)
+            .and_then(|control| control.label())
+        {
+            Some(label) => format!("{receiver_id} ({label})"),
+            None => receiver_id.to_string(),
+        }
+    }
+
+    /// Disconnect a receiver whose waker panicked, without waking it again.
+    pub fn poison(&mut self, shared: &Shared<Item>, receiver_id: u64) {
+        shared.count_poisoned();
+        if !self.controls.contains_key(&receiver_id) {
+            // It woke before the Engine caught up with it joining.
+            self.catch_up(shared);
+        }
+        if let Some(control) = self.controls.get(&receiver_id) {
+            control.poison();
+        }
+    }
+
+    /// Release a receiver whose deadline passed. Returns false if it was already released,
+    /// e.g., because it was dropped or it noticed the deadline first.
+    #[cfg(feature = "tokio")]
+    pub fn expire(&mut self, shared: &Shared<Item>, receiver_id: u64) -> bool {
+        if !self.controls.contains_key(&receiver_id) {
+            // It expired before the Engine caught up with it joining.
+            self.catch_up(shared);
+        }
+        let Some(control) = self.controls.get(&receiver_id) else {
+            return false;
+        };
+        if !control.claim_release() {
+            return false;
+        }
+        shared.release_receiver(receiver_id, control, shared.epoch(), control.position());
+        true
+    }
+
+    /// How far the slowest receiver is behind `next_head`, as of its last poll.
+    pub fn slowest_backlog(&self, next_head: u64) -> usize {
+        self.controls
+            .values()
+            .map(|control| next_head.saturating_sub(control.position()))
+            .max()
+            .unwrap_or_default() as usize
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use crate::sync::{AtomicU64, Ordering};
+
+use crossbeam_queue::SegQueue;
+use futures::Stream;
+
+use crate::{Message, Receiver};
+
+/// The position of a member that has left the group. It no longer holds anyone back.
+const DEPARTED: u64 = u64::MAX;
+
+struct GroupState {
+    /// The id of the last entry each member has received.
+    // sync(Release, Acquire): each member stores its position as it yields, and the group
+    // Acquires them to decide whether everyone has passed an id.
+    positions: Vec<AtomicU64>,
+    /// Barrier waiters, woken whenever a member moves.
+    wakers: SegQueue<Waker>,
+}
+
+impl GroupState {
+    fn advance(&self, member: usize, position: u64) {
+        self.positions[member].store(position, Ordering::Release);
+        while let Some(waker) = self.wakers.pop() {
+            waker.wake();
+        }
+    }
+
+    fn all_at(&self, id: u64) -> bool {
+        self.positions
+            .iter()
+            .all(|position| id <= position.load(Ordering::Acquire))
+    }
+}
+
+/// A handle on a group of coordinated Receivers, from [`crate::Splaycast::subscribe_group`].
+///
+/// Use [`ReceiverGroup::wait_all_at`] as a barrier, e.g., when N workers must all finish
+/// processing entry X before any of them proceeds past it.
+#[derive(Clone)]
+pub struct ReceiverGroup {
+    state: Arc<GroupState>,
+}
+
+impl std::fmt::Debug for ReceiverGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReceiverGroup")
+            .field("positions", &self.positions())
+            .finish()
+    }
+}
+
+impl ReceiverGroup {
+    /// Wait until every member of the group has received the entry with sequence id `id`,
+    /// or has moved past it.
+    ///
+    /// Members that are dropped, or whose splaycast terminated, no longer hold the group back.
+    pub fn wait_all_at(&self, id: u64) -> WaitAllAt {
+        WaitAllAt {
+            state: self.state.clone(),
+            id,
+        }
+    }
+
+    /// The id of the last entry each member has received, or `None` for members that have
+    /// left the group.
+    pub fn positions(&self) -> Vec<Option<u64>> {
+        self.state
+            .positions
+            .iter()
+            .map(|position| match position.load(Ordering::Acquire) {
+                DEPARTED => None,
+                position => Some(position),
+            })
+            .collect()
+    }
+}
+
+/// A Receiver that is a member of a [`ReceiverGroup`]. It reports its position to the
+/// group as it moves.
+pub struct GroupReceiver<Item>
+where
+    Item: Clone,
+{
+    receiver: Receiver<Item>,
+    member: usize,
+    state: Arc<GroupState>,
+}
+
+impl<Item> std::fmt::Debug for GroupReceiver<Item>
+where
+    Item: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GroupReceiver")
+            .field("member", &self.member)
+            .field("receiver", &self.receiver)
+            .finish()
+    }
+}
+
+impl<Item> GroupReceiver<Item>
+where
+    Item: Clone,
+{
+    pub(crate) fn group(receivers: Vec<Receiver<Item>>) -> Vec<Self> {
+        let state = Arc::new(GroupState {
+            positions: receivers
+                .iter()
+                .map(|receiver| AtomicU64::new(receiver.next_message_id() - 1))
+                .collect(),
+            wakers: SegQueue::new(),
+        });
+        receivers
+            .into_iter()
+            .enumerate()
+            .map(|(member, receiver)| Self {
+                receiver,
+                member,
+                state: state.clone(),
+            })
+            .collect()
+    }
+
+    /// The group this Receiver is a member of.
+    pub fn receiver_group(&self) -> ReceiverGroup {
+        ReceiverGroup {
+            state: self.state.clone(),
+        }
+    }
+
+    /// The id of the next entry this Receiver expects, like [`crate::Receiver::position`].
+    /// The group tracks the entry before it: the last one this Receiver has received.
+    pub fn position(&self) -> u64 {
+        self.receiver.next_message_id()
+    }
+}
+
+impl<Item> Drop for GroupReceiver<Item>
+where
+    Item: Clone,
+{
+    fn drop(&mut self) {
+        self.state.advance(self.member, DEPARTED);
+    }
+}
+
+impl<Item> Stream for GroupReceiver<Item>
+where
+    Item: Clone,
+{
+    type Item = Message<Item>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let next = Pin::new(&mut self.receiver).poll_next(context);
+        match &next {
+            Poll::Ready(Some(_)) => self.state.advance(self.member, self.position() - 1),
+            Poll::Ready(None) => self.state.advance(self.member, DEPARTED),
+            Poll::Pending => (),
+        }
+        next
+    }
+}
+
+/// A barrier Future, from [`ReceiverGroup::wait_all_at`].
+pub struct WaitAllAt {
+    state: Arc<GroupState>,
+    id: u64,
+}
+
+impl std::fmt::Debug for WaitAllAt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WaitAllAt").field("id", &self.id).finish()
+    }
+}
+
+impl futures::Future for WaitAllAt {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.state.all_at(self.id) {
+            return Poll::Ready(());
+        }
+        self.state.wakers.push(context.waker().clone());
+        // A member may have moved before we registered, so check once more.
+        if self.state.all_at(self.id) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! A process-wide registry of named splaycasts, for debugging and observability.
+//!
+//! Register a [`SplaycastMirror`] under a name, and you can enumerate every registered
+//! channel along with its stats from anywhere in the process. This is handy for powering
+//! a debug endpoint like "show me all channels, their subscriber counts and buffer depths"
+//! without wiring up your own registry.
+//!
+//! The registry does not keep channels alive. Channels that have terminated are pruned
+//! automatically.
+//!
+//! ```
+//! # tokio_test::block_on(async {
+//! let (sender, engine, splaycast) = splaycast::channel::<u32>(128);
+//! tokio::spawn(engine);
+//! splaycast::registry::register("prices", splaycast.mirror());
+//!
+//! let _receiver = splaycast.subscribe();
+//! let channels = splaycast::registry::channels();
+//! assert_eq!("prices", channels[0].name);
+//! assert_eq!(1, channels[0].subscriber_count);
+//! # })
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, PoisonError, Weak},
+};
+
+use crate::{shared::Shared, SplaycastMirror};
+
+static REGISTRY: Mutex<BTreeMap<String, Box<dyn RegisteredChannel>>> = Mutex::new(BTreeMap::new());
+
+/// A point-in-time view of a registered channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelStats {
+    /// The name the channel was registered with.
+    pub name: String,
+    /// See [`crate::Splaycast::subscriber_count`].
+    pub subscriber_count: usize,
+    /// How many entries are currently retained in the channel's buffer.
+    pub buffer_len: usize,
+}
+
+/// Register a splaycast under `name`. If a channel is already registered with that name,
+/// it is replaced.
+pub fn register<Item>(name: impl Into<String>, mirror: SplaycastMirror<Item>)
+where
+    Item: Clone + Send + Sync + 'static,
+{
+    let name = name.into();
+    log::debug!("registering splaycast {name}");
+    let channel = Box::new(Registered(mirror.downgrade_shared()));
+    let mut registry = REGISTRY.lock().unwrap_or_else(PoisonError::into_inner);
+    registry.retain(|_, channel| channel.is_alive());
+    registry.insert(name, channel);
+}
+
+/// Remove the channel registered under `name`. Returns whether there was one.
+pub fn deregister(name: &str) -> bool {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(name)
+        .is_some()
+}
+
+/// Inspect every live registered channel, ordered by name.
+pub fn channels() -> Vec<ChannelStats> {
+    let mut registry = REGISTRY.lock().unwrap_or_else(PoisonError::into_inner);
+    registry.retain(|_, channel| channel.is_alive());
+    registry
+        .iter()
+        .filter_map(|(name, channel)| channel.stats(name))
+        .collect()
+}
+
+/// Type-erased access to a registered channel, so channels of any Item can share a registry.
+trait RegisteredChannel: Send {
+    fn is_alive(&self) -> bool;
+
+    fn stats(&self, name: &str) -> Option<ChannelStats>;
+}
+
+struct Registered<Item>(Weak<Shared<Item>>);
+
+impl<Item> RegisteredChannel for Registered<Item>
+where
+    Item: Clone + Send + Sync,
+{
+    fn is_alive(&self) -> bool {
+        self.0
+            .upgrade()
+            .map(|shared| !shared.is_dead())
+            .unwrap_or(false)
+    }
+
+    fn stats(&self, name: &str) -> Option<ChannelStats> {
+        let shared = self.0.upgrade()?;
+        Some(ChannelStats {
+            name: name.to_string(),
+            subscriber_count: shared.subscriber_count(),
+            buffer_len: shared.buffer_len(),
+        })
+    }
+}
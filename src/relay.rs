@@ -0,0 +1,85 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+
+use crate::{
+    sync::{AtomicBool, AtomicU64, Ordering},
+    Message,
+};
+
+/// What a [`Relay`] passes to its Engine besides items.
+#[derive(Debug, Default)]
+pub(crate) struct RelayControl {
+    // sync(Relaxed): the Relay is the Engine's upstream, so both only run in the Engine's
+    // poll.
+    gap: AtomicU64,
+    // sync(Relaxed): the Relay is the Engine's upstream, so both only run in the Engine's
+    // poll.
+    reset: AtomicBool,
+}
+
+impl RelayControl {
+    /// The upstream lag since the last call, and whether the upstream reset.
+    pub fn take(&self) -> (u64, bool) {
+        (
+            self.gap.swap(0, Ordering::Relaxed),
+            self.reset.swap(false, Ordering::Relaxed),
+        )
+    }
+}
+
+/// The upstream of a relaying splaycast, from [`crate::wrap_relay`].
+///
+/// Entries are passed through as items. A `Lagged` or `Reset` is handed to the Engine
+/// instead, and the stream returns Pending and wakes the Engine right away: that way the
+/// Engine publishes everything before the lag or reset first, and applies it in order.
+pub struct Relay<Upstream> {
+    upstream: Upstream,
+    control: Arc<RelayControl>,
+}
+
+impl<Upstream> std::fmt::Debug for Relay<Upstream> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Relay")
+            .field("control", &self.control)
+            .finish()
+    }
+}
+
+impl<Upstream> Relay<Upstream> {
+    pub(crate) fn new(upstream: Upstream, control: Arc<RelayControl>) -> Self {
+        Self { upstream, control }
+    }
+}
+
+impl<Upstream, Item> Stream for Relay<Upstream>
+where
+    Upstream: Stream<Item = Message<Item>> + Unpin,
+{
+    type Item = Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::new(&mut self.upstream).poll_next(context) {
+            Poll::Ready(Some(Message::Entry { item })) => Poll::Ready(Some(item)),
+            Poll::Ready(Some(Message::Lagged { count })) => {
+                log::debug!("upstream lagged by {count}");
+                self.control.gap.fetch_add(count as u64, Ordering::Relaxed);
+                context.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Poll::Ready(Some(Message::Reset { epoch })) => {
+                log::debug!("upstream reset to epoch {epoch}");
+                self.control.gap.store(0, Ordering::Relaxed);
+                self.control.reset.store(true, Ordering::Relaxed);
+                context.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
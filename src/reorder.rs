@@ -0,0 +1,152 @@
+use std::{cmp::Ordering, collections::VecDeque};
+
+#[cfg(feature = "tokio")]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::Context,
+    time::{Duration, Instant},
+};
+
+/// How long the Engine may hold items back to put them in order.
+///
+/// Items are held in a reorder buffer before they are assigned sequence ids. Once the
+/// buffer holds more than `count` items, the lowest is released to the splaycast. A
+/// larger window tolerates larger reorderings, at the cost of latency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorderWindow {
+    count: usize,
+    #[cfg(feature = "tokio")]
+    max_delay: Option<Duration>,
+}
+
+impl ReorderWindow {
+    /// Hold up to `count` items back for reordering.
+    pub fn count(count: usize) -> Self {
+        Self {
+            count,
+            #[cfg(feature = "tokio")]
+            max_delay: None,
+        }
+    }
+
+    /// Hold no item back for longer than `max_delay`, even if the count window is not full.
+    ///
+    /// When an item's delay is up, it is released along with everything ordered before it.
+    #[cfg(feature = "tokio")]
+    pub fn with_max_delay(self, max_delay: Duration) -> Self {
+        Self {
+            max_delay: Some(max_delay),
+            ..self
+        }
+    }
+}
+
+type Compare<Item> = Box<dyn Fn(&Item, &Item) -> Ordering + Send>;
+
+/// The reorder stage of an Engine. Pending items are kept sorted by the user's key.
+pub(crate) struct ReorderBuffer<Item> {
+    window: ReorderWindow,
+    compare: Compare<Item>,
+    pending: VecDeque<Pending<Item>>,
+    #[cfg(feature = "tokio")]
+    deadline: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+struct Pending<Item> {
+    item: Item,
+    #[cfg(feature = "tokio")]
+    arrived: Instant,
+}
+
+impl<Item> ReorderBuffer<Item> {
+    pub fn new<K: Ord>(window: ReorderWindow, key: impl Fn(&Item) -> K + Send + 'static) -> Self {
+        Self {
+            window,
+            compare: Box::new(move |a, b| key(a).cmp(&key(b))),
+            pending: VecDeque::with_capacity(window.count + 1),
+            #[cfg(feature = "tokio")]
+            deadline: None,
+        }
+    }
+
+    pub fn push(&mut self, item: Item) {
+        // Ties stay in arrival order.
+        let position = self
+            .pending
+            .partition_point(|pending| (self.compare)(&pending.item, &item) != Ordering::Greater);
+        self.pending.insert(
+            position,
+            Pending {
+                item,
+                #[cfg(feature = "tokio")]
+                arrived: Instant::now(),
+            },
+        );
+    }
+
+    /// Take the items that are ready to be published, in order.
+    pub fn release(&mut self) -> impl Iterator<Item = Item> + '_ {
+        let ready = self.ready_count();
+        self.pending.drain(..ready).map(|pending| pending.item)
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    fn ready_count(&self) -> usize {
+        self.pending.len().saturating_sub(self.window.count)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn ready_count(&self) -> usize {
+        let overflow = self.pending.len().saturating_sub(self.window.count);
+        let Some(max_delay) = self.window.max_delay else {
+            return overflow;
+        };
+        match self
+            .pending
+            .iter()
+            .rposition(|pending| max_delay <= pending.arrived.elapsed())
+        {
+            Some(expired) => overflow.max(expired + 1),
+            None => overflow,
+        }
+    }
+
+    /// Take everything, e.g., because the upstream has ended.
+    pub fn release_all(&mut self) -> impl Iterator<Item = Item> + '_ {
+        self.pending.drain(..).map(|pending| pending.item)
+    }
+
+    /// Make sure the Engine is woken when the oldest held item's delay is up.
+    #[cfg(feature = "tokio")]
+    pub fn poll_deadline(&mut self, context: &mut Context<'_>) {
+        let Some(max_delay) = self.window.max_delay else {
+            return;
+        };
+        let Some(oldest) = self.pending.iter().map(|pending| pending.arrived).min() else {
+            self.deadline = None;
+            return;
+        };
+        let deadline = tokio::time::Instant::from_std(oldest + max_delay);
+        let sleep = self
+            .deadline
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline)));
+        if sleep.deadline() != deadline {
+            sleep.as_mut().reset(deadline);
+        }
+        // Registers the Engine's waker with the timer. A Ready here means the deadline
+        // already passed, so the items are released on the next poll.
+        if sleep.as_mut().poll(context).is_ready() {
+            context.waker().wake_by_ref();
+        }
+    }
+}
+
+impl<Item> std::fmt::Debug for ReorderBuffer<Item> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReorderBuffer")
+            .field("window", &self.window)
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+/// How [`crate::Sender::send_with_retry`] retries a full send buffer.
+///
+/// Between attempts, the Sender waits for the Engine to drain an item from the send buffer,
+/// but for no longer than the current backoff. The backoff starts at `initial_backoff` and
+/// is multiplied by `multiplier` after each failed attempt, up to `max_backoff`. Once
+/// `timeout` has elapsed, the send gives up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub(crate) timeout: Duration,
+    pub(crate) initial_backoff: Duration,
+    pub(crate) max_backoff: Duration,
+    pub(crate) multiplier: f64,
+}
+
+impl RetryPolicy {
+    /// Retry for up to `timeout`, starting with a 1ms backoff that doubles up to 100ms.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(100),
+            multiplier: 2.0,
+        }
+    }
+
+    /// The backoff before the first retry.
+    pub fn initial_backoff(self, initial_backoff: Duration) -> Self {
+        Self {
+            initial_backoff,
+            ..self
+        }
+    }
+
+    /// The longest backoff between retries.
+    pub fn max_backoff(self, max_backoff: Duration) -> Self {
+        Self {
+            max_backoff,
+            ..self
+        }
+    }
+
+    /// How much the backoff grows after each failed attempt.
+    pub fn multiplier(self, multiplier: f64) -> Self {
+        Self { multiplier, ..self }
+    }
+
+    pub(crate) fn next_backoff(&self, backoff: Duration) -> Duration {
+        backoff.mul_f64(self.multiplier).min(self.max_backoff)
+    }
+}
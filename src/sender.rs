@@ -1,11 +1,14 @@
 use std::{
-    pin::Pin,
-    sync::Arc,
-    task::{Context, Poll},
+    future::Future,
+    pin::{pin, Pin},
+    sync::{Arc, Weak},
+    task::{Context, Poll, Waker},
 };
 
-use crossbeam_queue::ArrayQueue;
-use futures::{task::AtomicWaker, Stream};
+use crate::sync::{AtomicBool, AtomicUsize, Ordering};
+
+use crossbeam_queue::{ArrayQueue, SegQueue};
+use futures::{stream::FusedStream, task::AtomicWaker, Stream, StreamExt};
 
 /// A single-producer sender, for a splaycast.
 ///
@@ -18,51 +21,598 @@ use futures::{task::AtomicWaker, Stream};
 /// engine can drain, you should see memory usage track pretty closely to your
 /// splaycast buffer size, and not much worse than 2*buffer size worst case.
 pub struct Sender<T> {
-    queue: Arc<ArrayQueue<T>>,
-    waker: Arc<AtomicWaker>,
+    shared: Arc<SenderShared<T>>,
 }
 
-impl<T> Sender<T> {
-    /// Send a value. If the send buffer is full, you'll get your value back as the Err value.
-    /// If you get an Err often, you probvably need a larger splaycast buffer or you need to
-    /// make the splaycast Engine run more often (e.g., by adding more threads to your runtime
-    /// or other task throughput enhancements)
-    pub fn send(&self, item: T) -> Result<(), T> {
-        match self.queue.push(item) {
-            Ok(_) => {
-                self.waker.wake();
+/// How many items fit in the priority lane. It is meant for the occasional control item,
+/// not for sustained traffic.
+const PRIORITY_LANE_CAPACITY: usize = 16;
+
+/// The data lane of a Sender. Bounded unless the channel was made with
+/// [`crate::unbounded_channel`].
+enum SendQueue<T> {
+    Bounded(ArrayQueue<T>),
+    Unbounded(SegQueue<T>),
+}
+
+impl<T> SendQueue<T> {
+    fn push(&self, item: T) -> Result<(), T> {
+        match self {
+            SendQueue::Bounded(queue) => queue.push(item),
+            SendQueue::Unbounded(queue) => {
+                queue.push(item);
                 Ok(())
             }
-            Err(e) => Err(e),
         }
     }
 
+    fn pop(&self) -> Option<T> {
+        match self {
+            SendQueue::Bounded(queue) => queue.pop(),
+            SendQueue::Unbounded(queue) => queue.pop(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            SendQueue::Bounded(queue) => queue.len(),
+            SendQueue::Unbounded(queue) => queue.len(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            SendQueue::Bounded(queue) => queue.is_empty(),
+            SendQueue::Unbounded(queue) => queue.is_empty(),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        match self {
+            SendQueue::Bounded(queue) => queue.capacity(),
+            SendQueue::Unbounded(_) => usize::MAX,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        match self {
+            SendQueue::Bounded(queue) => queue.is_full(),
+            SendQueue::Unbounded(_) => false,
+        }
+    }
+}
+
+/// The state shared between a Sender and its SenderStream.
+struct SenderShared<T> {
+    queue: SendQueue<T>,
+    /// Drained before the queue, so priority items jump ahead of queued items.
+    priority_queue: ArrayQueue<T>,
+    waker: AtomicWaker,
+    // sync(SeqCst, Acquire): close and drop store it before waking the stream. Pushes load
+    // it SeqCst after counting themselves in_flight, and the stream loads it SeqCst before
+    // checking in_flight, so a push either sees the close or the stream sees the push.
+    // is_closed() only Acquires it.
+    is_closed: AtomicBool,
+    // sync(SeqCst, Release): pushes count themselves in before the close check and out
+    // after the push. The stream doesn't end while a push is in flight.
+    in_flight: AtomicUsize,
+    /// Senders waiting for room in the queue. Woken whenever the SenderStream drains an item.
+    drain_wakers: SegQueue<Weak<DrainWaiter>>,
+}
+
+/// A send future's place in the drain queue. It is queued at most once at a time, however
+/// often the future is polled, and a future that is dropped leaves only a dead entry.
+#[derive(Default)]
+struct DrainWaiter {
+    waker: AtomicWaker,
+    // sync(AcqRel, Release): the future swaps it in before queueing itself. Whoever pops
+    // the entry clears it before waking, so a future that finds it set is still queued or
+    // is about to be woken, and re-queues itself on that poll.
+    queued: AtomicBool,
+}
+
+impl DrainWaiter {
+    fn dequeue_and_wake(&self) {
+        self.queued.store(false, Ordering::Release);
+        self.waker.wake();
+    }
+}
+
+/// Why a send failed. Either way, you get your value back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendError<T> {
+    /// The send buffer is full. Retrying later may succeed.
+    Full(T),
+    /// The Sender was closed, or the splaycast Engine is gone. Retrying will never succeed.
+    Closed(T),
+}
+
+impl<T> SendError<T> {
+    /// Get your value back.
+    pub fn into_inner(self) -> T {
+        match self {
+            SendError::Full(item) | SendError::Closed(item) => item,
+        }
+    }
+
+    /// Whether the send failed because the send buffer is full.
+    pub fn is_full(&self) -> bool {
+        matches!(self, SendError::Full(_))
+    }
+
+    /// Whether the send failed because the channel is closed.
+    pub fn is_closed(&self) -> bool {
+        matches!(self, SendError::Closed(_))
+    }
+
+    pub(crate) fn map<U>(self, f: impl FnOnce(T) -> U) -> SendError<U> {
+        match self {
+            SendError::Full(item) => SendError::Full(f(item)),
+            SendError::Closed(item) => SendError::Closed(f(item)),
+        }
+    }
+}
+
+impl<T> std::fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Full(_) => write!(f, "send buffer is full"),
+            SendError::Closed(_) => write!(f, "sender is closed"),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::error::Error for SendError<T> {}
+
+impl<T> SenderShared<T> {
+    fn pop(&self) -> Option<T> {
+        self.priority_queue.pop().or_else(|| self.queue.pop())
+    }
+
+    /// Push an item onto a lane, unless the channel is closed. This is the only way items
+    /// get into the queues: a push that passed the close check is always drained.
+    fn push(&self, item: T, priority: bool) -> Result<(), SendError<T>> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let result = if self.is_closed.load(Ordering::SeqCst) {
+            Err(SendError::Closed(item))
+        } else if priority {
+            self.priority_queue.push(item).map_err(SendError::Full)
+        } else {
+            self.queue.push(item).map_err(SendError::Full)
+        };
+        self.in_flight.fetch_sub(1, Ordering::Release);
+        if result.is_err() && self.is_closed.load(Ordering::Acquire) {
+            // The stream may be waiting for this push to finish before it ends.
+            self.waker.wake();
+        }
+        result
+    }
+
+    fn wake_drain_waiters(&self) {
+        while let Some(waiter) = self.drain_wakers.pop() {
+            if let Some(waiter) = waiter.upgrade() {
+                waiter.dequeue_and_wake();
+            }
+        }
+    }
+
+    /// Wake `waiter` with `waker` when the SenderStream drains an item.
+    fn register_drain_waiter(&self, waiter: &mut Option<Arc<DrainWaiter>>, waker: &Waker) {
+        let waiter = waiter.get_or_insert_with(Default::default);
+        waiter.waker.register(waker);
+        if waiter.queued.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        // Make room first: drop an entry whose future is gone, or wake a live one so it
+        // queues itself again. Futures that keep timing out don't grow the queue this way.
+        if let Some(oldest) = self.drain_wakers.pop() {
+            if let Some(oldest) = oldest.upgrade() {
+                oldest.dequeue_and_wake();
+            }
+        }
+        self.drain_wakers.push(Arc::downgrade(waiter));
+    }
+}
+
+impl<T> Sender<T> {
+    /// Send a value. If the send buffer is full, you'll get your value back in
+    /// [`SendError::Full`]. If you get that often, you probvably need a larger splaycast
+    /// buffer or you need to make the splaycast Engine run more often (e.g., by adding more
+    /// threads to your runtime or other task throughput enhancements)
+    ///
+    /// If the Sender has been closed, you get your value back in [`SendError::Closed`]. There
+    /// is no point in retrying those.
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        self.shared.push(item, false)?;
+        self.shared.waker.wake();
+        Ok(())
+    }
+
+    /// Send a value ahead of any items that are waiting in the send buffer, e.g., a control
+    /// item like "config changed." Priority items are delivered in the order they were sent,
+    /// relative to each other.
+    ///
+    /// The priority lane is small. If it is full, or the Sender is closed, you'll get your
+    /// value back in the [`SendError`].
+    pub fn send_priority(&self, item: T) -> Result<(), SendError<T>> {
+        self.shared.push(item, true)?;
+        self.shared.waker.wake();
+        Ok(())
+    }
+
+    /// Signal that there will be no more items.
+    ///
+    /// Items that were already sent are still delivered. Once they have been drained, the
+    /// SenderStream ends, which cleanly terminates the splaycast and gives receivers an
+    /// end-of-stream. Subsequent sends give your value back in [`SendError::Closed`].
+    pub fn close(&self) {
+        log::debug!("closing sender");
+        self.shared.is_closed.store(true, Ordering::SeqCst);
+        self.shared.waker.wake();
+        self.shared.wake_drain_waiters();
+    }
+
+    /// Send a value, parking the current OS thread until there is room in the send buffer.
+    ///
+    /// This is for synchronous producers, like a blocking callback thread. It must not be
+    /// called from an async context: it will block your runtime's worker thread.
+    ///
+    /// If the Sender is closed, or the splaycast Engine has been dropped, you'll get your
+    /// value back in [`SendError::Closed`].
+    pub fn blocking_send(&self, item: T) -> Result<(), SendError<T>> {
+        futures::executor::block_on(SendWhenReady::new(self, item))
+    }
+
+    /// Send items from an iterator until the send buffer is full or the Sender is closed.
+    /// Returns how many items were sent.
+    ///
+    /// If it stops before the iterator is exhausted, you get the item that couldn't be sent
+    /// back in the [`SendError`], alongside how many were sent. Pass `&mut iterator` to keep
+    /// the rest for later.
+    pub fn send_all(
+        &self,
+        items: impl IntoIterator<Item = T>,
+    ) -> Result<usize, (usize, SendError<T>)> {
+        let mut sent = 0;
+        for item in items {
+            self.send(item).map_err(|e| (sent, e))?;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    /// Push items from an iterator into the send buffer, and wake the Engine once at the
+    /// end. Returns how many items were accepted.
+    ///
+    /// This is for bulk publishing, e.g., replaying a file into the channel. The Engine
+    /// absorbs the whole batch in one pass instead of being woken for each item, as with
+    /// [`Sender::send_all`]. It stops at the first item that doesn't fit, or once the
+    /// Sender is closed, and gives that item back alongside how many were accepted. Pass
+    /// `&mut iterator` to keep the rest for later.
+    pub fn extend(
+        &self,
+        items: impl IntoIterator<Item = T>,
+    ) -> Result<usize, (usize, SendError<T>)> {
+        let mut accepted = 0;
+        let mut result = Ok(());
+        for item in items {
+            if let Err(e) = self.shared.push(item, false) {
+                result = Err(e);
+                break;
+            }
+            accepted += 1;
+        }
+        if 0 < accepted {
+            self.shared.waker.wake();
+        }
+        result.map(|()| accepted).map_err(|e| (accepted, e))
+    }
+
+    /// Send every item from a stream, waiting for room in the send buffer as needed.
+    /// Returns how many items were sent.
+    ///
+    /// This stops early if the Sender is closed or the splaycast Engine is dropped. Then you
+    /// get the item that could not be sent back, alongside how many were sent. Pass
+    /// `&mut stream` to keep the rest for later.
+    pub async fn send_stream(&self, items: impl Stream<Item = T>) -> Result<usize, (usize, T)> {
+        let mut items = pin!(items);
+        let mut sent = 0;
+        while let Some(item) = items.next().await {
+            if let Err(e) = SendWhenReady::new(self, item).await {
+                log::debug!("sender closed while sending a stream");
+                return Err((sent, e.into_inner()));
+            }
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    /// Send a value, waiting up to `timeout` for room in the send buffer.
+    ///
+    /// This lets a producer under load make a bounded-latency decision to drop an item,
+    /// rather than waiting indefinitely. If there's still no room when the timeout elapses,
+    /// you get your value back in [`SendError::Full`]. If the Sender is closed, or the
+    /// splaycast Engine has been dropped, you get it back in [`SendError::Closed`].
+    #[cfg(feature = "tokio")]
+    pub async fn send_timeout(
+        &self,
+        item: T,
+        timeout: std::time::Duration,
+    ) -> Result<(), SendError<T>> {
+        let mut send = SendWhenReady::new(self, item);
+        let sleep = pin!(tokio::time::sleep(timeout));
+        match futures::future::select(&mut send, sleep).await {
+            futures::future::Either::Left((result, _)) => result,
+            futures::future::Either::Right(_) => {
+                log::debug!("send timed out");
+                match send.item.take() {
+                    Some(item) => Err(SendError::Full(item)),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
+    /// Send a value, retrying with backoff while the send buffer is full.
+    ///
+    /// Rather than sleeping blindly between attempts, this waits for the Engine to drain
+    /// an item from the send buffer, bounded by the backoff. If there's still no room when
+    /// the policy's timeout elapses, you get your value back in [`SendError::Full`]. If the
+    /// Sender is closed, you get it back in [`SendError::Closed`].
+    #[cfg(feature = "tokio")]
+    pub async fn send_with_retry(
+        &self,
+        item: T,
+        policy: crate::RetryPolicy,
+    ) -> Result<(), SendError<T>> {
+        let deadline = tokio::time::Instant::now() + policy.timeout;
+        let mut backoff = policy.initial_backoff;
+        let mut item = item;
+        loop {
+            item = match self.send(item) {
+                Ok(()) => return Ok(()),
+                Err(SendError::Full(item)) => item,
+                Err(closed) => return Err(closed),
+            };
+            let now = tokio::time::Instant::now();
+            if deadline <= now {
+                log::debug!("send retries timed out");
+                return Err(SendError::Full(item));
+            }
+            let sleep = pin!(tokio::time::sleep(backoff.min(deadline - now)));
+            futures::future::select(DrainNotified::new(self), sleep).await;
+            backoff = policy.next_backoff(backoff);
+        }
+    }
+
+    /// How many items are waiting in the send buffer for the Engine to pick them up,
+    /// including the priority lane.
+    ///
+    /// This is informational, and may be stale before it even returns: the Engine drains
+    /// the send buffer concurrently.
+    pub fn len(&self) -> usize {
+        self.shared.queue.len() + self.shared.priority_queue.len()
+    }
+
+    /// Whether the send buffer and the priority lane are currently empty.
+    pub fn is_empty(&self) -> bool {
+        self.shared.queue.is_empty() && self.shared.priority_queue.is_empty()
+    }
+
+    /// The maximum number of items the send buffer can hold, not counting the priority
+    /// lane. This is `usize::MAX` for an unbounded Sender.
+    pub fn capacity(&self) -> usize {
+        self.shared.queue.capacity()
+    }
+
+    /// Whether the send buffer is currently full. If it is, `send` will give your value back
+    /// in [`SendError::Full`]. The priority lane has its own room, so `send_priority` may
+    /// still succeed.
+    pub fn is_full(&self) -> bool {
+        self.shared.queue.is_full()
+    }
+
+    /// Whether [`Sender::close`] has been called.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_closed.load(Ordering::Acquire)
+    }
+
+    /// Get a Sender and the Stream that it feeds, without a splaycast.
+    ///
+    /// This is a lightweight, waker-correct single-producer queue to Stream adapter. It is
+    /// what [`crate::channel`] uses, and you can use it anywhere you need one, e.g., to feed
+    /// something that later feeds a splaycast.
+    ///
+    /// A `capacity` of 0 is treated as 1: the queue always has room for an item.
+    pub fn pair_with_capacity(capacity: usize) -> (Self, SenderStream<T>) {
+        Self::new(capacity.max(1))
+    }
+
     pub(crate) fn new(buffer_size: usize) -> (Self, SenderStream<T>) {
-        let queue = Arc::new(ArrayQueue::new(buffer_size));
-        let waker = Arc::new(AtomicWaker::new());
+        Self::with_queue(SendQueue::Bounded(ArrayQueue::new(buffer_size)))
+    }
+
+    pub(crate) fn new_unbounded() -> (Self, SenderStream<T>) {
+        Self::with_queue(SendQueue::Unbounded(SegQueue::new()))
+    }
+
+    fn with_queue(queue: SendQueue<T>) -> (Self, SenderStream<T>) {
+        let shared = Arc::new(SenderShared {
+            queue,
+            priority_queue: ArrayQueue::new(PRIORITY_LANE_CAPACITY),
+            waker: AtomicWaker::new(),
+            is_closed: AtomicBool::new(false),
+            in_flight: AtomicUsize::new(0),
+            drain_wakers: SegQueue::new(),
+        });
         (
             Self {
-                queue: queue.clone(),
-                waker: waker.clone(),
+                shared: shared.clone(),
+            },
+            SenderStream {
+                shared,
+                is_terminated: false,
             },
-            SenderStream { queue, waker },
         )
     }
 }
 
+/// The receiving end of a [`Sender`]: a Stream of the items that were sent to it.
+///
+/// The stream ends once the Sender is closed and everything it sent has been drained.
 pub struct SenderStream<T> {
-    queue: Arc<ArrayQueue<T>>,
-    waker: Arc<AtomicWaker>,
+    shared: Arc<SenderShared<T>>,
+    is_terminated: bool,
+}
+
+impl<T> SenderStream<T> {
+    /// How many items are waiting to be drained from this stream.
+    pub fn len(&self) -> usize {
+        self.shared.queue.len() + self.shared.priority_queue.len()
+    }
+
+    /// Whether there are no items waiting to be drained from this stream.
+    pub fn is_empty(&self) -> bool {
+        self.shared.queue.is_empty() && self.shared.priority_queue.is_empty()
+    }
+
+    /// Stop accepting items from the Sender, as if [`Sender::close`] had been called.
+    ///
+    /// Items that were already sent are still yielded before the stream ends.
+    pub fn close(&self) {
+        log::debug!("closing sender stream");
+        self.shared.is_closed.store(true, Ordering::SeqCst);
+        self.shared.wake_drain_waiters();
+    }
 }
 
 impl<T> Stream for SenderStream<T> {
     type Item = T;
 
-    fn poll_next(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.waker.register(context.waker());
-        match self.queue.pop() {
-            Some(more) => Poll::Ready(Some(more)),
-            None => Poll::Pending, // already waiting for the waker, possibly even already woken
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.is_terminated {
+            return Poll::Ready(None);
+        }
+        self.shared.waker.register(context.waker());
+        match self.shared.pop() {
+            Some(more) => {
+                self.shared.wake_drain_waiters();
+                Poll::Ready(Some(more))
+            }
+            None => {
+                if self.shared.is_closed.load(Ordering::SeqCst) {
+                    if 0 < self.shared.in_flight.load(Ordering::SeqCst) {
+                        // A push passed the close check before the close. It wakes us when
+                        // it lands, or when it gives up.
+                        return Poll::Pending;
+                    }
+                    // A send may have landed between the pop and the close check.
+                    match self.shared.pop() {
+                        Some(more) => Poll::Ready(Some(more)),
+                        None => {
+                            self.is_terminated = true;
+                            Poll::Ready(None)
+                        }
+                    }
+                } else {
+                    Poll::Pending // already waiting for the waker, possibly even already woken
+                }
+            }
+        }
+    }
+}
+
+impl<T> FusedStream for SenderStream<T> {
+    fn is_terminated(&self) -> bool {
+        self.is_terminated
+    }
+}
+
+impl<T> Drop for SenderStream<T> {
+    fn drop(&mut self) {
+        // Nobody is going to drain the queue anymore, so don't leave senders waiting for room.
+        self.shared.is_closed.store(true, Ordering::SeqCst);
+        self.shared.wake_drain_waiters();
+    }
+}
+
+/// Resolves when the SenderStream next drains, or there's room in the send buffer already.
+#[cfg(feature = "tokio")]
+struct DrainNotified<'a, T> {
+    sender: &'a Sender<T>,
+    waiter: Option<Arc<DrainWaiter>>,
+}
+
+#[cfg(feature = "tokio")]
+impl<'a, T> DrainNotified<'a, T> {
+    fn new(sender: &'a Sender<T>) -> Self {
+        Self {
+            sender,
+            waiter: None,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> Future for DrainNotified<'_, T> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.waiter.is_some() || !self.sender.is_full() || self.sender.is_closed() {
+            return Poll::Ready(());
+        }
+        let Self { sender, waiter } = &mut *self;
+        sender.shared.register_drain_waiter(waiter, context.waker());
+        // The queue may have drained before we registered.
+        if self.sender.is_full() {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+/// Waits for room in the send buffer, then sends.
+struct SendWhenReady<'a, T> {
+    sender: &'a Sender<T>,
+    item: Option<T>,
+    waiter: Option<Arc<DrainWaiter>>,
+}
+
+impl<'a, T> SendWhenReady<'a, T> {
+    fn new(sender: &'a Sender<T>, item: T) -> Self {
+        Self {
+            sender,
+            item: Some(item),
+            waiter: None,
+        }
+    }
+}
+
+impl<T> Unpin for SendWhenReady<'_, T> {}
+
+impl<T> Future for SendWhenReady<'_, T> {
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        #[allow(clippy::expect_used)]
+        let item = self.item.take().expect("polled after completion");
+        let item = match self.sender.send(item) {
+            Ok(()) => return Poll::Ready(Ok(())),
+            Err(SendError::Full(item)) => item,
+            Err(closed) => return Poll::Ready(Err(closed)),
+        };
+
+        let Self { sender, waiter, .. } = &mut *self;
+        sender.shared.register_drain_waiter(waiter, context.waker());
+        // The queue may have drained before we registered, so try once more.
+        match self.sender.send(item) {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(SendError::Full(item)) => {
+                self.item = Some(item);
+                Poll::Pending
+            }
+            Err(closed) => Poll::Ready(Err(closed)),
         }
     }
 }
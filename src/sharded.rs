@@ -0,0 +1,86 @@
+use std::{
+    collections::hash_map::RandomState,
+    future::Future,
+    hash::{BuildHasher, Hash},
+};
+
+use futures::{channel::mpsc, SinkExt, StreamExt};
+
+use crate::{Message, Receiver, SubscriptionMode};
+
+/// How many entries each worker may have waiting before the Receiver waits for it.
+const WORKER_BACKLOG: usize = 16;
+
+impl<Item, Mode> Receiver<Item, Mode>
+where
+    Item: Clone + Send + 'static,
+    Mode: SubscriptionMode,
+{
+    /// Consume this Receiver with `n_workers` tokio tasks, running `handler` on each entry.
+    ///
+    /// Entries are routed to a worker by the hash of `key_fn(&entry)`, so entries with
+    /// the same key are handled one at a time, in order, while different keys are handled
+    /// in parallel. Each worker has a small backlog; when a worker falls behind, the
+    /// Receiver waits for it, and the splaycast reports lag as usual.
+    ///
+    /// The handler only sees entries. Lag and resets are logged and skipped.
+    ///
+    /// This resolves once the splaycast terminates and every worker has finished its
+    /// backlog. If a handler panics, the remaining workers are drained and the panic is
+    /// resumed here.
+    ///
+    /// Workers are spawned with `tokio::spawn`, so this must run on a tokio runtime.
+    pub async fn sharded_process<Key, KeyFn, Handler, HandlerFuture>(
+        mut self,
+        n_workers: usize,
+        mut key_fn: KeyFn,
+        handler: Handler,
+    ) where
+        Key: Hash,
+        KeyFn: FnMut(&Item) -> Key,
+        Handler: Fn(Item) -> HandlerFuture + Clone + Send + 'static,
+        HandlerFuture: Future<Output = ()> + Send + 'static,
+    {
+        let n_workers = n_workers.max(1);
+        let (mut shards, workers): (Vec<_>, Vec<_>) = (0..n_workers)
+            .map(|_| {
+                let (shard, mut entries) = mpsc::channel::<Item>(WORKER_BACKLOG);
+                let handler = handler.clone();
+                let worker = tokio::spawn(async move {
+                    while let Some(item) = entries.next().await {
+                        handler(item).await;
+                    }
+                });
+                (shard, worker)
+            })
+            .unzip();
+
+        let hasher = RandomState::new();
+        while let Some(message) = self.next().await {
+            match message {
+                Message::Entry { item } => {
+                    let shard = (hasher.hash_one(key_fn(&item)) % n_workers as u64) as usize;
+                    if shards[shard].send(item).await.is_err() {
+                        log::warn!("sharded worker {shard} is gone, stopping");
+                        break;
+                    }
+                }
+                Message::Lagged { count } => log::debug!("sharded receiver lagged {count}"),
+                Message::Reset { epoch } => log::debug!("sharded receiver reset to {epoch}"),
+            }
+        }
+
+        drop(shards);
+        let mut panic = None;
+        for worker in workers {
+            match worker.await {
+                Ok(()) => (),
+                Err(e) if e.is_panic() => panic = panic.or(Some(e.into_panic())),
+                Err(e) => log::warn!("sharded worker did not finish: {e}"),
+            }
+        }
+        if let Some(panic) = panic {
+            std::panic::resume_unwind(panic);
+        }
+    }
+}
@@ -1,28 +1,113 @@
 use std::{
     collections::VecDeque,
-    sync::{
-        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
-        Arc,
-    },
-    task::Context,
+    sync::{Arc, Weak},
+    task::{Context, Waker},
+    time::Instant,
 };
 
-use arc_swap::ArcSwap;
+use crate::sync::{fence, AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+use arc_swap::{ArcSwap, ArcSwapOption};
 use crossbeam_queue::SegQueue;
 use futures::task::AtomicWaker;
 
-use crate::SplaycastEntry;
+use crate::{
+    lag_events::{LagEvent, LagMonitor},
+    receiver_close::{OnReceiverClosed, ReceiverClosed},
+    receiver_directory::{ReceiverDirectory, Registration},
+    subscribe_permit::SubscribePermit,
+    ChannelMetadata, CloseReason, SplaycastEntry, SubscribeError,
+};
 
 /// Shared, lock-free state for splaying out notifications to receiver streams from an upstream stream.
+///
+/// The receiver directory is locked by handles only: receivers and the Engine never wait on it.
 pub struct Shared<Item> {
+    // sync(Relaxed): a unique id counter; nothing is published through it.
     next_receiver_id: AtomicU64,
+    // sync(Relaxed): a statistic and wait condition. Count waiters are woken separately.
     subscriber_count: Arc<AtomicUsize>,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    peak_subscriber_count: AtomicUsize,
+    // sync(Relaxed): stored by the Engine after each queue swap. A stale read starts a
+    // head joiner at an older id, which the queue still covers or reports as lag.
     subscribe_sequence: AtomicU64,
+    // sync(Release, Acquire): stored by the Engine after it swaps in the queue that it
+    // describes, so a tail joiner that Acquires it also sees that queue.
     subscribe_tail_sequence: AtomicU64,
+    // sync(Release, Acquire): raised by unpin_through and cleared by start_epoch, and
+    // Acquired by receivers before they skip unpinned entries.
+    unpinned_through: AtomicU64,
+    // sync(Release, Acquire): stored by start_epoch before the Engine swaps in the new
+    // epoch's buffer. Receivers Acquire it when the buffer is empty.
+    epoch: AtomicU64,
+    // sync(Release, AcqRel): request_reset stores it before waking the Engine, which takes
+    // it with a swap.
+    reset_requested: AtomicBool,
+    // sync(Release, AcqRel): request_flush stores it before waking the Engine, which takes
+    // it with a swap.
+    flush_requested: AtomicBool,
+    /// Items injected from a handle with [`crate::Splaycast::announce`], for the Engine.
+    announcements: SegQueue<Item>,
     wakers: Arc<SegQueue<(u64, WakeHandle)>>,
+    /// Tasks waiting on the subscriber count, woken whenever it changes.
+    count_waiters: SegQueue<Waker>,
+    /// Tasks waiting on receiver positions, woken whenever one moves.
+    position_waiters: SegQueue<Waker>,
+    /// How many tasks are in `position_waiters`, so moving receivers can skip the queue.
+    // sync(Relaxed): counted in before the push and out after the pop. Waiters re-check
+    // the positions after registering, so a stale 0 only skips an empty queue.
+    position_waiter_count: AtomicUsize,
     queue: Arc<ArcSwap<VecDeque<SplaycastEntry<Item>>>>,
     waker: AtomicWaker,
+    /// Woken by receivers parking or closing. It is the Engine's waker, except while the
+    /// Engine is driven inline: then it only flags the Engine for the next send.
+    receiver_waker: AtomicWaker,
+    /// While the Engine is driven inline, a receiver's wake doesn't poll it, so a parking
+    /// receiver checks for entries it raced by itself.
+    // sync(Relaxed): set before the channel is shared, and only ever cleared. A stale read
+    // only costs a parking receiver a redundant check, which SeqCst fences order.
+    inline: AtomicBool,
+    // sync(SeqCst, Acquire): set_dead stores it before waking everyone, so whoever
+    // Acquires it also sees the channel's final state. The store and add_lag_monitor's load
+    // are SeqCst, like arc_swap's, so a monitor added as the channel dies is still closed.
     is_dead: AtomicBool,
+    // sync(Release, Acquire): stored by seal and unseal, and Acquired on subscribe.
+    is_sealed: AtomicBool,
+    // sync(Relaxed): a limit checked on subscribe. A subscribe that races a change may be
+    // checked against the old limit.
+    max_subscribers: AtomicUsize,
+    /// How many Splaycast handles are alive. The last one to drop kills the channel.
+    // sync(Relaxed, AcqRel, Acquire): like Arc, cloning a live handle only needs Relaxed.
+    // Releases are AcqRel so the handle that kills the channel sees every other handle's
+    // writes, and try_acquire_handle pairs with them.
+    handle_count: AtomicUsize,
+    /// Once detached, dropping handles no longer kills the channel.
+    // sync(Release, Acquire): detach stores it, and release_handle Acquires it after the
+    // count drops.
+    is_detached: AtomicBool,
+    /// Per-receiver controls, by receiver id, for acting on one receiver from a handle.
+    receivers: ReceiverDirectory<Item>,
+    /// Receivers joining and leaving, for the Engine's own map of them. The Engine shares
+    /// no lock with receivers, so it doesn't use the directory.
+    registrations: SegQueue<Registration<Item>>,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    abandoned_receivers: AtomicU64,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    abandoned_entries: AtomicU64,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    poisoned_receivers: AtomicU64,
+    stats: Arc<StatsCounters>,
+    /// Streams from [`crate::Splaycast::lag_events`], swapped in whole so that receivers
+    /// reporting lag never lock.
+    lag_monitors: ArcSwap<Vec<Weak<LagMonitor>>>,
+    channel_metadata: ChannelMetadata,
+    /// Closed and expired receivers whose parked wakers the Engine should drop.
+    closed_receivers: SegQueue<u64>,
+    /// Deadlines of expiring receivers, for the Engine to release them on time.
+    #[cfg(feature = "tokio")]
+    expiries: SegQueue<(tokio::time::Instant, u64)>,
+    on_receiver_closed: ArcSwapOption<OnReceiverClosed>,
 }
 
 impl<Item> std::fmt::Debug for Shared<Item>
@@ -40,28 +125,304 @@ impl<Item> Shared<Item>
 where
     Item: Clone,
 {
-    pub fn new() -> Self {
+    pub fn new(channel_metadata: ChannelMetadata) -> Self {
         Self {
             next_receiver_id: Default::default(),
             subscriber_count: Default::default(),
+            peak_subscriber_count: Default::default(),
             subscribe_sequence: AtomicU64::new(1),
             subscribe_tail_sequence: AtomicU64::new(1),
+            unpinned_through: AtomicU64::new(0),
+            epoch: AtomicU64::new(0),
+            reset_requested: AtomicBool::new(false),
+            flush_requested: AtomicBool::new(false),
+            announcements: SegQueue::new(),
             wakers: Arc::new(SegQueue::new()),
+            count_waiters: SegQueue::new(),
+            position_waiters: SegQueue::new(),
+            position_waiter_count: AtomicUsize::new(0),
             queue: Arc::new(ArcSwap::from_pointee(VecDeque::new())),
             waker: Default::default(),
+            receiver_waker: Default::default(),
+            inline: Default::default(),
             is_dead: Default::default(),
+            is_sealed: Default::default(),
+            max_subscribers: AtomicUsize::new(usize::MAX),
+            handle_count: AtomicUsize::new(1),
+            is_detached: Default::default(),
+            receivers: ReceiverDirectory::new(),
+            registrations: SegQueue::new(),
+            abandoned_receivers: Default::default(),
+            abandoned_entries: Default::default(),
+            poisoned_receivers: Default::default(),
+            stats: Arc::new(StatsCounters {
+                engine_alive: AtomicBool::new(true),
+                ..Default::default()
+            }),
+            lag_monitors: Default::default(),
+            channel_metadata,
+            closed_receivers: SegQueue::new(),
+            on_receiver_closed: Default::default(),
+            #[cfg(feature = "tokio")]
+            expiries: SegQueue::new(),
         }
     }
 
+    pub fn channel_metadata(&self) -> &ChannelMetadata {
+        &self.channel_metadata
+    }
+
     pub fn set_dead(&self) {
-        self.is_dead.store(true, Ordering::Release);
+        self.is_dead.store(true, Ordering::SeqCst);
         self.waker.wake(); // Make sure the Engine runs promptly
+        self.wake_count_waiters();
+        self.lag_monitors
+            .load()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .for_each(|monitor| monitor.close());
+    }
+
+    pub fn add_lag_monitor(&self, capacity: usize) -> Arc<LagMonitor> {
+        let monitor = Arc::new(LagMonitor::new(capacity));
+        self.lag_monitors.rcu(|monitors| {
+            let mut monitors: Vec<_> = monitors
+                .iter()
+                .filter(|monitor| monitor.strong_count() != 0)
+                .cloned()
+                .collect();
+            monitors.push(Arc::downgrade(&monitor));
+            monitors
+        });
+        // Either set_dead sees this monitor, or this sees that the channel is dead.
+        if self.is_dead.load(Ordering::SeqCst) {
+            monitor.close();
+        }
+        monitor
+    }
+
+    /// Count a receiver's lag, and pass it on to the lag monitors.
+    pub fn report_lag(&self, receiver_id: u64, label: Option<Arc<str>>, count: usize) {
+        self.stats.lag_events.fetch_add(1, Ordering::Relaxed);
+        let monitors = self.lag_monitors.load();
+        if monitors.is_empty() {
+            return;
+        }
+        let event = LagEvent {
+            receiver_id,
+            label,
+            count,
+            at: Instant::now(),
+        };
+        // Monitors whose stream was dropped are pruned when the next one is added.
+        monitors
+            .iter()
+            .filter_map(Weak::upgrade)
+            .for_each(|monitor| monitor.report(event.clone()));
+    }
+
+    pub fn register_count_waiter(&self, waker: &Waker) {
+        self.count_waiters.push(waker.clone());
+    }
+
+    /// Wake on the next receiver position change, subscriber count change, or termination.
+    pub fn register_position_waiter(&self, waker: &Waker) {
+        self.position_waiter_count.fetch_add(1, Ordering::Relaxed);
+        self.position_waiters.push(waker.clone());
+        self.count_waiters.push(waker.clone());
+    }
+
+    pub fn wake_position_waiters(&self) {
+        if self.position_waiter_count.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        while let Some(waker) = self.position_waiters.pop() {
+            self.position_waiter_count.fetch_sub(1, Ordering::Relaxed);
+            waker.wake();
+        }
+    }
+
+    fn wake_count_waiters(&self) {
+        while let Some(waker) = self.count_waiters.pop() {
+            waker.wake();
+        }
     }
 
     pub fn is_dead(&self) -> bool {
         self.is_dead.load(Ordering::Acquire)
     }
 
+    pub fn acquire_handle(&self) {
+        self.handle_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Take a new handle, but only if the channel is still alive.
+    pub fn try_acquire_handle(&self) -> bool {
+        !self.is_dead()
+            && self
+                .handle_count
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                    (0 < count || self.is_detached()).then_some(count + 1)
+                })
+                .is_ok()
+    }
+
+    /// Returns true if this was the last handle, and the channel should die with it.
+    pub fn release_handle(&self) -> bool {
+        self.handle_count.fetch_sub(1, Ordering::AcqRel) == 1 && !self.is_detached()
+    }
+
+    pub fn detach(&self) {
+        self.is_detached.store(true, Ordering::Release);
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.is_detached.load(Ordering::Acquire)
+    }
+
+    pub fn register_receiver(
+        &self,
+        receiver_id: u64,
+        next_message_id: u64,
+    ) -> Arc<ReceiverControl<Item>> {
+        let control = Arc::new(ReceiverControl::default());
+        control.set_position(next_message_id);
+        self.register(Registration::Joined(receiver_id, control.clone()));
+        control
+    }
+
+    pub fn deregister_receiver(&self, receiver_id: u64) {
+        self.register(Registration::Left(receiver_id));
+    }
+
+    /// Give up a receiver's place: it no longer counts as a subscriber, and its permit goes
+    /// back. Only whoever claimed the release on its control does this.
+    pub fn release_receiver(
+        &self,
+        receiver_id: u64,
+        control: &ReceiverControl<Item>,
+        epoch: u64,
+        next_message_id: u64,
+    ) {
+        self.record_abandoned(epoch, next_message_id);
+        self.deregister_receiver(receiver_id);
+        // The Engine holds on to the control until it catches up with this.
+        control.forget_waker();
+        self.decrement_subscriber_count();
+        drop(control.take_permit());
+    }
+
+    /// Have the Engine release the receiver at `deadline`, even if nothing polls it.
+    #[cfg(feature = "tokio")]
+    pub fn expire_at(&self, receiver_id: u64, deadline: tokio::time::Instant) {
+        self.expiries.push((deadline, receiver_id));
+        self.waker.wake();
+    }
+
+    #[cfg(feature = "tokio")]
+    pub fn take_expiry(&self) -> Option<(tokio::time::Instant, u64)> {
+        self.expiries.pop()
+    }
+
+    /// Tell the directory and the Engine that a receiver joined or left.
+    fn register(&self, registration: Registration<Item>) {
+        self.receivers.register(registration.clone());
+        self.registrations.push(registration);
+    }
+
+    pub fn take_registration(&self) -> Option<Registration<Item>> {
+        self.registrations.pop()
+    }
+
+    pub fn receiver_cursors(&self) -> Vec<ReceiverCursor> {
+        let next_head = self.subscribe_sequence_number();
+        let mut cursors: Vec<_> = self
+            .receivers
+            .lock()
+            .iter()
+            .map(|(receiver_id, control)| {
+                let next_message_id = control.position();
+                ReceiverCursor {
+                    receiver_id: *receiver_id,
+                    label: control.label(),
+                    next_message_id,
+                    lag_behind_head: next_head.saturating_sub(next_message_id),
+                    lag_events: control.lag_events(),
+                    entries_skipped: control.entries_skipped(),
+                }
+            })
+            .collect();
+        cursors.sort_unstable_by_key(|cursor| cursor.receiver_id);
+        cursors
+    }
+
+    /// How far the slowest receiver is behind the head, as of its last poll.
+    pub fn slowest_backlog(&self) -> usize {
+        let next_head = self.subscribe_sequence_number();
+        self.receivers
+            .lock()
+            .values()
+            .map(|control| next_head.saturating_sub(control.position()))
+            .max()
+            .unwrap_or_default() as usize
+    }
+
+    /// Returns false if there is no such receiver.
+    pub fn disconnect(&self, receiver_id: u64) -> bool {
+        match self.receivers.lock().get(&receiver_id) {
+            Some(control) => {
+                control.disconnect();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Queue `item` for one receiver. Gives it back if there is no such receiver.
+    pub fn send_to(&self, receiver_id: u64, item: Item) -> Result<(), Item> {
+        match self.receivers.lock().get(&receiver_id) {
+            Some(control) if !control.is_disconnected() => {
+                control.send_direct(item);
+                Ok(())
+            }
+            _ => Err(item),
+        }
+    }
+
+    pub fn set_max_subscribers(&self, max_subscribers: usize) {
+        self.max_subscribers
+            .store(max_subscribers, Ordering::Relaxed);
+    }
+
+    /// Whether `joining` more subscribers fit under the limit.
+    pub fn has_room_for(&self, joining: usize) -> bool {
+        self.subscriber_count().saturating_add(joining)
+            <= self.max_subscribers.load(Ordering::Relaxed)
+    }
+
+    pub fn seal(&self) {
+        self.is_sealed.store(true, Ordering::Release);
+    }
+
+    pub fn unseal(&self) {
+        self.is_sealed.store(false, Ordering::Release);
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.is_sealed.load(Ordering::Acquire)
+    }
+
+    /// Whether `joining` more subscribers may subscribe right now.
+    pub fn check_admission(&self, joining: usize) -> Result<(), SubscribeError> {
+        if self.is_sealed() {
+            return Err(SubscribeError::Sealed);
+        }
+        if !self.has_room_for(joining) {
+            return Err(SubscribeError::AtCapacity);
+        }
+        Ok(())
+    }
+
     pub fn next_receiver_id(&self) -> u64 {
         self.next_receiver_id.fetch_add(1, Ordering::Relaxed)
     }
@@ -71,17 +432,85 @@ where
         self.subscriber_count.load(Ordering::Relaxed)
     }
 
+    pub fn peak_subscriber_count(&self) -> usize {
+        self.peak_subscriber_count.load(Ordering::Relaxed)
+    }
+
     #[inline]
     pub fn increment_subscriber_count(&self) -> usize {
         let count = self.subscriber_count.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak_subscriber_count
+            .fetch_max(count, Ordering::Relaxed);
         log::trace!("incrementing subscriber count to {count}");
+        self.wake_count_waiters();
         count
     }
 
+    /// Account for a receiver that was dropped while it was behind the head.
+    pub fn record_abandoned(&self, epoch: u64, next_message_id: u64) {
+        if epoch != self.epoch() {
+            return; // Positions from before a reset don't compare to the head.
+        }
+        let backlog = self
+            .subscribe_sequence_number()
+            .saturating_sub(next_message_id);
+        if 0 < backlog {
+            log::trace!("receiver abandoned {backlog} entries");
+            self.abandoned_receivers.fetch_add(1, Ordering::Relaxed);
+            self.abandoned_entries.fetch_add(backlog, Ordering::Relaxed);
+        }
+    }
+
+    pub fn abandoned_backlog(&self) -> AbandonedBacklog {
+        AbandonedBacklog {
+            receivers: self.abandoned_receivers.load(Ordering::Relaxed),
+            entries: self.abandoned_entries.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Count a receiver whose waker panicked. The Engine disconnects it.
+    pub fn count_poisoned(&self) {
+        self.poisoned_receivers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_on_receiver_closed(&self, on_receiver_closed: OnReceiverClosed) {
+        self.on_receiver_closed
+            .store(Some(Arc::new(on_receiver_closed)));
+    }
+
+    /// Count a receiver that was closed with a reason, pass it to the hook, and have the
+    /// Engine drop its parked waker.
+    pub fn record_close(&self, closed: ReceiverClosed) {
+        self.stats.closed_receivers.fetch_add(1, Ordering::Relaxed);
+        if closed.reason == CloseReason::Failed {
+            self.stats.failed_receivers.fetch_add(1, Ordering::Relaxed);
+        }
+        self.forget_parked_waker(closed.receiver_id);
+        if let Some(on_receiver_closed) = self.on_receiver_closed.load_full() {
+            on_receiver_closed(closed);
+        }
+    }
+
+    /// Have the Engine drop a receiver's parked waker, instead of holding it until the next
+    /// publish.
+    pub fn forget_parked_waker(&self, receiver_id: u64) {
+        self.closed_receivers.push(receiver_id);
+        self.receiver_waker.wake();
+    }
+
+    pub fn take_closed_receiver(&self) -> Option<u64> {
+        self.closed_receivers.pop()
+    }
+
+    pub fn poisoned_receivers(&self) -> u64 {
+        self.poisoned_receivers.load(Ordering::Relaxed)
+    }
+
     #[inline]
     pub fn decrement_subscriber_count(&self) -> usize {
         let count = self.subscriber_count.fetch_sub(1, Ordering::Relaxed) - 1;
         log::trace!("decrementing subscriber count to {count}");
+        self.wake_count_waiters();
         count
     }
 
@@ -90,6 +519,25 @@ where
         self.queue.load()
     }
 
+    #[inline]
+    pub(crate) fn load_queue_full(&self) -> Arc<VecDeque<SplaycastEntry<Item>>> {
+        self.queue.load_full()
+    }
+
+    #[inline]
+    pub(crate) fn buffer_len(&self) -> usize {
+        self.queue.load().len()
+    }
+
+    #[inline]
+    pub(crate) fn buffer_sequence_range(&self) -> Option<(u64, u64)> {
+        let queue = self.queue.load();
+        queue
+            .front()
+            .zip(queue.back())
+            .map(|(oldest, newest)| (oldest.id, newest.id))
+    }
+
     #[inline]
     pub(crate) fn swap_queue(
         &self,
@@ -100,13 +548,22 @@ where
             self.queue.load().len(),
             next.len()
         );
-        let first_sequence_number = next.front().map(|item| item.id).unwrap_or(0);
+        // Tail subscribers start 1 past the oldest entry to help win join races without lag,
+        // unless the oldest entry is pinned or a summary: those are there for joiners to see.
+        // Deferred entries are on their way out, so joiners start after them.
         let last_sequence_number = next.back().map(|item| item.id).unwrap_or(0);
+        let tail_sequence_number = match next.iter().find(|item| !item.is_deferred()) {
+            Some(item) if item.is_pinned() || item.is_summary() => item.id,
+            Some(item) => item.id + 1,
+            None if next.is_empty() => 1,
+            None => last_sequence_number + 1,
+        };
+        self.stats.buffered.store(next.len(), Ordering::Relaxed);
         let previous = self.queue.swap(Arc::new(next));
         self.subscribe_sequence
             .store(last_sequence_number + 1, Ordering::Relaxed);
         self.subscribe_tail_sequence
-            .store(first_sequence_number + 1, Ordering::Release);
+            .store(tail_sequence_number, Ordering::Release);
         previous
     }
 
@@ -120,6 +577,76 @@ where
         self.subscribe_tail_sequence.load(Ordering::Acquire)
     }
 
+    /// Entries at or below this id are no longer pinned.
+    #[inline]
+    pub(crate) fn unpinned_through(&self) -> u64 {
+        self.unpinned_through.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub(crate) fn unpin_through(&self, id: u64) {
+        self.unpinned_through.fetch_max(id, Ordering::Release);
+    }
+
+    /// The epoch of the current buffer. Only consult this when the buffer is empty:
+    /// otherwise, the entries carry their epoch.
+    #[inline]
+    pub(crate) fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub(crate) fn request_flush(&self) {
+        self.flush_requested.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+
+    /// Called by the Engine when it flushes. Returns true if a flush was requested.
+    #[inline]
+    pub(crate) fn take_flush_request(&self) -> bool {
+        self.flush_requested.swap(false, Ordering::AcqRel)
+    }
+
+    /// Empty the buffer without touching the sequence: the next entry published still gets
+    /// the next id, and every subscriber starts there.
+    pub(crate) fn flush_queue(&self) -> Arc<VecDeque<SplaycastEntry<Item>>> {
+        log::trace!("flush queue length {}", self.queue.load().len());
+        self.stats.buffered.store(0, Ordering::Relaxed);
+        let previous = self.queue.swap(Arc::new(VecDeque::new()));
+        self.subscribe_tail_sequence
+            .store(self.subscribe_sequence_number(), Ordering::Release);
+        previous
+    }
+
+    pub(crate) fn request_reset(&self) {
+        self.reset_requested.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+
+    pub(crate) fn announce(&self, item: Item) {
+        self.announcements.push(item);
+        self.waker.wake();
+    }
+
+    /// Called by the Engine as it absorbs, to publish announcements in order.
+    #[inline]
+    pub(crate) fn take_announcement(&self) -> Option<Item> {
+        self.announcements.pop()
+    }
+
+    /// Called by the Engine when it resets. Returns true if a reset was requested.
+    #[inline]
+    pub(crate) fn take_reset_request(&self) -> bool {
+        self.reset_requested.swap(false, Ordering::AcqRel)
+    }
+
+    /// Called by the Engine before it swaps in the first buffer of a new epoch.
+    #[inline]
+    pub(crate) fn start_epoch(&self, epoch: u64) {
+        self.unpinned_through.store(0, Ordering::Release);
+        self.epoch.store(epoch, Ordering::Release);
+    }
+
     #[inline]
     pub fn register_waker(&self, receiver_id: u64, handle: WakeHandle) {
         log::trace!("register waker at {}", handle.message_id);
@@ -128,16 +655,37 @@ where
             return;
         }
         self.wakers.push((receiver_id, handle));
-        self.waker.wake()
+        self.receiver_waker.wake()
     }
 
+    /// Whether entries past `next_message_id` were published, for a receiver that parked
+    /// on an inline Engine. Either this sees them, or the Engine sees the receiver parked.
+    pub fn published_past(&self, next_message_id: u64) -> bool {
+        fence(Ordering::SeqCst);
+        next_message_id < self.subscribe_sequence_number()
+    }
+
+    pub fn is_inline(&self) -> bool {
+        self.inline.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_inline(&self, inline: bool) {
+        self.inline.store(inline, Ordering::Relaxed);
+    }
+
+    /// Register the Engine's waker, and the waker for receivers to wake it with.
     #[inline]
-    pub fn register_wake_interest(&self, context: &mut Context) {
+    pub fn register_wake_interest(&self, context: &mut Context, receiver_waker: &Waker) {
         self.waker.register(context.waker());
+        self.receiver_waker.register(receiver_waker);
     }
 
     #[inline]
     pub fn drain_wakelist(self: &Arc<Self>) -> impl Iterator<Item = (u64, WakeHandle)> {
+        if self.is_inline() {
+            // Pairs with published_past.
+            fence(Ordering::SeqCst);
+        }
         WakeIterator {
             shared: self.clone(),
         }
@@ -149,6 +697,18 @@ where
             subscriber_count: Arc::downgrade(&self.subscriber_count),
         }
     }
+
+    #[inline]
+    pub fn stats(&self) -> &StatsCounters {
+        &self.stats
+    }
+
+    pub fn stats_handle(&self) -> StatsHandle {
+        StatsHandle {
+            subscriber_count: self.subscriber_count_handle(),
+            stats: Arc::downgrade(&self.stats),
+        }
+    }
 }
 
 struct WakeIterator<T>
@@ -188,19 +748,196 @@ impl WakeHandle {
     }
 
     #[inline]
-    pub fn will_wake(&self, other: &Self) -> bool {
-        self.waker.will_wake(&other.waker)
+    pub fn will_wake(&self, other: &core::task::Waker) -> bool {
+        self.waker.will_wake(other)
+    }
+
+    #[inline]
+    pub fn into_waker(self) -> core::task::Waker {
+        self.waker
+    }
+}
+
+/// The state a handle can use to act on one particular receiver.
+#[derive(Debug)]
+pub struct ReceiverControl<Item> {
+    // sync(Release, Acquire): disconnect and poison store it before waking the receiver,
+    // which Acquires it when it polls.
+    disconnected: AtomicBool,
+    /// The id of the next entry the receiver will yield, as of its last poll.
+    // sync(Relaxed): a receiver's progress, for monitoring. Position waiters are woken
+    // separately.
+    position: AtomicU64,
+    /// Items from [`crate::Splaycast::send_to`], for this receiver only.
+    direct: SegQueue<Item>,
+    /// Woken to deliver out-of-band instructions, since the Engine only wakes for new entries.
+    waker: AtomicWaker,
+    /// See [`crate::Receiver::set_label`].
+    label: ArcSwapOption<Arc<str>>,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    lag_events: AtomicU64,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    entries_skipped: AtomicU64,
+    /// Whoever swaps this first, the receiver or the Engine when it expires, releases it.
+    // sync(AcqRel, Acquire): a one-time claim, like a flag guarding a drop. Checking
+    // whether it was claimed only Acquires it.
+    released: AtomicBool,
+    /// Held for as long as the receiver is subscribed, and returned to its pool on release.
+    permit: ArcSwapOption<SubscribePermit>,
+}
+
+impl<Item> Default for ReceiverControl<Item> {
+    fn default() -> Self {
+        Self {
+            disconnected: Default::default(),
+            position: Default::default(),
+            direct: SegQueue::new(),
+            waker: Default::default(),
+            label: Default::default(),
+            lag_events: Default::default(),
+            entries_skipped: Default::default(),
+            released: Default::default(),
+            permit: Default::default(),
+        }
+    }
+}
+
+impl<Item> ReceiverControl<Item> {
+    pub fn register_waker(&self, waker: &core::task::Waker) {
+        self.waker.register(waker)
+    }
+
+    pub fn forget_waker(&self) {
+        self.waker.take();
+    }
+
+    /// Returns true for the one caller that gets to release the receiver.
+    pub fn claim_release(&self) -> bool {
+        !self.released.swap(true, Ordering::AcqRel)
+    }
+
+    #[cfg(feature = "tokio")]
+    pub fn is_released(&self) -> bool {
+        self.released.load(Ordering::Acquire)
+    }
+
+    pub fn hold_permit(&self, permit: Arc<SubscribePermit>) {
+        self.permit.store(Some(permit));
+    }
+
+    pub fn take_permit(&self) -> Option<Arc<SubscribePermit>> {
+        self.permit.swap(None)
+    }
+
+    pub fn disconnect(&self) {
+        self.disconnected.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+
+    /// Disconnect without waking: the receiver's waker is what failed.
+    pub fn poison(&self) {
+        self.disconnected.store(true, Ordering::Release);
+        drop(self.waker.take());
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::Acquire)
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the position moved. Only its receiver stores it, so this doesn't
+    /// need a swap.
+    pub fn set_position(&self, next_message_id: u64) -> bool {
+        if self.position.load(Ordering::Relaxed) == next_message_id {
+            return false;
+        }
+        self.position.store(next_message_id, Ordering::Relaxed);
+        true
+    }
+
+    pub fn send_direct(&self, item: Item) {
+        self.direct.push(item);
+        self.waker.wake();
+    }
+
+    pub fn take_direct(&self) -> Option<Item> {
+        self.direct.pop()
+    }
+
+    pub fn has_direct(&self) -> bool {
+        !self.direct.is_empty()
+    }
+
+    pub fn set_label(&self, label: Arc<str>) {
+        self.label.store(Some(Arc::new(label)));
+    }
+
+    pub fn label(&self) -> Option<Arc<str>> {
+        self.label.load().as_deref().cloned()
+    }
+
+    pub fn record_lag(&self, lag_events: u64, entries_skipped: u64) {
+        self.lag_events.fetch_add(lag_events, Ordering::Relaxed);
+        self.entries_skipped
+            .fetch_add(entries_skipped, Ordering::Relaxed);
+    }
+
+    pub fn lag_events(&self) -> u64 {
+        self.lag_events.load(Ordering::Relaxed)
+    }
+
+    pub fn entries_skipped(&self) -> u64 {
+        self.entries_skipped.load(Ordering::Relaxed)
     }
 }
 
+/// What receivers walked away from, from [`crate::Splaycast::abandoned_backlog`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AbandonedBacklog {
+    /// How many receivers were dropped while they were behind.
+    pub receivers: u64,
+    /// How many published entries those receivers never yielded, in total.
+    pub entries: u64,
+}
+
+/// Where one receiver is, from [`crate::Splaycast::receiver_cursors`].
+///
+/// Positions are published by each receiver as it polls, so they may be stale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiverCursor {
+    /// See [`crate::Receiver::id`].
+    pub receiver_id: u64,
+    /// See [`crate::Receiver::label`].
+    pub label: Option<Arc<str>>,
+    /// The sequence id of the next entry the receiver will yield.
+    pub next_message_id: u64,
+    /// How many published entries the receiver has not yielded yet.
+    pub lag_behind_head: u64,
+    /// See [`crate::Receiver::lag_events`].
+    pub lag_events: u64,
+    /// See [`crate::Receiver::entries_skipped`].
+    pub entries_skipped: u64,
+}
+
 /// A handle for inspecting the current subscriber count.
 /// Subscriber counts are updated asynchronously, so values may be stale.
 #[derive(Debug, Clone)]
 pub struct SubscriberCountHandle {
+    // sync(Relaxed): the same atomic as Shared::subscriber_count.
     subscriber_count: std::sync::Weak<AtomicUsize>,
 }
 
 impl SubscriberCountHandle {
+    /// A handle for a channel that is already gone.
+    pub(crate) fn dangling() -> Self {
+        Self {
+            subscriber_count: Weak::new(),
+        }
+    }
+
     /// Get the current subscriber count.
     /// Subscriber counts are updated asynchronously, so values may be stale.
     ///
@@ -211,3 +948,122 @@ impl SubscriberCountHandle {
             .map(|count| count.load(Ordering::Relaxed))
     }
 }
+
+/// Counters behind a [`StatsHandle`]. The Engine and the receivers keep them up to date.
+#[derive(Debug, Default)]
+pub(crate) struct StatsCounters {
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub published: AtomicU64,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub buffered: AtomicUsize,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub evictions: AtomicU64,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub lag_events: AtomicU64,
+    // sync(Release, Acquire): the Engine clears it as it dies, after its last update to
+    // the other counters.
+    pub engine_alive: AtomicBool,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub allocation_failures: AtomicU64,
+    // sync(Relaxed): a status flag for snapshots; nothing is published through it.
+    pub unhealthy: AtomicBool,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub compressed_entries: AtomicU64,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub compression_input_bytes: AtomicU64,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub compression_output_bytes: AtomicU64,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub closed_receivers: AtomicU64,
+    // sync(Relaxed): a statistic; nothing is published through it.
+    pub failed_receivers: AtomicU64,
+}
+
+/// A point-in-time view of a splaycast, from [`StatsHandle::get`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Stats {
+    /// See [`crate::Splaycast::subscriber_count`].
+    pub subscriber_count: usize,
+    /// How many entries the Engine has published, over the life of the splaycast.
+    pub messages_published: u64,
+    /// How many entries are in the buffer right now.
+    pub buffered_entries: usize,
+    /// How many entries the buffer policy has popped or deferred, over the life of the
+    /// splaycast.
+    pub evictions: u64,
+    /// How many times a receiver yielded a [`crate::Message::Lagged`], over the life of the
+    /// splaycast.
+    pub lag_events: u64,
+    /// Whether the Engine is still running: false once it completes or is dropped.
+    pub engine_alive: bool,
+    /// How many times the buffer couldn't grow, with [`crate::Engine::set_fallible_growth`].
+    pub allocation_failures: u64,
+    /// False once the Engine has dropped an item because the buffer couldn't grow.
+    pub healthy: bool,
+    /// How many entries were candidates for compression, with `Engine::set_compression`
+    /// and the `compression` feature, over the life of the splaycast.
+    pub compressed_entries: u64,
+    /// The bytes of those entries before compression.
+    pub compression_input_bytes: u64,
+    /// The bytes of those entries in the buffer, after compression.
+    pub compression_output_bytes: u64,
+    /// How many receivers were closed with [`crate::Receiver::close`], rather than dropped,
+    /// over the life of the splaycast.
+    pub closed_receivers: u64,
+    /// How many of those were closed with [`crate::CloseReason::Failed`].
+    pub failed_receivers: u64,
+}
+
+impl Stats {
+    /// The compressed size of compression candidates over their original size: 0.25 means
+    /// they take a quarter of the memory. This is 1 when nothing has been compressed.
+    pub fn compression_ratio(&self) -> f64 {
+        if self.compression_input_bytes == 0 {
+            1.0
+        } else {
+            self.compression_output_bytes as f64 / self.compression_input_bytes as f64
+        }
+    }
+}
+
+/// A handle for inspecting a splaycast's stats, e.g., for a metrics exporter.
+/// Like [`SubscriberCountHandle`], it does not keep the splaycast alive, and reading it
+/// is a handful of atomic loads. Values are updated asynchronously, so they may be stale.
+#[derive(Debug, Clone)]
+pub struct StatsHandle {
+    subscriber_count: SubscriberCountHandle,
+    stats: std::sync::Weak<StatsCounters>,
+}
+
+impl StatsHandle {
+    /// A handle for a channel that is already gone.
+    pub(crate) fn dangling() -> Self {
+        Self {
+            subscriber_count: SubscriberCountHandle::dangling(),
+            stats: Weak::new(),
+        }
+    }
+
+    /// Get the current stats.
+    ///
+    /// Returns None if the channel has been dropped.
+    pub fn get(&self) -> Option<Stats> {
+        let stats = self.stats.upgrade()?;
+        Some(Stats {
+            subscriber_count: self.subscriber_count.get()?,
+            messages_published: stats.published.load(Ordering::Relaxed),
+            buffered_entries: stats.buffered.load(Ordering::Relaxed),
+            evictions: stats.evictions.load(Ordering::Relaxed),
+            lag_events: stats.lag_events.load(Ordering::Relaxed),
+            engine_alive: stats.engine_alive.load(Ordering::Acquire),
+            allocation_failures: stats.allocation_failures.load(Ordering::Relaxed),
+            healthy: !stats.unhealthy.load(Ordering::Relaxed),
+            compressed_entries: stats.compressed_entries.load(Ordering::Relaxed),
+            compression_input_bytes: stats.compression_input_bytes.load(Ordering::Relaxed),
+            compression_output_bytes: stats.compression_output_bytes.load(Ordering::Relaxed),
+            closed_receivers: stats.closed_receivers.load(Ordering::Relaxed),
+            failed_receivers: stats.failed_receivers.load(Ordering::Relaxed),
+        })
+    }
+}
@@ -0,0 +1,300 @@
+//! Capacity planning for a splaycast, in virtual time.
+//!
+//! A [`Simulation`] runs a real Engine, buffer policy and Receivers, but drives them by
+//! hand in fixed ticks instead of on a runtime. Each tick, the upstream publishes some
+//! entries, the Engine runs, and each synthetic subscriber consumes according to its
+//! [`SubscriberProfile`]. The [`Report`] says how full the buffer got, how much each
+//! subscriber lagged, and how many wakes the Engine issued, so you can check a policy and
+//! buffer size before you deploy them.
+//! ```
+//! # use splaycast::buffer_policy::BufferLengthPolicy;
+//! # use splaycast::simulate::{Simulation, SubscriberProfile};
+//! let report = Simulation::new(BufferLengthPolicy::new(64))
+//!     .publish_per_tick(10)
+//!     .subscribers(SubscriberProfile::new(10), 100)
+//!     .subscribers(SubscriberProfile::new(10).with_stalls(50, 10), 1)
+//!     .run(1_000);
+//!
+//! assert!(report.subscribers[..100].iter().all(|s| s.lagged_entries == 0));
+//! assert!(0 < report.subscribers[100].lagged_entries, "100 entries per stall is too many");
+//! ```
+//!
+//! Only entry counts are simulated, so policies that read the clock, like
+//! [`crate::buffer_policy::BufferAgePolicy`], see real time rather than virtual time.
+
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use futures::{
+    task::{waker, ArcWake},
+    Future, Stream,
+};
+
+use crate::{buffer_policy::BufferPolicy, Message, Receiver};
+
+/// How a group of synthetic subscribers consumes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriberProfile {
+    consume_per_tick: usize,
+    stall_every: usize,
+    stall_for: usize,
+    start_tick: usize,
+}
+
+impl SubscriberProfile {
+    /// Subscribers that consume up to `consume_per_tick` messages per tick.
+    pub fn new(consume_per_tick: usize) -> Self {
+        Self {
+            consume_per_tick,
+            stall_every: 0,
+            stall_for: 0,
+            start_tick: 0,
+        }
+    }
+
+    /// Stop consuming for `stall_for` ticks out of every `stall_every`, e.g., for a
+    /// garbage collection pause or a slow network.
+    pub fn with_stalls(self, stall_every: usize, stall_for: usize) -> Self {
+        Self {
+            stall_every,
+            stall_for,
+            ..self
+        }
+    }
+
+    /// Subscribe at tick `start_tick` instead of at the start.
+    pub fn starting_at(self, start_tick: usize) -> Self {
+        Self { start_tick, ..self }
+    }
+
+    fn is_stalled(&self, tick: usize) -> bool {
+        0 < self.stall_every
+            && self.stall_every.saturating_sub(self.stall_for) <= tick % self.stall_every
+    }
+}
+
+/// What happened to one synthetic subscriber.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SubscriberReport {
+    /// How many entries it received.
+    pub received: u64,
+    /// How many times it yielded a `Lagged`.
+    pub lag_events: u64,
+    /// How many entries it skipped, over all of its lag events.
+    pub lagged_entries: u64,
+    /// The most entries it skipped at once.
+    pub max_lag: u64,
+    /// How many times it was woken.
+    pub wakes: u64,
+}
+
+/// The outcome of a [`Simulation`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Report {
+    /// How many ticks ran.
+    pub ticks: usize,
+    /// How many entries the Engine published.
+    pub published: u64,
+    /// The buffer length at the end of each tick.
+    pub buffer_occupancy: Vec<usize>,
+    /// How many subscriber wakes the Engine issued in each tick.
+    pub wakes_per_tick: Vec<u64>,
+    /// One report per subscriber, in the order they were added.
+    pub subscribers: Vec<SubscriberReport>,
+}
+
+impl Report {
+    /// The most entries the buffer held at the end of a tick.
+    pub fn peak_occupancy(&self) -> usize {
+        self.buffer_occupancy
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The most subscriber wakes in one tick.
+    pub fn peak_wakes(&self) -> u64 {
+        self.wakes_per_tick
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The total entries skipped, over all subscribers.
+    pub fn lagged_entries(&self) -> u64 {
+        self.subscribers.iter().map(|s| s.lagged_entries).sum()
+    }
+}
+
+/// A splaycast configuration and subscriber population to simulate. See the
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Simulation<Policy> {
+    buffer_policy: Policy,
+    publish_per_tick: usize,
+    subscribers: Vec<(SubscriberProfile, usize)>,
+}
+
+impl<Policy> Simulation<Policy>
+where
+    Policy: BufferPolicy<u64> + Clone,
+{
+    /// A simulation of a channel with `buffer_policy`. It publishes 1 entry per tick and
+    /// has no subscribers until you add some.
+    pub fn new(buffer_policy: Policy) -> Self {
+        Self {
+            buffer_policy,
+            publish_per_tick: 1,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Publish this many entries per tick.
+    pub fn publish_per_tick(self, publish_per_tick: usize) -> Self {
+        Self {
+            publish_per_tick,
+            ..self
+        }
+    }
+
+    /// Add `count` subscribers that consume like `profile`.
+    pub fn subscribers(mut self, profile: SubscriberProfile, count: usize) -> Self {
+        self.subscribers.push((profile, count));
+        self
+    }
+
+    /// Run for `ticks` ticks. The simulation can be run again, and starts over each time.
+    pub fn run(&self, ticks: usize) -> Report {
+        let (sender, mut engine, splaycast) =
+            crate::channel_with_policy(self.publish_per_tick.max(1), self.buffer_policy.clone());
+        let stats = splaycast.stats_handle();
+        let engine_wakes = Arc::new(WakeCounter::default());
+        let engine_waker = waker(engine_wakes.clone());
+        let mut engine_context = Context::from_waker(&engine_waker);
+
+        let mut subscribers: Vec<Subscriber> = self
+            .subscribers
+            .iter()
+            .flat_map(|(profile, count)| std::iter::repeat_n(*profile, *count))
+            .map(Subscriber::new)
+            .collect();
+        let mut report = Report {
+            ticks,
+            subscribers: vec![SubscriberReport::default(); subscribers.len()],
+            ..Default::default()
+        };
+
+        let mut next_item = 0;
+        for tick in 0..ticks {
+            for subscriber in subscribers.iter_mut() {
+                if subscriber.profile.start_tick == tick {
+                    subscriber.receiver = Some(splaycast.subscribe());
+                }
+            }
+
+            for _ in 0..self.publish_per_tick {
+                let _ = sender.send(next_item);
+                next_item += 1;
+            }
+            let woken_before: u64 = subscribers.iter().map(|s| s.wakes.get()).sum();
+            // Run the Engine until it is caught up, as a runtime would when it wakes itself.
+            loop {
+                let wakes = engine_wakes.get();
+                if let Poll::Ready(()) = Pin::new(&mut engine).poll(&mut engine_context) {
+                    break;
+                }
+                if engine_wakes.get() == wakes {
+                    break;
+                }
+            }
+            let woken: u64 = subscribers.iter().map(|s| s.wakes.get()).sum();
+            report.wakes_per_tick.push(woken - woken_before);
+
+            for (subscriber, subscriber_report) in
+                subscribers.iter_mut().zip(&mut report.subscribers)
+            {
+                subscriber.consume(tick, subscriber_report);
+            }
+            report.buffer_occupancy.push(
+                stats
+                    .get()
+                    .map(|stats| stats.buffered_entries)
+                    .unwrap_or_default(),
+            );
+        }
+        report.published = stats
+            .get()
+            .map(|stats| stats.messages_published)
+            .unwrap_or_default();
+        for (subscriber, subscriber_report) in subscribers.iter().zip(&mut report.subscribers) {
+            subscriber_report.wakes = subscriber.wakes.get();
+        }
+        report
+    }
+}
+
+#[derive(Debug, Default)]
+struct WakeCounter(AtomicU64);
+
+impl WakeCounter {
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl ArcWake for WakeCounter {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+struct Subscriber {
+    profile: SubscriberProfile,
+    receiver: Option<Receiver<u64>>,
+    wakes: Arc<WakeCounter>,
+}
+
+impl Subscriber {
+    fn new(profile: SubscriberProfile) -> Self {
+        Self {
+            profile,
+            receiver: None,
+            wakes: Default::default(),
+        }
+    }
+
+    fn consume(&mut self, tick: usize, report: &mut SubscriberReport) {
+        if self.profile.is_stalled(tick) {
+            return;
+        }
+        let Some(receiver) = &mut self.receiver else {
+            return;
+        };
+        let waker = waker(self.wakes.clone());
+        let mut context = Context::from_waker(&waker);
+        let mut consumed = 0;
+        while consumed < self.profile.consume_per_tick {
+            match Pin::new(&mut *receiver).poll_next(&mut context) {
+                Poll::Ready(Some(Message::Entry { .. })) => report.received += 1,
+                Poll::Ready(Some(Message::Lagged { count })) => {
+                    report.lag_events += 1;
+                    report.lagged_entries += count as u64;
+                    report.max_lag = report.max_lag.max(count as u64);
+                }
+                Poll::Ready(Some(Message::Reset { .. })) => (),
+                Poll::Ready(None) | Poll::Pending => return,
+            }
+            consumed += 1;
+        }
+    }
+}
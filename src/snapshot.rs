@@ -0,0 +1,49 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use crate::SplaycastEntry;
+
+/// A point-in-time view of a splaycast's buffer, from [`crate::Splaycast::snapshot`].
+///
+/// This shares the buffer that receivers read, so taking one is cheap: no items are
+/// cloned. Holding on to a snapshot keeps its entries alive after the buffer moves on,
+/// so don't hold one for longer than you need it.
+pub struct Snapshot<Item> {
+    queue: Arc<VecDeque<SplaycastEntry<Item>>>,
+}
+
+impl<Item> std::fmt::Debug for Snapshot<Item> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("len", &self.queue.len())
+            .finish()
+    }
+}
+
+impl<Item> Clone for Snapshot<Item> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<Item> Snapshot<Item> {
+    pub(crate) fn new(queue: Arc<VecDeque<SplaycastEntry<Item>>>) -> Self {
+        Self { queue }
+    }
+
+    /// The buffered items with their sequence ids, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (u64, &Item)> + ExactSizeIterator {
+        self.queue.iter().map(|entry| (entry.id, &entry.item))
+    }
+
+    /// How many entries were buffered.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Whether the buffer was empty.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
@@ -1,13 +1,53 @@
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 use crate::{
+    await_subscribers::AwaitSubscribers,
     buffer_policy::BufferPolicy,
     engine::Engine,
-    receiver::Receiver,
-    shared::{Shared, SubscriberCountHandle},
+    lag_events::{LagEvents, CAPACITY as LAG_EVENTS_CAPACITY},
+    mapped_receiver::MappedReceiver,
+    mirror::SplaycastMirror,
+    next_matching::NextMatching,
+    quiesced::Quiesced,
+    receiver::{Receiver, SubscriptionState},
+    receiver_group::GroupReceiver,
+    shared::{AbandonedBacklog, ReceiverCursor, Shared, StatsHandle, SubscriberCountHandle},
+    snapshot::Snapshot,
+    subscribe_options::{StartAt, SubscribeOptions},
+    subscriber_count_changes::SubscriberCountChanges,
+    subscription_mode::{SubscriptionMode, TipOnly},
+    ChannelMetadata, SubscribePermit,
 };
 
+/// Why a subscription was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SubscribeError {
+    /// The splaycast was sealed with [`Splaycast::seal`], and no longer accepts subscribers.
+    Sealed,
+    /// The splaycast has terminated, e.g., when subscribing through a [`WeakSplaycast`].
+    Closed,
+    /// The splaycast has as many subscribers as [`Splaycast::set_max_subscribers`] allows.
+    AtCapacity,
+}
+
+impl std::fmt::Display for SubscribeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubscribeError::Sealed => write!(f, "splaycast is sealed"),
+            SubscribeError::Closed => write!(f, "splaycast is closed"),
+            SubscribeError::AtCapacity => write!(f, "splaycast is at its subscriber limit"),
+        }
+    }
+}
+
+impl std::error::Error for SubscribeError {}
+
 /// The handle for attaching new subscribers to and inspecting the state of a splaycast.
+///
+/// You can clone this handle to share it, e.g., with many connection-accept tasks. The
+/// splaycast terminates when the last clone is dropped, unless it was
+/// [detached](Splaycast::detach).
 #[derive(Debug)]
 pub struct Splaycast<Item>
 where
@@ -29,7 +69,19 @@ where
         Upstream: futures::Stream<Item = Item> + Unpin,
         Policy: BufferPolicy<Item>,
     {
-        let shared = Arc::new(Shared::new());
+        Self::new_with_metadata(upstream, buffer_policy, ChannelMetadata::new())
+    }
+
+    pub(crate) fn new_with_metadata<Upstream, Policy>(
+        upstream: Upstream,
+        buffer_policy: Policy,
+        channel_metadata: ChannelMetadata,
+    ) -> (Engine<Upstream, Item, Policy>, Self)
+    where
+        Upstream: futures::Stream<Item = Item> + Unpin,
+        Policy: BufferPolicy<Item>,
+    {
+        let shared = Arc::new(Shared::new(channel_metadata));
         let engine = Engine::new(upstream, shared.clone(), buffer_policy);
         (engine, Self { shared })
     }
@@ -37,8 +89,22 @@ where
     /// Get a new streaming Receiver from the upstream stream. Values are cloned to
     /// this receiver, and lag is tracked if you consume too slowly and fall off of
     /// the configured buffer.
+    ///
+    /// # Refusal
+    ///
+    /// This doesn't fail: if the splaycast is sealed, or at its subscriber limit, you get
+    /// a Receiver whose stream ends right away, as if the splaycast had terminated. The
+    /// refusal is only logged. Prefer [`Splaycast::try_subscribe`] wherever a refusal is
+    /// possible: it tells you why.
     pub fn subscribe(&self) -> Receiver<Item> {
-        Receiver::new(self.shared.next_receiver_id(), self.shared.clone())
+        self.subscribe_with(SubscribeOptions::new())
+    }
+
+    /// Get a new streaming Receiver like [`Splaycast::subscribe`], or why the subscription
+    /// was refused: the splaycast is sealed, or at its subscriber limit. This is the way to
+    /// subscribe when either can happen.
+    pub fn try_subscribe(&self) -> Result<Receiver<Item>, SubscribeError> {
+        self.try_subscribe_with(SubscribeOptions::new())
     }
 
     /// Get a new streaming Receiver from the upstream stream. Values are cloned to
@@ -48,8 +114,463 @@ where
     /// This subscription receives starting from the oldest item in the buffer. You will
     /// race with the buffer policy to get the items, so you may see lag messages as you
     /// get started and catch up.
+    ///
+    /// # Refusal
+    ///
+    /// Like [`Splaycast::subscribe`], this doesn't fail: a refused Receiver's stream ends
+    /// right away. Use [`Splaycast::try_subscribe_with`] and [`StartAt::Tail`] to find out
+    /// why.
     pub fn subscribe_at_tail(&self) -> Receiver<Item> {
-        Receiver::new_at_buffer_start(self.shared.next_receiver_id(), self.shared.clone())
+        self.subscribe_with(SubscribeOptions::new().start_at(StartAt::Tail))
+    }
+
+    /// Get a new streaming Receiver whose stream ends once `duration` has passed, e.g., for
+    /// trial or preview clients. It starts at the head, like [`Splaycast::subscribe`].
+    ///
+    /// When the deadline passes, the Receiver gives up its place right away: it stops
+    /// counting as a subscriber and returns its permit, even if the caller forgets to drop
+    /// it. The Engine sees to this, so it happens even if nothing polls the Receiver.
+    /// [`Receiver::is_expired`] tells the end apart from a termination.
+    ///
+    /// This is a shorthand for [`SubscribeOptions::expires_after`], which needs a tokio
+    /// runtime for its timer.
+    ///
+    /// This fails once the splaycast is sealed, or at its subscriber limit.
+    #[cfg(feature = "tokio")]
+    pub fn subscribe_for(
+        &self,
+        duration: std::time::Duration,
+    ) -> Result<Receiver<Item>, SubscribeError> {
+        self.try_subscribe_with(SubscribeOptions::new().expires_after(duration))
+    }
+
+    /// Get a new streaming Receiver that starts at the entry with sequence id `id`, e.g.,
+    /// to resume a client that reconnects after it last saw `id - 1`.
+    ///
+    /// If that entry already fell off the buffer, the Receiver starts with a `Lagged`
+    /// for what was missed. Ids that haven't been published yet start at the head.
+    ///
+    /// This fails once the splaycast is sealed, or at its subscriber limit.
+    pub fn subscribe_from(&self, id: u64) -> Result<Receiver<Item>, SubscribeError> {
+        self.try_subscribe_with(SubscribeOptions::new().start_at(StartAt::Sequence(id)))
+    }
+
+    /// Get a new streaming Receiver, configured by `options`.
+    ///
+    /// # Refusal
+    ///
+    /// Like [`Splaycast::subscribe`], this doesn't fail: a refused Receiver's stream ends
+    /// right away. Prefer [`Splaycast::try_subscribe_with`], which tells you why.
+    pub fn subscribe_with(&self, options: SubscribeOptions) -> Receiver<Item> {
+        self.try_subscribe_with(options).unwrap_or_else(|error| {
+            log::debug!("refusing subscriber: {error}");
+            Receiver::refused(self.shared.next_receiver_id(), self.shared.clone())
+        })
+    }
+
+    /// Get a new streaming Receiver, configured by `options`, or why the subscription was
+    /// refused.
+    pub fn try_subscribe_with(
+        &self,
+        options: SubscribeOptions,
+    ) -> Result<Receiver<Item>, SubscribeError> {
+        self.subscribe_mode(options)
+    }
+
+    fn subscribe_mode<Mode: SubscriptionMode>(
+        &self,
+        options: SubscribeOptions,
+    ) -> Result<Receiver<Item, Mode>, SubscribeError> {
+        self.shared.check_admission(1)?;
+        let next_message_id = match options.start_at {
+            StartAt::Head => self.shared.subscribe_sequence_number(),
+            StartAt::Tail => self.shared.subscribe_tail_sequence_number(),
+            StartAt::Sequence(id) => id.clamp(1, self.shared.subscribe_sequence_number()),
+        };
+        let mut receiver = Receiver::new_at(
+            self.shared.next_receiver_id(),
+            self.shared.clone(),
+            next_message_id,
+        );
+        // Resuming from an id, the caller has seen what came before it.
+        receiver.set_takes_summary(!matches!(options.start_at, StartAt::Sequence(_)));
+        receiver.set_prefetch(options.prefetch);
+        receiver.set_resume_at(options.resume_at);
+        if let Some(label) = options.label {
+            receiver.set_label(label);
+        }
+        #[cfg(feature = "tokio")]
+        if let Some(deadline) = options.expires_at {
+            receiver.set_expiry(deadline);
+        }
+        Ok(receiver)
+    }
+
+    /// Get a new streaming Receiver that holds `permit` until it is dropped, which returns
+    /// the permit to its [`crate::SubscribePermits`] pool. It starts at the head, like
+    /// [`Splaycast::subscribe`].
+    ///
+    /// This fails once the splaycast is sealed, or at its subscriber limit, and then the
+    /// permit goes straight back.
+    pub fn subscribe_with_permit(
+        &self,
+        permit: SubscribePermit,
+    ) -> Result<Receiver<Item>, SubscribeError> {
+        let mut receiver = self.try_subscribe()?;
+        receiver.set_permit(permit);
+        Ok(receiver)
+    }
+
+    /// Get a new streaming Receiver that only yields entries for which `predicate` returns
+    /// true.
+    ///
+    /// The predicate runs in the Receiver as it polls, so entries that don't match are
+    /// skipped without being cloned. Lag is still reported for entries that fell off the
+    /// buffer, whether or not they would have matched.
+    ///
+    /// This fails once the splaycast is sealed, or at its subscriber limit.
+    pub fn subscribe_filtered(
+        &self,
+        predicate: impl Fn(&Item) -> bool + Send + Sync + 'static,
+    ) -> Result<Receiver<Item>, SubscribeError> {
+        let mut receiver = self.try_subscribe()?;
+        receiver.set_filter(Arc::new(predicate));
+        Ok(receiver)
+    }
+
+    /// Get a new streaming Receiver that only ever yields the newest entry.
+    ///
+    /// Each poll jumps to the head of the buffer, so intermediate entries are discarded
+    /// without any `Lagged` messages. The first poll yields the newest entry already in the
+    /// buffer, if there is one. This is for state-sync subscribers, like a UI, that only
+    /// care about the current value.
+    ///
+    /// The Receiver is [`TipOnly`], so replay operations like [`Receiver::seek`] are not
+    /// available on it:
+    /// ```compile_fail
+    /// let (_sender, _engine, splaycast) = splaycast::channel::<usize>(4);
+    /// let mut latest = splaycast.subscribe_latest_only().expect("not sealed");
+    /// latest.seek(1);
+    /// ```
+    ///
+    /// This fails once the splaycast is sealed, or at its subscriber limit.
+    pub fn subscribe_latest_only(&self) -> Result<Receiver<Item, TipOnly>, SubscribeError> {
+        self.subscribe_mode(SubscribeOptions::new().start_at(StartAt::Tail))
+    }
+
+    /// Get a new stream that yields at most one entry per `interval`: the newest one at
+    /// each tick. See [`crate::Throttled`].
+    ///
+    /// This uses the tokio timer, so it must be polled on a tokio runtime. It fails once the
+    /// splaycast is sealed, or at its subscriber limit.
+    #[cfg(feature = "tokio")]
+    pub fn subscribe_throttled(
+        &self,
+        interval: std::time::Duration,
+    ) -> Result<crate::Throttled<Item>, SubscribeError> {
+        Ok(crate::Throttled::new(
+            self.subscribe_latest_only()?,
+            interval,
+        ))
+    }
+
+    /// Get a new streaming Receiver that yields `map(&item)` for each entry, instead of a
+    /// clone of the entry.
+    ///
+    /// Use this when a subscriber only needs a projected or owned subset of a large item:
+    /// the full item is never cloned for it.
+    ///
+    /// This fails once the splaycast is sealed, or at its subscriber limit.
+    pub fn subscribe_map<U, F>(&self, map: F) -> Result<MappedReceiver<Item, F>, SubscribeError>
+    where
+        F: FnMut(&Item) -> U,
+    {
+        Ok(MappedReceiver::new(self.try_subscribe()?, map))
+    }
+
+    /// Get `size` Receivers that coordinate as a group, all starting at the same position.
+    ///
+    /// Each member reports its position to the group as it moves, so you can use
+    /// [`crate::ReceiverGroup::wait_all_at`] as a barrier between them.
+    ///
+    /// This fails once the splaycast is sealed, or if it has no room for all `size` of them.
+    pub fn subscribe_group(&self, size: usize) -> Result<Vec<GroupReceiver<Item>>, SubscribeError> {
+        self.shared.check_admission(size)?;
+        let next_message_id = self.shared.subscribe_sequence_number();
+        let receivers = (0..size)
+            .map(|_| {
+                Receiver::new_at(
+                    self.shared.next_receiver_id(),
+                    self.shared.clone(),
+                    next_message_id,
+                )
+            })
+            .collect();
+        Ok(GroupReceiver::group(receivers))
+    }
+
+    /// Stop accepting new subscribers. Existing receivers are unaffected: they continue to
+    /// get data until the splaycast terminates normally.
+    ///
+    /// This is for draining, e.g., taking a node out of a load balancer without cutting
+    /// off its existing clients.
+    pub fn seal(&self) {
+        log::debug!("sealing splaycast");
+        self.shared.seal()
+    }
+
+    /// Accept new subscribers again after [`Splaycast::seal`], e.g., when a drain is
+    /// called off.
+    pub fn unseal(&self) {
+        log::debug!("unsealing splaycast");
+        self.shared.unseal()
+    }
+
+    /// Where each live Receiver is, ordered by receiver id, e.g., for a dashboard of which
+    /// downstream connections are falling behind.
+    ///
+    /// Receivers publish their position as they poll, so a Receiver that is stuck waiting
+    /// on its downstream shows where it was when it last polled.
+    pub fn receiver_cursors(&self) -> Vec<ReceiverCursor> {
+        self.shared.receiver_cursors()
+    }
+
+    /// Get a future that resolves once there are at least `count` subscribers, e.g., to
+    /// hold off publishing until a warm-up set of receivers has joined.
+    ///
+    /// It resolves with `true` when the count is reached, or `false` if the splaycast
+    /// terminates first.
+    pub fn await_subscribers(&self, count: usize) -> AwaitSubscribers<Item> {
+        AwaitSubscribers::new(self.shared.clone(), count)
+    }
+
+    /// Get a Future that resolves once every current subscriber has caught up to the tip,
+    /// e.g., before taking a snapshot, rotating the upstream, or a sealed shutdown.
+    ///
+    /// This is best-effort, from the positions receivers report as they yield: the tip can
+    /// move on as soon as it resolves. It resolves with `true` once there is no backlog,
+    /// including when there are no subscribers, or with `false` if the splaycast terminates
+    /// first.
+    pub fn quiesced(&self) -> Quiesced<Item> {
+        Quiesced::new(self.shared.clone())
+    }
+
+    /// Get a stream that yields the subscriber count whenever it crosses one of the
+    /// `thresholds`. A threshold of `1` reports the `0 -> 1` and `1 -> 0` transitions.
+    ///
+    /// Crossing means going from below a threshold to at or above it, or back.
+    pub fn subscriber_count_changes(
+        &self,
+        thresholds: impl IntoIterator<Item = usize>,
+    ) -> SubscriberCountChanges<Item> {
+        SubscriberCountChanges::new(self.shared.clone(), thresholds.into_iter().collect())
+    }
+
+    /// Get a stream of every receiver's lag, with the receiver's id, the count and a
+    /// timestamp, so one monitor task can watch fan-out health. See [`LagEvents`].
+    pub fn lag_events(&self) -> LagEvents {
+        LagEvents::new(self.shared.add_lag_monitor(LAG_EVENTS_CAPACITY))
+    }
+
+    /// How much data receivers walked away from: the receivers that were dropped while
+    /// they were behind, and how many published entries they never yielded.
+    ///
+    /// These are running totals over the life of the splaycast.
+    pub fn abandoned_backlog(&self) -> AbandonedBacklog {
+        self.shared.abandoned_backlog()
+    }
+
+    /// How many Receivers were disconnected because their waker panicked.
+    ///
+    /// The Engine isolates a panicking waker rather than unwinding and terminating the
+    /// splaycast for everyone. That Receiver is disconnected, as with
+    /// [`Splaycast::disconnect`], and counted here.
+    pub fn poisoned_receivers(&self) -> u64 {
+        self.shared.poisoned_receivers()
+    }
+
+    /// Deliver `item` to only the Receiver with this [id](Receiver::id), e.g., a
+    /// per-connection ack or error, without a second channel per connection.
+    ///
+    /// The item is yielded as an ordinary [`crate::Message::Entry`], ahead of the next
+    /// broadcast entry, and it does not get a sequence id. It is not subject to the
+    /// buffer policy: it waits for the Receiver until it is yielded or the Receiver drops.
+    ///
+    /// Returns the item back if there is no such Receiver, or it was disconnected.
+    pub fn send_to(&self, receiver_id: u64, item: Item) -> Result<(), Item> {
+        self.shared.send_to(receiver_id, item)
+    }
+
+    /// Disconnect the Receiver with this [id](Receiver::id), e.g., to ban an abusive client
+    /// without tearing down the channel.
+    ///
+    /// The Receiver's stream ends promptly, and its [`Receiver::is_disconnected`] tells
+    /// that apart from the end of the splaycast. Returns false if there is no such Receiver.
+    pub fn disconnect(&self, receiver_id: u64) -> bool {
+        log::debug!("disconnecting receiver {receiver_id}");
+        self.shared.disconnect(receiver_id)
+    }
+
+    /// Get a new Receiver that picks up exactly where the one that made `state` with
+    /// [`Receiver::hand_off`] left off.
+    ///
+    /// Like a subscriber that was admitted already, this does not check [`Splaycast::seal`]
+    /// or the subscriber limit. Returns the state back if it is from a different splaycast.
+    pub fn adopt<Mode>(
+        &self,
+        state: SubscriptionState<Item, Mode>,
+    ) -> Result<Receiver<Item, Mode>, SubscriptionState<Item, Mode>>
+    where
+        Mode: SubscriptionMode,
+    {
+        if !state.is_for(&self.shared) {
+            return Err(state);
+        }
+        Ok(state.adopt())
+    }
+
+    /// Refuse new subscribers with [`SubscribeError::AtCapacity`] once there are
+    /// `max_subscribers` of them, e.g., to shed load before parked receivers and their
+    /// wakers use too much memory. The default is no limit.
+    ///
+    /// Lowering the limit doesn't disconnect anybody. The limit is checked against the
+    /// current subscriber count, so a burst of concurrent subscribes can overshoot it
+    /// slightly.
+    pub fn set_max_subscribers(&self, max_subscribers: usize) {
+        self.shared.set_max_subscribers(max_subscribers)
+    }
+
+    /// Clear the buffer, e.g., when the buffered data became invalid after an upstream
+    /// device reset.
+    ///
+    /// Unlike [`Splaycast::reset_sequence`], sequence ids carry on. Receivers that were
+    /// behind yield a [`crate::Message::Lagged`] for what they missed and jump forward, and
+    /// new subscribers start with an empty buffer. This takes effect the next time the
+    /// Engine runs.
+    pub fn flush_buffer(&self) {
+        log::debug!("requesting buffer flush");
+        self.shared.request_flush()
+    }
+
+    /// Start the sequence over, e.g., after restoring upstream state.
+    ///
+    /// The Engine clears the buffer and starts a new epoch, with sequence ids counting up
+    /// from 1 again. Receivers yield a [`crate::Message::Reset`] and then continue from the
+    /// start of the new sequence. This takes effect the next time the Engine runs.
+    pub fn reset_sequence(&self) {
+        log::debug!("requesting sequence reset");
+        self.shared.request_reset()
+    }
+
+    /// Terminate the splaycast now, the same as dropping the last handle would.
+    ///
+    /// Receivers get a prompt end-of-stream, and the Engine completes. This is for shutdown
+    /// code that holds a handle while other clones are still out there.
+    pub fn close(&self) {
+        log::debug!("closing splaycast");
+        self.shared.set_dead()
+    }
+
+    /// Publish `item` to every Receiver from this handle, bypassing the upstream, e.g., for
+    /// a shutdown notice or an admin message.
+    ///
+    /// The Engine publishes announcements the next time it runs, ahead of whatever it
+    /// absorbs from the upstream in that pass. From then on, an announcement is an ordinary
+    /// entry: it gets a sequence id and is delivered in order to all receivers.
+    ///
+    /// Returns the item back if the splaycast is closed.
+    pub fn announce(&self, item: Item) -> Result<(), Item> {
+        if self.shared.is_dead() {
+            return Err(item);
+        }
+        self.shared.announce(item);
+        Ok(())
+    }
+
+    /// Whether the splaycast has terminated, e.g., because its upstream ended or it was
+    /// [`Splaycast::close`]d. A closed splaycast never publishes again, and new Receivers
+    /// end right away.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_dead()
+    }
+
+    /// Whether [`Splaycast::seal`] has been called.
+    pub fn is_sealed(&self) -> bool {
+        self.shared.is_sealed()
+    }
+
+    /// Let the splaycast outlive its handles: it runs for as long as the Engine and the
+    /// upstream do, and dropping the last Splaycast handle no longer terminates it.
+    ///
+    /// This is for the "subscribe some receivers, then discard the handle" pattern. This
+    /// applies to every clone of this handle. [`Splaycast::close`] still terminates the
+    /// splaycast, and you can still subscribe later through a [`WeakSplaycast`].
+    pub fn detach(self) {
+        self.shared.detach();
+    }
+
+    /// Get a handle that can subscribe while the splaycast is alive, but does not keep it
+    /// alive. Dropping a WeakSplaycast never terminates the splaycast.
+    pub fn downgrade(&self) -> WeakSplaycast<Item> {
+        WeakSplaycast {
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+
+    /// Get a one-shot future that resolves with the first upcoming entry for which
+    /// `predicate` returns true. Only entries published after this call are considered.
+    ///
+    /// This does not create a Receiver and does not count as a subscriber. It is intended
+    /// for "wait until X appears on the feed" coordination, and you can have a great many
+    /// of these waiting at once without them behaving like full subscribers.
+    pub fn next_matching<F>(&self, predicate: F) -> NextMatching<Item, F>
+    where
+        F: FnMut(&Item) -> bool,
+    {
+        NextMatching::new(
+            self.shared.next_receiver_id(),
+            self.shared.clone(),
+            predicate,
+        )
+    }
+
+    /// Get the current buffer contents, with their sequence ids, without subscribing.
+    ///
+    /// This is cheap: it shares the buffer that receivers read rather than cloning it.
+    /// Use it for things like a "recent history" endpoint.
+    pub fn snapshot(&self) -> Snapshot<Item> {
+        Snapshot::new(self.shared.load_queue_full())
+    }
+
+    /// How many entries are currently retained in the buffer.
+    ///
+    /// This is informational, and may be stale before it even returns. It is useful for
+    /// alerting on buffer growth under weight or age policies.
+    pub fn buffer_len(&self) -> usize {
+        self.shared.buffer_len()
+    }
+
+    /// The sequence ids of the oldest and newest entries currently retained in the buffer,
+    /// or None if it is empty.
+    ///
+    /// A [`Splaycast::subscribe_from`] below the oldest id will start with a lag. This is
+    /// informational, and may be stale before it even returns.
+    pub fn buffer_sequence_range(&self) -> Option<(u64, u64)> {
+        self.shared.buffer_sequence_range()
+    }
+
+    /// Get a read-only view of this splaycast for observability tooling. A mirror can
+    /// inspect the channel, but it cannot subscribe and it does not keep the channel alive.
+    pub fn mirror(&self) -> SplaycastMirror<Item> {
+        SplaycastMirror::new(&self.shared)
+    }
+
+    /// Unpin every entry with a sequence id at or below `id`, so the buffer policy can pop
+    /// them again. See [`crate::buffer_policy::PinInstruction`].
+    ///
+    /// This takes effect the next time the Engine absorbs from the upstream.
+    pub fn unpin_through(&self, id: u64) {
+        self.shared.unpin_through(id)
     }
 
     /// This is informational, and may be stale before it even returns. It is maintained
@@ -71,10 +592,77 @@ where
     pub fn subscriber_count_handle(&self) -> SubscriberCountHandle {
         self.shared.subscriber_count_handle()
     }
+
+    /// Get a handle for reading this splaycast's stats, e.g., from a metrics exporter:
+    /// subscribers, messages published, entries buffered, buffer policy evictions, lag
+    /// events and whether the Engine is alive.
+    ///
+    /// Like [`Splaycast::subscriber_count_handle`], the handle does not keep the splaycast
+    /// alive.
+    pub fn stats_handle(&self) -> StatsHandle {
+        self.shared.stats_handle()
+    }
+
+    /// The metadata this channel was created with. See [`crate::wrap_with_metadata`].
+    pub fn channel_metadata(&self) -> &ChannelMetadata {
+        self.shared.channel_metadata()
+    }
+}
+
+impl<T: Clone> Clone for Splaycast<T> {
+    fn clone(&self) -> Self {
+        self.shared.acquire_handle();
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
 }
 
 impl<T: Clone> Drop for Splaycast<T> {
     fn drop(&mut self) {
-        self.shared.set_dead()
+        if self.shared.release_handle() {
+            self.shared.set_dead()
+        }
+    }
+}
+
+/// A [`Splaycast`] handle that doesn't keep the splaycast alive, from [`Splaycast::downgrade`].
+///
+/// This is for things like registries, which hold on to many splaycasts without
+/// controlling their lifetimes.
+#[derive(Debug)]
+pub struct WeakSplaycast<Item>
+where
+    Item: Clone,
+{
+    shared: Weak<Shared<Item>>,
+}
+
+impl<Item> WeakSplaycast<Item>
+where
+    Item: Unpin + Clone + Send,
+{
+    /// Get a full Splaycast handle, if the splaycast has not terminated.
+    ///
+    /// The upgraded handle counts like any other clone: the splaycast stays alive at least
+    /// until it is dropped.
+    pub fn upgrade(&self) -> Option<Splaycast<Item>> {
+        let shared = self.shared.upgrade()?;
+        shared.try_acquire_handle().then_some(Splaycast { shared })
+    }
+
+    /// Subscribe, if the splaycast has not terminated. See [`Splaycast::try_subscribe`].
+    pub fn subscribe(&self) -> Result<Receiver<Item>, SubscribeError> {
+        self.upgrade()
+            .ok_or(SubscribeError::Closed)?
+            .try_subscribe()
+    }
+}
+
+impl<T: Clone> Clone for WeakSplaycast<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
     }
 }
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use crate::{
+    buffer_policy::BufferPolicy,
+    reorder::{ReorderBuffer, ReorderWindow},
+    Engine, Splaycast,
+};
+
+type ReorderFactory<Item> = Arc<dyn Fn() -> ReorderBuffer<Item> + Send + Sync>;
+
+/// A reusable splaycast configuration, for services that create many identical channels.
+///
+/// Configure the template once, then [`SplaycastTemplate::instantiate`] it for each
+/// upstream. Changing the template only affects channels that are instantiated afterward,
+/// so you can hot-update it while earlier channels keep running as they were.
+/// ```
+/// # use futures::StreamExt;
+/// # use splaycast::{buffer_policy::BufferLengthPolicy, Message, SplaycastTemplate};
+/// # tokio_test::block_on(async {
+/// let mut template = SplaycastTemplate::new(BufferLengthPolicy::new(16));
+/// template.set_wake_limit(64);
+///
+/// let (sender, upstream) = futures::channel::mpsc::unbounded();
+/// let (engine, splaycast) = template.instantiate(upstream);
+/// tokio::spawn(engine);
+///
+/// let mut receiver = splaycast.subscribe();
+/// sender.unbounded_send("hello").expect("the engine is running");
+/// assert_eq!(Some(Message::Entry { item: "hello" }), receiver.next().await);
+/// # })
+/// ```
+pub struct SplaycastTemplate<Item, Policy> {
+    buffer_policy: Policy,
+    wake_limit: usize,
+    max_subscribers: usize,
+    reorder: Option<ReorderFactory<Item>>,
+}
+
+impl<Item, Policy> std::fmt::Debug for SplaycastTemplate<Item, Policy>
+where
+    Policy: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SplaycastTemplate")
+            .field("buffer_policy", &self.buffer_policy)
+            .field("wake_limit", &self.wake_limit)
+            .field("max_subscribers", &self.max_subscribers)
+            .field("reorder", &self.reorder.is_some())
+            .finish()
+    }
+}
+
+impl<Item, Policy> Clone for SplaycastTemplate<Item, Policy>
+where
+    Policy: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            buffer_policy: self.buffer_policy.clone(),
+            wake_limit: self.wake_limit,
+            max_subscribers: self.max_subscribers,
+            reorder: self.reorder.clone(),
+        }
+    }
+}
+
+impl<Item, Policy> SplaycastTemplate<Item, Policy>
+where
+    Item: Clone + Send + Unpin,
+    Policy: BufferPolicy<Item> + Clone,
+{
+    /// A template whose channels each get a clone of `buffer_policy`.
+    pub fn new(buffer_policy: Policy) -> Self {
+        Self {
+            buffer_policy,
+            wake_limit: 32,
+            max_subscribers: usize::MAX,
+            reorder: None,
+        }
+    }
+
+    /// Set the buffer policy for new channels.
+    pub fn set_buffer_policy(&mut self, buffer_policy: Policy) {
+        self.buffer_policy = buffer_policy
+    }
+
+    /// Set the wake limit for new channels. See [`Engine::set_wake_limit`].
+    pub fn set_wake_limit(&mut self, wake_limit: usize) {
+        self.wake_limit = wake_limit.max(1)
+    }
+
+    /// Set the subscriber limit for new channels. See [`Splaycast::set_max_subscribers`].
+    pub fn set_max_subscribers(&mut self, max_subscribers: usize) {
+        self.max_subscribers = max_subscribers
+    }
+
+    /// Give new channels a reorder window. See [`Engine::set_reorder_window`].
+    pub fn set_reorder_window<K: Ord>(
+        &mut self,
+        window: ReorderWindow,
+        key: impl Fn(&Item) -> K + Send + Sync + 'static,
+    ) {
+        let key = Arc::new(key);
+        self.reorder = Some(Arc::new(move || {
+            let key = key.clone();
+            ReorderBuffer::new(window, move |item: &Item| key(item))
+        }))
+    }
+
+    /// Wrap `upstream` with a new splaycast, configured by this template.
+    ///
+    /// Like [`crate::wrap`], you need to spawn the Engine on your async runtime.
+    pub fn instantiate<Upstream>(
+        &self,
+        upstream: Upstream,
+    ) -> (Engine<Upstream, Item, Policy>, Splaycast<Item>)
+    where
+        Upstream: futures::Stream<Item = Item> + Unpin,
+    {
+        let (mut engine, splaycast) = Splaycast::new(upstream, self.buffer_policy.clone());
+        engine.set_wake_limit(self.wake_limit);
+        splaycast.set_max_subscribers(self.max_subscribers);
+        if let Some(reorder) = &self.reorder {
+            engine.set_reorder_buffer(reorder());
+        }
+        (engine, splaycast)
+    }
+}
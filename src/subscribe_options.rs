@@ -0,0 +1,92 @@
+/// Where a new Receiver starts reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum StartAt {
+    /// Start with the next entry to be published. This is what `subscribe()` does.
+    #[default]
+    Head,
+    /// Start with the oldest entry in the buffer. This is what `subscribe_at_tail()` does.
+    Tail,
+    /// Start at a sequence id. This is what `subscribe_from(id)` does.
+    Sequence(u64),
+}
+
+/// Where a Receiver picks up after it falls off the buffer and yields a `Lagged`.
+///
+/// Resuming closer to the head skips more, but a chronically slow consumer then gets a
+/// head start on the buffer policy instead of lagging again right away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ResumeAt {
+    /// Resume with the oldest entry in the buffer, skipping as little as possible.
+    #[default]
+    Tail,
+    /// Resume with the newest entry in the buffer.
+    Head,
+    /// Resume this far into the buffer, from 0 (the tail) to 100 (the head).
+    Percent(u8),
+}
+
+/// Per-subscription configuration, for [`crate::Splaycast::subscribe_with`].
+///
+/// ```
+/// # use splaycast::{StartAt, SubscribeOptions};
+/// let options = SubscribeOptions::new().start_at(StartAt::Tail).prefetch(8);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SubscribeOptions {
+    pub(crate) start_at: StartAt,
+    pub(crate) prefetch: usize,
+    pub(crate) resume_at: ResumeAt,
+    pub(crate) label: Option<std::sync::Arc<str>>,
+    #[cfg(feature = "tokio")]
+    pub(crate) expires_at: Option<std::time::Instant>,
+}
+
+impl SubscribeOptions {
+    /// The same options as a plain `subscribe()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Where the Receiver starts reading. The default is [`StartAt::Head`].
+    pub fn start_at(self, start_at: StartAt) -> Self {
+        Self { start_at, ..self }
+    }
+
+    /// How many entries the Receiver clones ahead. See [`crate::Receiver::set_prefetch`].
+    pub fn prefetch(self, prefetch: usize) -> Self {
+        Self { prefetch, ..self }
+    }
+
+    /// Where the Receiver picks up after a lag. See [`crate::Receiver::set_resume_at`].
+    pub fn resume_at(self, resume_at: ResumeAt) -> Self {
+        Self { resume_at, ..self }
+    }
+
+    /// Label the Receiver for diagnostics, e.g., with a connection id. See
+    /// [`crate::Receiver::set_label`].
+    pub fn label(self, label: impl Into<std::sync::Arc<str>>) -> Self {
+        Self {
+            label: Some(label.into()),
+            ..self
+        }
+    }
+
+    /// End the Receiver's stream at `deadline`, e.g., to cut off a trial client. Entries
+    /// that arrive before the deadline are delivered as usual.
+    #[cfg(feature = "tokio")]
+    pub fn expires_at(self, deadline: std::time::Instant) -> Self {
+        Self {
+            expires_at: Some(deadline),
+            ..self
+        }
+    }
+
+    /// End the Receiver's stream once `duration` has passed. See
+    /// [`SubscribeOptions::expires_at`].
+    #[cfg(feature = "tokio")]
+    pub fn expires_after(self, duration: std::time::Duration) -> Self {
+        self.expires_at(std::time::Instant::now() + duration)
+    }
+}
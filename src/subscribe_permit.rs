@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use crate::sync::{AtomicUsize, Ordering};
+
+/// A pool of [`SubscribePermit`]s, for fan-out quota that is managed by the application.
+///
+/// Hand out permits, e.g., per tenant from a quota service, and subscribe with
+/// [`crate::Splaycast::subscribe_with_permit`]. The Receiver holds its permit, and the
+/// permit goes back to the pool when the Receiver is dropped. One pool can cover any
+/// number of splaycasts.
+///
+/// This is separate from [`crate::Splaycast::set_max_subscribers`], which limits one
+/// splaycast no matter who is subscribing.
+#[derive(Debug, Clone)]
+pub struct SubscribePermits {
+    // sync(AcqRel, Acquire): permits are taken with a fetch_update and returned with
+    // AcqRel, like a semaphore.
+    available: Arc<AtomicUsize>,
+}
+
+impl SubscribePermits {
+    /// A pool with `count` permits.
+    pub fn new(count: usize) -> Self {
+        Self {
+            available: Arc::new(AtomicUsize::new(count)),
+        }
+    }
+
+    /// Take a permit, if there are any left.
+    pub fn try_acquire(&self) -> Option<SubscribePermit> {
+        self.available
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |available| {
+                available.checked_sub(1)
+            })
+            .ok()
+            .map(|_| SubscribePermit {
+                available: self.available.clone(),
+            })
+    }
+
+    /// How many permits are left in the pool.
+    pub fn available(&self) -> usize {
+        self.available.load(Ordering::Acquire)
+    }
+
+    /// Grow the pool by `count` permits, e.g., when a tenant's quota is raised.
+    pub fn add_permits(&self, count: usize) {
+        self.available.fetch_add(count, Ordering::AcqRel);
+    }
+}
+
+/// One subscription's worth of quota from [`SubscribePermits`]. It goes back to its pool
+/// when it is dropped.
+#[derive(Debug)]
+pub struct SubscribePermit {
+    // sync(AcqRel, Acquire): permits are taken with a fetch_update and returned with
+    // AcqRel, like a semaphore.
+    available: Arc<AtomicUsize>,
+}
+
+impl Drop for SubscribePermit {
+    fn drop(&mut self) {
+        self.available.fetch_add(1, Ordering::AcqRel);
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::{stream::FusedStream, Stream};
+
+use crate::shared::Shared;
+
+/// A stream of subscriber counts that yields whenever the count crosses a threshold, from
+/// [`crate::Splaycast::subscriber_count_changes`].
+///
+/// With a threshold of `1`, you see the `0 -> 1` and `1 -> 0` transitions, e.g., to
+/// start and stop an expensive upstream only while somebody is listening. Each item is
+/// the subscriber count when the crossing was noticed. Counts are coalesced: if the count
+/// crosses a threshold and crosses back before you poll, you see nothing.
+///
+/// The stream ends when the splaycast terminates.
+pub struct SubscriberCountChanges<Item>
+where
+    Item: Clone,
+{
+    shared: Arc<Shared<Item>>,
+    thresholds: Vec<usize>,
+    band: usize,
+    terminated: bool,
+}
+
+impl<Item> std::fmt::Debug for SubscriberCountChanges<Item>
+where
+    Item: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubscriberCountChanges")
+            .field("thresholds", &self.thresholds)
+            .field("band", &self.band)
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+impl<Item> SubscriberCountChanges<Item>
+where
+    Item: Clone,
+{
+    pub(crate) fn new(shared: Arc<Shared<Item>>, mut thresholds: Vec<usize>) -> Self {
+        thresholds.sort_unstable();
+        thresholds.dedup();
+        let mut changes = Self {
+            shared,
+            thresholds,
+            band: 0,
+            terminated: false,
+        };
+        changes.band = changes.band_of(changes.shared.subscriber_count());
+        changes
+    }
+
+    /// How many thresholds the count has reached.
+    fn band_of(&self, count: usize) -> usize {
+        self.thresholds
+            .partition_point(|threshold| *threshold <= count)
+    }
+
+    fn check(&mut self) -> Poll<Option<usize>> {
+        let count = self.shared.subscriber_count();
+        let band = self.band_of(count);
+        if band != self.band {
+            self.band = band;
+            Poll::Ready(Some(count))
+        } else if self.shared.is_dead() {
+            self.terminated = true;
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Safety: I don't use unsafe for this type
+impl<Item> Unpin for SubscriberCountChanges<Item> where Item: Clone {}
+
+impl<Item> Stream for SubscriberCountChanges<Item>
+where
+    Item: Clone,
+{
+    type Item = usize;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        if let Poll::Ready(next) = self.check() {
+            return Poll::Ready(next);
+        }
+        self.shared.register_count_waiter(context.waker());
+        // The count may have moved before we registered, so check once more.
+        self.check()
+    }
+}
+
+impl<Item> FusedStream for SubscriberCountChanges<Item>
+where
+    Item: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
@@ -0,0 +1,33 @@
+pub(crate) mod sealed {
+    pub trait Sealed {
+        /// Whether the receiver skips straight to the newest entry on each poll.
+        const LATEST_ONLY: bool;
+    }
+}
+
+/// The subscription mode of a [`crate::Receiver`], checked at compile time.
+///
+/// Replay operations like [`crate::Receiver::seek`] only exist on `Receiver<Item, Replay>`,
+/// so code can't accidentally call them on a conflated [`TipOnly`] subscription. This
+/// trait is sealed: the modes are [`Replay`] and [`TipOnly`].
+pub trait SubscriptionMode: sealed::Sealed {}
+
+/// A Receiver that yields every entry in order, and can move around the buffer. This is
+/// the default mode.
+#[derive(Debug)]
+pub enum Replay {}
+
+/// A Receiver that only ever yields the newest entry, from
+/// [`crate::Splaycast::subscribe_latest_only`].
+#[derive(Debug)]
+pub enum TipOnly {}
+
+impl sealed::Sealed for Replay {
+    const LATEST_ONLY: bool = false;
+}
+impl SubscriptionMode for Replay {}
+
+impl sealed::Sealed for TipOnly {
+    const LATEST_ONLY: bool = true;
+}
+impl SubscriptionMode for TipOnly {}
@@ -0,0 +1,13 @@
+//! The crate's atomics, in one place.
+//!
+//! Normally these are std's atomics. Built with `--cfg splaycast_audit`, they are the
+//! recording wrappers from [`crate::audit`] instead, which behave the same but note every
+//! access site and ordering. Every atomic field carries a `// sync(...)` annotation with the
+//! orderings it allows and what it pairs with; see `ATOMICS.md`.
+
+pub(crate) use std::sync::atomic::{fence, Ordering};
+
+#[cfg(splaycast_audit)]
+pub(crate) use crate::audit::{AtomicBool, AtomicU64, AtomicUsize};
+#[cfg(not(splaycast_audit))]
+pub(crate) use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
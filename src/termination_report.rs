@@ -0,0 +1,30 @@
+/// Why a splaycast terminated, in a [`TerminationReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TerminationReason {
+    /// The upstream stream ended, e.g., every Sender was dropped.
+    UpstreamEnded,
+    /// The last Splaycast handle was dropped, or [`crate::Splaycast::close`] was called.
+    Closed,
+    /// The Engine was dropped before it completed, e.g., when its task was aborted.
+    EngineDropped,
+    /// The tokio runtime running the Engine shut down, and dropped it.
+    RuntimeShutdown,
+}
+
+/// A summary of a splaycast's life, for [`crate::Engine::set_on_terminate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TerminationReport {
+    /// Why the splaycast terminated.
+    pub reason: TerminationReason,
+    /// How many items the Engine took from the upstream.
+    pub items_absorbed: u64,
+    /// How many entries the buffer policy popped or deferred. See [`crate::Stats::evictions`].
+    pub pops: u64,
+    /// The most Receivers that were subscribed at once.
+    pub peak_subscriber_count: usize,
+    /// The id of the last entry published, or 0 if there were none. After a
+    /// [`crate::Splaycast::reset_sequence`], this is in the last epoch's sequence.
+    pub final_sequence_id: u64,
+}
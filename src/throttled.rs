@@ -0,0 +1,115 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{stream::FusedStream, Stream};
+use tokio::time::{Instant, Sleep};
+
+use crate::{Message, Receiver, TipOnly};
+
+/// A Receiver that yields at most one entry per interval, from
+/// [`crate::Splaycast::subscribe_throttled`].
+///
+/// This is for clients that refresh at a fixed rate, like a UI at 10 Hz. An entry that
+/// arrives after a quiet interval is yielded right away. After that, the stream waits out
+/// the interval and then yields the newest entry: the ones in between are skipped without
+/// a `Lagged`, and they are never cloned.
+pub struct Throttled<Item>
+where
+    Item: Clone,
+{
+    receiver: Receiver<Item, TipOnly>,
+    interval: Duration,
+    /// Created on the first entry, so the stream can be made outside of a runtime.
+    sleep: Option<Pin<Box<Sleep>>>,
+    throttling: bool,
+    terminated: bool,
+}
+
+impl<Item> std::fmt::Debug for Throttled<Item>
+where
+    Item: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Throttled")
+            .field("receiver", &self.receiver)
+            .field("interval", &self.interval)
+            .field("throttling", &self.throttling)
+            .field("terminated", &self.terminated)
+            .finish()
+    }
+}
+
+impl<Item> Throttled<Item>
+where
+    Item: Clone,
+{
+    pub(crate) fn new(receiver: Receiver<Item, TipOnly>, interval: Duration) -> Self {
+        Self {
+            receiver,
+            interval,
+            sleep: None,
+            throttling: false,
+            terminated: false,
+        }
+    }
+
+    /// Get back the Receiver.
+    pub fn into_inner(self) -> Receiver<Item, TipOnly> {
+        self.receiver
+    }
+}
+
+/// Safety: I don't use unsafe for this type
+impl<Item> Unpin for Throttled<Item> where Item: Clone {}
+
+impl<Item> Stream for Throttled<Item>
+where
+    Item: Clone,
+{
+    type Item = Message<Item>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        if self.throttling {
+            if let Some(sleep) = self.sleep.as_mut() {
+                if sleep.as_mut().poll(context).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+            self.throttling = false;
+        }
+        match Pin::new(&mut self.receiver).poll_next(context) {
+            Poll::Ready(Some(message)) => {
+                if let Message::Entry { .. } = message {
+                    let deadline = Instant::now() + self.interval;
+                    match self.sleep.as_mut() {
+                        Some(sleep) => sleep.as_mut().reset(deadline),
+                        None => self.sleep = Some(Box::pin(tokio::time::sleep_until(deadline))),
+                    }
+                    self.throttling = true;
+                }
+                Poll::Ready(Some(message))
+            }
+            Poll::Ready(None) => {
+                self.terminated = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<Item> FusedStream for Throttled<Item>
+where
+    Item: Clone,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
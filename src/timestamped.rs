@@ -0,0 +1,54 @@
+use std::time::Instant;
+
+use crate::{SendError, Sender};
+
+/// An item stamped with the instant it was sent.
+///
+/// This is what flows through a [`crate::channel_timestamped`] splaycast, so that a
+/// [`crate::buffer_policy::BufferAgePolicy`] can be used without writing your own
+/// timestamp wrapper type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timestamped<T> {
+    /// The item that was sent.
+    pub item: T,
+    /// When the item was sent.
+    pub timestamp: Instant,
+}
+
+impl<T> Timestamped<T> {
+    /// Stamp an item with the current instant.
+    pub fn now(item: T) -> Self {
+        Self {
+            item,
+            timestamp: Instant::now(),
+        }
+    }
+
+    /// Unwrap the item, discarding the timestamp.
+    pub fn into_inner(self) -> T {
+        self.item
+    }
+}
+
+/// A [`Sender`] that stamps each item with the instant it was sent.
+pub struct TimestampedSender<T> {
+    sender: Sender<Timestamped<T>>,
+}
+
+impl<T> TimestampedSender<T> {
+    pub(crate) fn new(sender: Sender<Timestamped<T>>) -> Self {
+        Self { sender }
+    }
+
+    /// Stamp a value with the current instant and send it. See [`Sender::send`].
+    pub fn send(&self, item: T) -> Result<(), SendError<T>> {
+        self.sender
+            .send(Timestamped::now(item))
+            .map_err(|error| error.map(Timestamped::into_inner))
+    }
+
+    /// The underlying Sender, for everything other than plain sends.
+    pub fn sender(&self) -> &Sender<Timestamped<T>> {
+        &self.sender
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    collections::VecDeque,
+    io::IoSlice,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+
+use crate::{Message, Receiver, Replay, SubscriptionMode};
+
+/// A Receiver of [`Bytes`] that hands out buffered entries as a batch of [`IoSlice`]s, from
+/// [`Receiver::into_vectored`].
+///
+/// This is for transports with vectored writes, like `write_vectored` on a socket: a
+/// subscriber that has fallen a few entries behind flushes all of them with one syscall
+/// instead of one per entry. Write what you can, then [`VectoredReceiver::consume`] the
+/// number of bytes that were written. Partial writes are fine; the rest is offered again
+/// on the next poll.
+pub struct VectoredReceiver<Mode = Replay>
+where
+    Mode: SubscriptionMode,
+{
+    receiver: Receiver<Bytes, Mode>,
+    /// Messages received but not yet written. Entries are offered as slices up to the
+    /// first lag or reset.
+    queue: VecDeque<Message<Bytes>>,
+    /// How much of the front entry has already been written.
+    written: usize,
+    max_slices: usize,
+    batch: Vec<Message<Bytes>>,
+    ended: bool,
+}
+
+impl<Mode> std::fmt::Debug for VectoredReceiver<Mode>
+where
+    Mode: SubscriptionMode,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VectoredReceiver")
+            .field("receiver", &self.receiver)
+            .field("queued", &self.queue.len())
+            .field("written", &self.written)
+            .field("max_slices", &self.max_slices)
+            .finish()
+    }
+}
+
+impl<Mode> Receiver<Bytes, Mode>
+where
+    Mode: SubscriptionMode,
+{
+    /// Convert this Receiver into a [`VectoredReceiver`], which offers up to `max_slices`
+    /// entries per batch. Keep `max_slices` at or below your platform's `IOV_MAX`.
+    pub fn into_vectored(self, max_slices: usize) -> VectoredReceiver<Mode> {
+        let max_slices = max_slices.max(1);
+        VectoredReceiver {
+            receiver: self,
+            queue: VecDeque::with_capacity(max_slices),
+            written: 0,
+            max_slices,
+            batch: Vec::with_capacity(max_slices),
+            ended: false,
+        }
+    }
+}
+
+impl<Mode> VectoredReceiver<Mode>
+where
+    Mode: SubscriptionMode,
+{
+    /// Poll for the entries that are ready to write, as one batch of slices in a
+    /// [`Message::Entry`]. Lag and resets come through in order, between batches.
+    ///
+    /// Polling again without consuming offers the same entries, plus any that arrived
+    /// since, up to `max_slices`.
+    pub fn poll_ready_slices(
+        &mut self,
+        context: &mut Context<'_>,
+    ) -> Poll<Option<Message<Vec<IoSlice<'_>>>>> {
+        self.fill(context);
+        match self.queue.pop_front() {
+            None if self.ended => return Poll::Ready(None),
+            None => return Poll::Pending,
+            Some(Message::Lagged { count }) => return Poll::Ready(Some(Message::Lagged { count })),
+            Some(Message::Reset { epoch }) => return Poll::Ready(Some(Message::Reset { epoch })),
+            Some(entry) => self.queue.push_front(entry),
+        }
+        let written = self.written;
+        let slices = self
+            .queue
+            .iter()
+            .map_while(|message| match message {
+                Message::Entry { item } => Some(item),
+                _ => None,
+            })
+            .enumerate()
+            .map(|(i, item)| match i {
+                0 => IoSlice::new(&item[written..]),
+                _ => IoSlice::new(item),
+            })
+            .collect();
+        Poll::Ready(Some(Message::Entry { item: slices }))
+    }
+
+    /// Mark `bytes` from the front of the last batch as written.
+    pub fn consume(&mut self, mut bytes: usize) {
+        while let Some(Message::Entry { item }) = self.queue.front() {
+            let remaining = item.len() - self.written;
+            if bytes < remaining {
+                self.written += bytes;
+                return;
+            }
+            bytes -= remaining;
+            self.written = 0;
+            self.queue.pop_front();
+        }
+    }
+
+    /// Get back the Receiver. Entries that were received but not consumed are lost.
+    pub fn into_inner(self) -> Receiver<Bytes, Mode> {
+        self.receiver
+    }
+
+    /// Top up the queue from the Receiver. This only waits if there is nothing to offer.
+    fn fill(&mut self, context: &mut Context<'_>) {
+        let room = self.max_slices.saturating_sub(self.queue.len());
+        if self.ended || room == 0 {
+            return;
+        }
+        match self
+            .receiver
+            .poll_next_batch(context, &mut self.batch, room)
+        {
+            Poll::Ready(0) => self.ended = true,
+            Poll::Ready(_) => self.queue.extend(self.batch.drain(..)),
+            Poll::Pending => (),
+        }
+    }
+}
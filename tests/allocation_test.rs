@@ -0,0 +1,159 @@
+//! Fallible buffer growth, under an allocator that refuses large allocations on demand. This
+//! is its own test binary so that the allocator doesn't affect other tests.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    pin::pin,
+    task::{Context, Poll},
+};
+
+use futures::{task::noop_waker_ref, Future, Stream};
+use splaycast::{
+    buffer_policy::{
+        BufferInstruction, BufferLengthPolicy, BufferPolicy, PinInstruction, PolicyContext,
+    },
+    GrowthFailure, Message, Stats,
+};
+
+struct LimitedAllocator;
+
+thread_local! {
+    /// Allocations of this many bytes or more fail, on this thread only.
+    static LIMIT: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+unsafe impl GlobalAlloc for LimitedAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let limit = LIMIT.try_with(Cell::get).unwrap_or(usize::MAX);
+        if limit <= layout.size() {
+            return std::ptr::null_mut();
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: LimitedAllocator = LimitedAllocator;
+
+const LIMIT_BYTES: usize = 4096;
+
+/// Publish `count` items one Engine pass at a time, with large allocations failing, then
+/// lift the limit and run the Engine once more.
+#[allow(clippy::expect_used)] // i mean, it's a test
+fn run(response: GrowthFailure, count: usize) -> (Stats, Stats, Vec<usize>) {
+    let (sender, mut engine, splaycast) =
+        splaycast::channel_with_policy(count, BufferLengthPolicy::new(count));
+    engine.set_fallible_growth(response);
+    let mut receiver = splaycast.subscribe();
+    let stats = splaycast.stats_handle();
+    let mut context = Context::from_waker(noop_waker_ref());
+
+    LIMIT.set(LIMIT_BYTES);
+    for i in 0..count {
+        sender.send(i).expect("room in the send buffer");
+        assert_eq!(Poll::Pending, pin!(&mut engine).poll(&mut context));
+    }
+    LIMIT.set(usize::MAX);
+    let limited = stats.get().expect("the channel is alive");
+
+    // The first pass publishes held items, and the next one resumes intake.
+    for _ in 0..2 {
+        assert_eq!(Poll::Pending, pin!(&mut engine).poll(&mut context));
+    }
+    let mut received = Vec::new();
+    while let Poll::Ready(Some(message)) = pin!(&mut receiver).poll_next(&mut context) {
+        if let Message::Entry { item } = message {
+            received.push(item);
+        }
+    }
+    (
+        limited,
+        stats.get().expect("the channel is alive"),
+        received,
+    )
+}
+
+#[test]
+fn fallible_growth() {
+    let (limited, _, received) = run(GrowthFailure::Shed, 1000);
+    assert!(0 < limited.allocation_failures);
+    assert!(0 < limited.evictions, "entries were shed to make room");
+    assert_eq!(1000, limited.messages_published);
+    assert!(limited.healthy);
+    assert_eq!(Some(&999), received.last());
+
+    let (limited, _, received) = run(GrowthFailure::MarkUnhealthy, 1000);
+    assert!(0 < limited.allocation_failures);
+    assert!(!limited.healthy);
+    assert!(
+        limited.messages_published < 1000,
+        "items that didn't fit were dropped"
+    );
+    assert_eq!(limited.messages_published as usize, received.len());
+
+    let (limited, after, received) = run(GrowthFailure::Pause, 1000);
+    assert!(0 < limited.allocation_failures);
+    assert!(limited.healthy);
+    assert!(limited.messages_published < 1000, "intake paused");
+    assert_eq!(
+        1000, after.messages_published,
+        "held items are published once the buffer can grow"
+    );
+    assert_eq!((0..1000).collect::<Vec<_>>(), received);
+}
+
+/// Pins every item, leaving it pinned until the application unpins it.
+struct PinningPolicy(BufferLengthPolicy);
+
+impl BufferPolicy<usize> for PinningPolicy {
+    fn buffer_tail_policy(
+        &mut self,
+        tail_item: &usize,
+        context: &PolicyContext,
+    ) -> BufferInstruction {
+        BufferPolicy::<usize>::buffer_tail_policy(&mut self.0, tail_item, context)
+    }
+
+    fn on_before_send(&mut self, new_item: &mut usize) {
+        self.0.on_before_send(new_item)
+    }
+
+    fn on_after_pop(&mut self, popped_item: &usize) {
+        self.0.on_after_pop(popped_item)
+    }
+
+    fn pin_policy(&mut self, _new_item: &usize) -> PinInstruction {
+        PinInstruction::Pin
+    }
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn shedding_unpinned_entries() {
+    let (sender, mut engine, splaycast) =
+        splaycast::channel_with_policy(1000, PinningPolicy(BufferLengthPolicy::new(1000)));
+    engine.set_fallible_growth(GrowthFailure::Shed);
+    splaycast.unpin_through(u64::MAX);
+    let stats = splaycast.stats_handle();
+    let mut context = Context::from_waker(noop_waker_ref());
+
+    LIMIT.set(LIMIT_BYTES);
+    for i in 0..1000 {
+        sender.send(i).expect("room in the send buffer");
+        assert_eq!(Poll::Pending, pin!(&mut engine).poll(&mut context));
+    }
+    LIMIT.set(usize::MAX);
+    let limited = stats.get().expect("the channel is alive");
+    assert!(0 < limited.allocation_failures);
+    assert!(
+        0 < limited.evictions,
+        "entries that were unpinned are shed like any other"
+    );
+    assert_eq!(1000, limited.messages_published);
+    assert!(limited.healthy);
+}
@@ -0,0 +1,353 @@
+//! The atomics audit. Every atomic field in `src` carries a `// sync(...)` annotation with
+//! the orderings it may be accessed with and what it pairs with, every access uses one of
+//! those orderings, and `ATOMICS.md` is generated from the annotations.
+//!
+//! Regenerate `ATOMICS.md` with `SPLAYCAST_BLESS=1 cargo test --test atomics_audit_test`.
+//! Built with `--cfg splaycast_audit`, the accesses recorded at runtime are checked too.
+
+use std::{collections::BTreeMap, fmt::Write, path::Path};
+
+/// The atomics facade itself, and the simulation harness's wake counter, which is not
+/// channel state.
+const UNAUDITED: &[&str] = &["src/audit.rs", "src/sync.rs", "src/simulate.rs"];
+
+const OPERATIONS: &[&str] = &[
+    "load",
+    "store",
+    "swap",
+    "fetch_add",
+    "fetch_sub",
+    "fetch_max",
+    "fetch_update",
+];
+
+#[derive(Debug)]
+struct Annotation {
+    file: String,
+    owner: String,
+    field: String,
+    orderings: Vec<String>,
+    pairing: String,
+}
+
+fn sources() -> BTreeMap<String, String> {
+    fn visit(directory: &Path, sources: &mut BTreeMap<String, String>) {
+        for entry in std::fs::read_dir(directory).expect("src is readable") {
+            let path = entry.expect("src is readable").path();
+            if path.is_dir() {
+                visit(&path, sources);
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                let name = path.to_string_lossy().replace('\\', "/");
+                if !UNAUDITED.contains(&name.as_str()) {
+                    let source = std::fs::read_to_string(&path).expect("source is readable");
+                    sources.insert(name, source);
+                }
+            }
+        }
+    }
+    let mut sources = BTreeMap::new();
+    visit(Path::new("src"), &mut sources);
+    sources
+}
+
+fn is_atomic_type(field_type: &str) -> bool {
+    ["AtomicBool", "AtomicU64", "AtomicUsize"]
+        .iter()
+        .any(|atomic| field_type.contains(atomic))
+}
+
+/// Parse the annotations, or describe every atomic field that is missing one.
+fn annotations(sources: &BTreeMap<String, String>) -> Result<Vec<Annotation>, Vec<String>> {
+    let mut annotations = Vec::new();
+    let mut problems = Vec::new();
+    for (file, source) in sources {
+        let mut owner = String::new();
+        let mut pending: Option<(Vec<String>, String)> = None;
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            let declaration = line
+                .trim_start_matches("pub ")
+                .trim_start_matches("pub(crate) ");
+            if let Some(rest) = declaration.strip_prefix("struct ") {
+                owner = rest
+                    .split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+            }
+            if let Some(rest) = line.strip_prefix("// sync(") {
+                let (orderings, pairing) = rest.split_once("):").unwrap_or((rest, ""));
+                let orderings = orderings.split(',').map(|o| o.trim().to_string()).collect();
+                pending = Some((orderings, pairing.trim().to_string()));
+                continue;
+            }
+            if let (Some((_, pairing)), Some(more)) = (&mut pending, line.strip_prefix("// ")) {
+                pairing.push(' ');
+                pairing.push_str(more);
+                continue;
+            }
+            let field = declaration
+                .split_once(": ")
+                .filter(|(name, field_type)| {
+                    name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                        && field_type.ends_with(',')
+                        && !field_type.contains('(')
+                        && is_atomic_type(field_type)
+                })
+                .map(|(name, _)| name.to_string());
+            match (field, pending.take()) {
+                (Some(field), Some((orderings, pairing))) => annotations.push(Annotation {
+                    file: file.clone(),
+                    owner: owner.clone(),
+                    field,
+                    orderings,
+                    pairing,
+                }),
+                (Some(field), None) => problems.push(format!(
+                    "{file}:{}: {owner}::{field} has no // sync(...) annotation",
+                    index + 1
+                )),
+                (None, Some(_)) => problems.push(format!(
+                    "{file}:{}: a // sync(...) annotation isn't followed by an atomic field",
+                    index + 1
+                )),
+                (None, None) => (),
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(annotations)
+    } else {
+        Err(problems)
+    }
+}
+
+/// The annotated atomics that an access resolves to, given the source text before the
+/// accessing method's `.`. That's the field it's called on, a binding or element named
+/// after part of a field in the same file, like `position` for `positions`, or else a field
+/// from another file, like the Engine's updates to the stats counters.
+fn resolve<'a>(annotations: &'a [Annotation], file: &str, before: &str) -> Vec<&'a Annotation> {
+    let before = before.trim_end();
+    let before = match before.strip_suffix(']') {
+        Some(indexed) => indexed.rsplit_once('[').map_or(indexed, |(field, _)| field),
+        None => before,
+    };
+    let identifier: String = before
+        .chars()
+        .rev()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let matching = |same_file: bool, exact: bool| -> Vec<&'a Annotation> {
+        annotations
+            .iter()
+            .filter(|annotation| !same_file || annotation.file == file)
+            .filter(|annotation| {
+                if exact {
+                    annotation.field == identifier
+                } else {
+                    annotation.field.contains(&identifier)
+                }
+            })
+            .collect()
+    };
+    [(true, true), (true, false), (false, true)]
+        .into_iter()
+        .map(|(same_file, exact)| matching(same_file, exact))
+        .find(|candidates| !identifier.is_empty() && !candidates.is_empty())
+        .unwrap_or_default()
+}
+
+/// Describe the problem if an access doesn't resolve to annotated atomics that allow its
+/// orderings.
+fn check_access(
+    annotations: &[Annotation],
+    file: &str,
+    line: usize,
+    before: &str,
+    operation: &str,
+    orderings: &[String],
+) -> Option<String> {
+    let candidates = resolve(annotations, file, before);
+    if candidates.is_empty() {
+        return Some(format!(
+            "{file}:{line}: {operation} isn't on an annotated atomic"
+        ));
+    }
+    candidates.iter().find_map(|annotation| {
+        orderings
+            .iter()
+            .find(|ordering| !annotation.orderings.contains(ordering))
+            .map(|ordering| {
+                format!(
+                    "{file}:{line}: {operation}({ordering}) on {}::{}, which allows {}",
+                    annotation.owner,
+                    annotation.field,
+                    annotation.orderings.join(", ")
+                )
+            })
+    })
+}
+
+fn document(annotations: &[Annotation]) -> String {
+    let mut document = String::from(
+        "# Atomics\n\
+         \n\
+         Every atomic in splaycast, the orderings it may be accessed with, and what it pairs\n\
+         with. This is generated from the `// sync(...)` annotations in `src` by\n\
+         `tests/atomics_audit_test.rs`, which also checks every access against them. Don't edit\n\
+         it by hand: run `SPLAYCAST_BLESS=1 cargo test --test atomics_audit_test`.\n\
+         \n\
+         Built with `--cfg splaycast_audit`, the atomics are recording wrappers, and the same\n\
+         test checks the accesses recorded at runtime. To run the tests under ThreadSanitizer:\n\
+         \n\
+         ```text\n\
+         RUSTFLAGS=\"-Zsanitizer=thread --cfg splaycast_audit\" \\\n    \
+         cargo +nightly test -Zbuild-std --target x86_64-unknown-linux-gnu --all-features\n\
+         ```\n\
+         \n\
+         | Atomic | Orderings | Pairing |\n\
+         | --- | --- | --- |\n",
+    );
+    for annotation in annotations {
+        writeln!(
+            document,
+            "| `{}` `{}::{}` | {} | {} |",
+            annotation.file,
+            annotation.owner,
+            annotation.field,
+            annotation.orderings.join(", "),
+            annotation.pairing
+        )
+        .expect("strings are infallible");
+    }
+    document
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn every_atomic_is_annotated() {
+    let sources = sources();
+    let annotations = annotations(&sources).unwrap_or_else(|problems| {
+        panic!("unannotated atomics:\n{}", problems.join("\n"));
+    });
+
+    let mut problems = Vec::new();
+    for (file, source) in &sources {
+        for operation in OPERATIONS {
+            let call = format!(".{operation}(");
+            for (start, _) in source.match_indices(&call) {
+                let arguments = &source[start + call.len()..];
+                let mut depth = 1;
+                let end = arguments
+                    .find(|c| {
+                        depth += match c {
+                            '(' => 1,
+                            ')' => -1,
+                            _ => 0,
+                        };
+                        depth == 0
+                    })
+                    .expect("balanced parentheses");
+                let orderings: Vec<String> = arguments[..end]
+                    .split("Ordering::")
+                    .skip(1)
+                    .map(|rest| rest.chars().take_while(|c| c.is_alphabetic()).collect())
+                    .collect();
+                if orderings.is_empty() {
+                    continue; // Not an atomic, like ArcSwap::load.
+                }
+                let line = source[..start].lines().count();
+                problems.extend(check_access(
+                    &annotations,
+                    file,
+                    line,
+                    &source[..start],
+                    operation,
+                    &orderings,
+                ));
+            }
+        }
+    }
+    assert!(problems.is_empty(), "{}", problems.join("\n"));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn atomics_document_is_current() {
+    let annotations = annotations(&sources()).unwrap_or_default();
+    let document = document(&annotations);
+    if std::env::var_os("SPLAYCAST_BLESS").is_some() {
+        std::fs::write("ATOMICS.md", document).expect("ATOMICS.md is writable");
+        return;
+    }
+    let current = std::fs::read_to_string("ATOMICS.md").unwrap_or_default();
+    assert!(
+        current == document,
+        "ATOMICS.md is stale: run SPLAYCAST_BLESS=1 cargo test --test atomics_audit_test"
+    );
+}
+
+#[cfg(splaycast_audit)]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn recorded_accesses_match_annotations() {
+    use futures::StreamExt;
+    use splaycast::{buffer_policy::BufferLengthPolicy, Message};
+
+    // Exercise the common paths: publishing, receiving, lag, stats and termination.
+    let (sender, engine, splaycast) =
+        splaycast::channel_with_policy(16, BufferLengthPolicy::new(4));
+    let mut lagging = splaycast.subscribe();
+    let mut receiver = splaycast.subscribe_at_tail();
+    let stats = splaycast.stats_handle();
+    let engine = tokio::spawn(engine);
+    for i in 0..8 {
+        sender.send(i).expect("room in the send buffer");
+        assert_eq!(
+            Some(Message::Entry { item: i }),
+            receiver.next().await,
+            "the tail receiver keeps up"
+        );
+    }
+    assert!(matches!(lagging.next().await, Some(Message::Lagged { .. })));
+    assert!(stats.get().is_some());
+    splaycast.seal();
+    drop(splaycast);
+    drop(sender);
+    while receiver.next().await.is_some() {}
+    engine.await.expect("the engine completes");
+
+    let sources = sources();
+    let annotations = annotations(&sources).unwrap_or_default();
+    let accesses: Vec<_> = splaycast::audit::accesses()
+        .into_iter()
+        .filter(|access| sources.contains_key(access.file))
+        .collect();
+    assert!(!accesses.is_empty(), "the workload touches atomics");
+    let problems: Vec<_> = accesses
+        .iter()
+        .filter_map(|access| {
+            let source = &sources[access.file];
+            let method = source
+                .split_inclusive('\n')
+                .take(access.line as usize - 1)
+                .map(str::len)
+                .sum::<usize>()
+                + access.column as usize
+                - 1;
+            let before = source[..method].trim_end().strip_suffix('.')?;
+            check_access(
+                &annotations,
+                access.file,
+                access.line as usize,
+                before,
+                access.operation,
+                &[access.ordering.to_string()],
+            )
+        })
+        .collect();
+    assert!(problems.is_empty(), "{}", problems.join("\n"));
+}
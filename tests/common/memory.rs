@@ -0,0 +1,84 @@
+//! Memory footprint measurements, shared by the memory bench and the memory regression test.
+//!
+//! Install [`CountingAllocator`] as the `#[global_allocator]` of the including crate. The
+//! measurements are only meaningful when nothing else is allocating concurrently.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    pin::pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+
+use futures::{task::noop_waker_ref, Future, Stream};
+use splaycast::buffer_policy::BufferLengthPolicy;
+
+pub struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// How many bytes are currently allocated on the heap.
+pub fn allocated_bytes() -> usize {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+/// Steady-state heap bytes per parked subscriber: the Engine's park list entry and the
+/// Waker. Receivers themselves are owned by the caller, so they are not counted, and
+/// neither is the bookkeeping of them joining.
+pub fn bytes_per_parked_subscriber(subscribers: usize) -> f64 {
+    let (_sender, mut engine, splaycast) = splaycast::channel::<usize>(4);
+    let mut receivers: Vec<_> = (0..subscribers).map(|_| splaycast.subscribe()).collect();
+    // Warm up the Engine's park list once, so growth is amortized like it is in steady state.
+    park_all(&mut receivers, &mut engine);
+    drop(receivers);
+    // Let the Engine catch up with them leaving.
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let before = allocated_bytes();
+    let mut receivers: Vec<_> = (0..subscribers).map(|_| splaycast.subscribe()).collect();
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    let receivers_bytes = allocated_bytes() - before;
+    park_all(&mut receivers, &mut engine);
+    let parked_bytes = allocated_bytes() - before - receivers_bytes;
+    parked_bytes as f64 / subscribers as f64
+}
+
+/// Steady-state heap bytes per buffered entry of `usize` items.
+pub fn bytes_per_buffered_entry(entries: usize) -> f64 {
+    let (sender, mut engine, _splaycast) =
+        splaycast::channel_with_policy::<usize>(entries, BufferLengthPolicy::new(entries));
+    let before = allocated_bytes();
+    for i in 0..entries {
+        sender.send(i).expect("room in the buffer");
+    }
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    (allocated_bytes() - before) as f64 / entries as f64
+}
+
+fn park_all<F: Future<Output = ()> + Unpin>(
+    receivers: &mut [splaycast::Receiver<usize>],
+    engine: &mut F,
+) {
+    for receiver in receivers.iter_mut() {
+        assert!(pin!(receiver)
+            .poll_next(&mut Context::from_waker(noop_waker_ref()))
+            .is_pending());
+    }
+    assert_eq!(Poll::Pending, poll(engine));
+}
+
+fn poll<F: Future<Output = ()> + Unpin>(future: &mut F) -> Poll<()> {
+    pin!(future).poll(&mut Context::from_waker(noop_waker_ref()))
+}
@@ -0,0 +1,26 @@
+//! Regression tracking for per-subscriber and per-entry memory. This is its own test binary
+//! so that nothing else allocates while it measures.
+
+#[path = "common/memory.rs"]
+mod memory;
+
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
+
+#[test]
+fn memory_footprint() {
+    // Parked subscribers cost a park list id and a Waker in a hash map. The noop waker
+    // used here has no heap of its own; real wakers are usually an Arc clone.
+    let per_subscriber = memory::bytes_per_parked_subscriber(100_000);
+    assert!(
+        per_subscriber <= 64.0,
+        "bytes per parked subscriber regressed: {per_subscriber}"
+    );
+
+    // Entries cost their id, flags, and item in the buffer, plus VecDeque slack.
+    let per_entry = memory::bytes_per_buffered_entry(4096);
+    assert!(
+        per_entry <= 48.0,
+        "bytes per buffered entry regressed: {per_entry}"
+    );
+}
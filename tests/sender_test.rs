@@ -0,0 +1,445 @@
+use std::{
+    pin::pin,
+    task::{Context, Poll},
+};
+
+use futures::{stream::FusedStream, task::noop_waker_ref, Future, Stream};
+use splaycast::{buffer_policy::BufferLengthPolicy, Message, SendError};
+use tokio_stream::StreamExt;
+
+/// These tests are doing raw poll rather than await to make sure the futures are doing exactly what they are supposed to do, when they are supposed to do it.
+fn poll<T, F: futures::Future<Output = T> + Unpin>(future: &mut F) -> Poll<T> {
+    pin!(future).poll(&mut Context::from_waker(noop_waker_ref()))
+}
+
+fn poll_next<T, F: futures::Stream<Item = T> + Unpin>(stream: &mut F) -> Poll<Option<T>> {
+    pin!(stream).poll_next(&mut Context::from_waker(noop_waker_ref()))
+}
+
+fn entry<T>(item: T) -> Option<Message<T>> {
+    Some(Message::Entry { item })
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn close_sender() {
+    let (sender, mut engine, splaycast) = splaycast::channel(4);
+    let mut subscriber = splaycast.subscribe();
+
+    sender.send(1).expect("room in the buffer");
+    sender.send(2).expect("room in the buffer");
+    sender.close();
+    assert!(sender.is_closed());
+    assert_eq!(
+        Err(SendError::Closed(3)),
+        sender.send(3),
+        "closed senders give the item back"
+    );
+
+    assert_eq!(
+        Poll::Ready(()),
+        poll(&mut engine),
+        "the engine drains what was sent, then sees the end of the stream"
+    );
+    assert_eq!(
+        Poll::Ready(None),
+        poll_next(&mut subscriber),
+        "the splaycast is terminated once the sender is closed and drained"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn close_sender_delivers_sent_items() {
+    let (sender, mut engine, splaycast) = splaycast::channel(4);
+    let mut subscriber = splaycast.subscribe();
+    assert_eq!(Poll::Pending, poll_next(&mut subscriber));
+
+    sender.send(1).expect("room in the buffer");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    sender.close();
+
+    assert_eq!(
+        Poll::Ready(entry(1)),
+        poll(&mut pin!(subscriber.next())),
+        "items sent before close are delivered"
+    );
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(Poll::Ready(None), poll_next(&mut subscriber));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn sender_occupancy() {
+    let (sender, mut engine, _splaycast) = splaycast::channel(2);
+    assert_eq!(2, sender.capacity());
+    assert!(sender.is_empty());
+
+    sender.send(1).expect("room in the buffer");
+    assert_eq!(1, sender.len());
+    assert!(!sender.is_full());
+
+    sender.send(2).expect("room in the buffer");
+    assert!(sender.is_full());
+    assert_eq!(
+        Err(SendError::Full(3)),
+        sender.send(3),
+        "full senders give the item back"
+    );
+    sender.send_priority(0).expect("room in the priority lane");
+    assert_eq!(3, sender.len(), "the priority lane is waiting too");
+
+    assert_eq!(Poll::Pending, poll(&mut engine), "drain the send buffer");
+    assert!(sender.is_empty());
+    assert_eq!(2, sender.capacity());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn blocking_send() {
+    let (sender, mut engine, splaycast) =
+        splaycast::channel_with_policy(1, BufferLengthPolicy::new(4));
+    let mut subscriber = splaycast.subscribe();
+    sender.send(1).expect("room in the buffer");
+
+    let producer = std::thread::spawn(move || {
+        let result = sender.blocking_send(2);
+        (sender, result)
+    });
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    assert_eq!(Poll::Pending, poll(&mut engine), "drain the send buffer");
+
+    let (sender, result) = producer.join().expect("producer thread completes");
+    assert_eq!(
+        Ok(()),
+        result,
+        "the blocked send completes once there's room"
+    );
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+
+    sender.send(3).expect("room in the buffer");
+    drop(engine);
+    assert_eq!(
+        Err(SendError::Closed(4)),
+        sender.blocking_send(4),
+        "nobody is draining the send buffer anymore"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn send_all() {
+    let (sender, mut engine, splaycast) = splaycast::channel(2);
+    let mut subscriber = splaycast.subscribe();
+
+    let mut history = 1..=5;
+    assert_eq!(
+        Err((2, SendError::Full(3))),
+        sender.send_all(&mut history),
+        "only 2 fit, and the 3rd is given back"
+    );
+    assert_eq!(Some(4), history.next(), "the rest are left in the iterator");
+
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+    assert_eq!(
+        Ok(1),
+        sender.send_all(history),
+        "there's room for the rest now"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn extend() {
+    let (sender, mut engine, splaycast) = splaycast::channel(4);
+    let mut subscriber = splaycast.subscribe();
+
+    let mut history = 1..=6;
+    assert_eq!(
+        Err((4, SendError::Full(5))),
+        sender.extend(&mut history),
+        "only 4 fit, and the 5th is given back"
+    );
+    assert_eq!(Some(6), history.next(), "the rest are left in the iterator");
+
+    assert_eq!(
+        Poll::Pending,
+        poll(&mut engine),
+        "one pass absorbs the batch"
+    );
+    for i in 1..=4 {
+        assert_eq!(Poll::Ready(entry(i)), poll_next(&mut subscriber));
+    }
+    assert_eq!(
+        Ok(2),
+        sender.extend([5, 6]),
+        "an exhausted iterator was all accepted"
+    );
+
+    sender.close();
+    assert_eq!(
+        Err((0, SendError::Closed(7))),
+        sender.extend(7..10),
+        "closed senders accept nothing"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn send_stream() {
+    let (sender, engine, splaycast) =
+        splaycast::channel_with_policy(2, BufferLengthPolicy::new(16));
+    let mut subscriber = splaycast.subscribe();
+    tokio::spawn(engine);
+
+    let sent = sender.send_stream(futures::stream::iter(0..10_usize)).await;
+    assert_eq!(Ok(10), sent, "waits for room rather than giving up");
+
+    for i in 0..10 {
+        assert_eq!(
+            entry(i),
+            subscriber.next().await,
+            "everything arrives in order"
+        );
+    }
+
+    sender.close();
+    let mut rest = futures::stream::iter(10..20_usize);
+    assert_eq!(
+        Err((0, 10)),
+        sender.send_stream(&mut rest).await,
+        "the item that couldn't be sent comes back"
+    );
+    assert_eq!(Some(11), rest.next().await, "the rest stays in the stream");
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn send_priority() {
+    let (sender, mut engine, splaycast) = splaycast::channel(4);
+    let mut subscriber = splaycast.subscribe();
+
+    sender.send(1).expect("room in the buffer");
+    sender.send(2).expect("room in the buffer");
+    sender.send_priority(10).expect("room in the priority lane");
+    sender.send_priority(11).expect("room in the priority lane");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    for expected in [10, 11, 1, 2] {
+        assert_eq!(
+            Poll::Ready(entry(expected)),
+            poll_next(&mut subscriber),
+            "priority items jump the queue, and each lane stays in order"
+        );
+    }
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn unbounded_sender() {
+    let (sender, mut engine, splaycast) = splaycast::unbounded_channel(BufferLengthPolicy::new(2));
+    let mut subscriber = splaycast.subscribe();
+    assert_eq!(usize::MAX, sender.capacity());
+
+    for i in 0..1000 {
+        sender.send(i).expect("unbounded senders are never full");
+    }
+    assert!(!sender.is_full());
+    assert_eq!(1000, sender.len());
+
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert!(sender.is_empty());
+    assert_eq!(
+        Poll::Ready(Some(Message::Lagged { count: 998 })),
+        poll_next(&mut subscriber),
+        "the buffer policy still controls retention"
+    );
+    assert_eq!(Poll::Ready(entry(998)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(999)), poll_next(&mut subscriber));
+
+    sender.close();
+    assert_eq!(Err(SendError::Closed(1000)), sender.send(1000));
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn send_timeout() {
+    let (sender, engine, splaycast) = splaycast::channel_with_policy(1, BufferLengthPolicy::new(4));
+    let mut subscriber = splaycast.subscribe();
+    sender.send(1).expect("room in the buffer");
+
+    assert_eq!(
+        Err(SendError::Full(2)),
+        sender
+            .send_timeout(2, std::time::Duration::from_millis(10))
+            .await,
+        "nobody drained the send buffer in time"
+    );
+
+    tokio::spawn(engine);
+    sender
+        .send_timeout(2, std::time::Duration::from_secs(1))
+        .await
+        .expect("the engine makes room");
+    assert_eq!(entry(1), subscriber.next().await);
+    assert_eq!(entry(2), subscriber.next().await);
+
+    sender.close();
+    assert_eq!(
+        Err(SendError::Closed(3)),
+        sender
+            .send_timeout(3, std::time::Duration::from_secs(1))
+            .await,
+        "closed senders don't wait"
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test(start_paused = true)]
+async fn send_timeout_does_not_pile_up_wakers() {
+    struct Nothing;
+    impl futures::task::ArcWake for Nothing {
+        fn wake_by_ref(_arc_self: &std::sync::Arc<Self>) {}
+    }
+    let wake_target = std::sync::Arc::new(Nothing);
+    let waker = futures::task::waker(wake_target.clone());
+    let mut context = Context::from_waker(&waker);
+
+    let (sender, mut stream) = splaycast::Sender::pair_with_capacity(1);
+    sender.send(1).expect("room in the buffer");
+    for _ in 0..100 {
+        let mut send = pin!(sender.send_timeout(2, std::time::Duration::from_millis(1)));
+        assert!(send.as_mut().poll(&mut context).is_pending());
+        assert!(send.as_mut().poll(&mut context).is_pending());
+    }
+    assert!(
+        std::sync::Arc::strong_count(&wake_target) < 5,
+        "abandoned sends don't leave their wakers behind"
+    );
+
+    let mut send = pin!(sender.send_timeout(2, std::time::Duration::from_secs(1)));
+    assert!(send.as_mut().poll(&mut context).is_pending());
+    assert_eq!(Poll::Ready(Some(1)), poll_next(&mut stream));
+    assert_eq!(Poll::Ready(Ok(())), send.as_mut().poll(&mut context));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn sender_stream_pair() {
+    let (sender, mut stream) = splaycast::Sender::pair_with_capacity(4);
+    assert!(stream.is_empty());
+    sender.send(1).expect("room in the buffer");
+    sender.send_priority(0).expect("room in the priority lane");
+    assert_eq!(2, stream.len());
+
+    stream.close();
+    assert!(sender.is_closed(), "closing the stream closes the sender");
+    assert_eq!(Err(SendError::Closed(2)), sender.send(2));
+
+    assert_eq!(Poll::Ready(Some(0)), poll_next(&mut stream));
+    assert_eq!(
+        Poll::Ready(Some(1)),
+        poll_next(&mut stream),
+        "items that were already sent are still drained"
+    );
+    assert!(!stream.is_terminated());
+    assert_eq!(Poll::Ready(None), poll_next(&mut stream));
+    assert!(stream.is_terminated());
+
+    let (sender, mut stream) = splaycast::Sender::pair_with_capacity(0);
+    sender.send(1).expect("a capacity of 0 still holds an item");
+    assert_eq!(Err(SendError::Full(2)), sender.send(2));
+    assert_eq!(Poll::Ready(Some(1)), poll_next(&mut stream));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn send_racing_close() {
+    for _ in 0..1000 {
+        let (sender, stream) = splaycast::Sender::pair_with_capacity(1024);
+        let sender = std::sync::Arc::new(sender);
+        let producer = std::thread::spawn({
+            let sender = sender.clone();
+            move || {
+                let mut accepted = 0;
+                while sender.send(accepted).is_ok() {
+                    accepted += 1;
+                }
+                accepted
+            }
+        });
+        let closer = std::thread::spawn(move || {
+            std::thread::yield_now();
+            sender.close();
+        });
+        let drained: Vec<usize> = futures::executor::block_on(stream.collect());
+        closer.join().expect("closer doesn't panic");
+        let accepted = producer.join().expect("producer doesn't panic");
+        assert_eq!(
+            (0..accepted).collect::<Vec<_>>(),
+            drained,
+            "every accepted send is delivered, even when it races the close"
+        );
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn send_with_retry() {
+    let (sender, engine, splaycast) = splaycast::channel_with_policy(1, BufferLengthPolicy::new(4));
+    let mut subscriber = splaycast.subscribe();
+    sender.send(1).expect("room in the buffer");
+
+    let policy = splaycast::RetryPolicy::new(std::time::Duration::from_millis(20));
+    assert_eq!(
+        Err(SendError::Full(2)),
+        sender.send_with_retry(2, policy).await,
+        "nobody drained the send buffer before the timeout"
+    );
+
+    let engine = tokio::spawn(engine);
+    sender
+        .send_with_retry(2, policy)
+        .await
+        .expect("the engine makes room");
+    assert_eq!(entry(1), subscriber.next().await);
+    assert_eq!(entry(2), subscriber.next().await);
+
+    engine.abort();
+    assert!(engine.await.is_err(), "the engine was aborted");
+    assert_eq!(
+        Err(SendError::Closed(3)),
+        sender.send_with_retry(3, policy).await,
+        "closed senders don't retry"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn publish_confirmations() {
+    let (sender, mut engine, splaycast) = splaycast::channel(8);
+    let mut confirmations = engine.publish_confirmations();
+    assert_eq!(Poll::Pending, poll_next(&mut confirmations));
+
+    (1..=3).for_each(|i| sender.send(i).expect("room in the buffer"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    sender.send(4).expect("room in the buffer");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    let Poll::Ready(Some((sequence_id, _published_at))) = poll_next(&mut confirmations) else {
+        panic!("expected a confirmation");
+    };
+    assert_eq!(4, sequence_id, "confirmations are coalesced");
+    assert_eq!(Poll::Pending, poll_next(&mut confirmations));
+
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(Poll::Ready(None), poll_next(&mut confirmations));
+    assert!(confirmations.is_terminated());
+}
@@ -4,10 +4,18 @@ use std::{
 };
 
 use futures::{task::noop_waker_ref, Future, Stream};
-use splaycast::{buffer_policy::BufferPolicy, Engine, Message, Splaycast};
+use splaycast::{
+    buffer_policy::{
+        BufferInstruction, BufferLengthPolicy, BufferPolicy, PinInstruction, PolicyContext,
+    },
+    AbandonedBacklog, AdaptiveConflation, ChannelMetadata, Engine, EntryMetadata, Fidelity,
+    Message, MultiEngine, MultiReceiver, ReceiverCursor, ReorderWindow, ResumeAt, Splaycast,
+    SplaycastTemplate, StartAt, SubscribeError, SubscribeOptions, SubscribePermits, TipOnly,
+};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
 use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
 
+#[allow(clippy::type_complexity)]
 fn get_splaycast() -> (
     UnboundedSender<usize>,
     Splaycast<usize>,
@@ -16,6 +24,7 @@ fn get_splaycast() -> (
     get_splaycast_with_buffer(2)
 }
 
+#[allow(clippy::type_complexity)]
 fn get_splaycast_with_buffer(
     length: usize,
 ) -> (
@@ -163,6 +172,7 @@ fn join_active_splaycast() {
     );
 }
 
+#[allow(clippy::expect_used)] // i mean, it's a test
 #[test]
 fn subscriber_count() {
     let (_publish_handle, splaycast, mut _engine) = get_splaycast();
@@ -300,6 +310,7 @@ fn slow_subscriber() {
     );
 }
 
+#[allow(clippy::expect_used)] // i mean, it's a test
 #[test]
 fn drop_splaycast() {
     let (_publish_handle, splaycast, mut engine) = get_splaycast();
@@ -339,6 +350,53 @@ fn drop_splaycast() {
     );
 }
 
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn drop_splaycast_clones() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast();
+    let clone = splaycast.clone();
+    let mut subscriber = clone.subscribe();
+
+    drop(splaycast);
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(
+        Poll::Pending,
+        poll(&mut engine),
+        "a clone keeps the splaycast alive"
+    );
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+
+    drop(clone);
+    assert_eq!(
+        Poll::Ready(()),
+        poll(&mut engine),
+        "the last clone to drop kills the splaycast"
+    );
+    assert_eq!(Poll::Ready(None), poll_next(&mut subscriber));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn close_splaycast() {
+    let (_publish_handle, splaycast, mut engine) = get_splaycast();
+    let _clone = splaycast.clone();
+    let mut subscriber = splaycast.subscribe();
+    assert_eq!(Poll::Pending, poll_next(&mut subscriber));
+    assert!(!splaycast.is_closed());
+    assert!(!subscriber.is_closed());
+
+    splaycast.close();
+    assert!(splaycast.is_closed());
+    assert!(subscriber.is_closed());
+    assert_eq!(
+        Poll::Ready(()),
+        poll(&mut engine),
+        "closing terminates the splaycast even though there are clones"
+    );
+    assert_eq!(Poll::Ready(None), poll_next(&mut subscriber));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
 #[test]
 fn drop_engine() {
     let (_publish_handle, splaycast, mut engine) = get_splaycast();
@@ -372,6 +430,7 @@ fn drop_engine() {
     );
 }
 
+#[allow(clippy::expect_used)] // i mean, it's a test
 #[test]
 fn drop_upstream() {
     let (publish_handle, splaycast, mut engine) = get_splaycast();
@@ -448,3 +507,2962 @@ fn drop_downstreams() {
         "Engine is still happily pending"
     );
 }
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn next_matching() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    publish_handle.send(2).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let mut next_odd = splaycast.next_matching(|item| item % 2 == 1);
+    assert_eq!(
+        Poll::Pending,
+        poll(&mut next_odd),
+        "only entries published after the future was made are considered"
+    );
+    assert_eq!(
+        0,
+        splaycast.subscriber_count(),
+        "a one-shot waiter is not a subscriber"
+    );
+
+    publish_handle.send(4).expect("unbounded send");
+    publish_handle.send(5).expect("unbounded send");
+    publish_handle.send(7).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    assert_eq!(
+        Poll::Ready(Some(5)),
+        poll(&mut next_odd),
+        "resolves with the first match"
+    );
+
+    let mut never = splaycast.next_matching(|item| *item == 100);
+    assert_eq!(Poll::Pending, poll(&mut never));
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(None),
+        poll(&mut never),
+        "the channel closed before a match showed up"
+    );
+}
+
+/// Pins every 10th item as a "keyframe", replacing the previous keyframe.
+struct KeyframePolicy(BufferLengthPolicy);
+
+impl BufferPolicy<usize> for KeyframePolicy {
+    fn buffer_tail_policy(
+        &mut self,
+        tail_item: &usize,
+        context: &PolicyContext,
+    ) -> BufferInstruction {
+        BufferPolicy::<usize>::buffer_tail_policy(&mut self.0, tail_item, context)
+    }
+
+    fn on_before_send(&mut self, new_item: &mut usize) {
+        self.0.on_before_send(new_item)
+    }
+
+    fn on_after_pop(&mut self, popped_item: &usize) {
+        self.0.on_after_pop(popped_item)
+    }
+
+    fn pin_policy(&mut self, new_item: &usize) -> PinInstruction {
+        if new_item.is_multiple_of(10) {
+            PinInstruction::Replace
+        } else {
+            PinInstruction::Unpinned
+        }
+    }
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn pinned_entries() {
+    let (publish_handle, upstream) = unbounded_channel::<usize>();
+    let (mut engine, splaycast) = splaycast::wrap_with_policy(
+        UnboundedReceiverStream::new(upstream),
+        KeyframePolicy(BufferLengthPolicy::new(3)),
+    );
+    (0..15).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let mut joiner = splaycast.subscribe_at_tail();
+    assert_eq!(
+        Poll::Ready(entry(10)),
+        poll_next(&mut joiner),
+        "The latest keyframe replaced 0 and survived the length policy"
+    );
+    assert_eq!(
+        Poll::Ready(lag(2)),
+        poll_next(&mut joiner),
+        "11 and 12 were popped from behind the pinned keyframe"
+    );
+    assert_eq!(Poll::Ready(entry(13)), poll_next(&mut joiner));
+    assert_eq!(Poll::Ready(entry(14)), poll_next(&mut joiner));
+    assert_eq!(Poll::Pending, poll_next(&mut joiner));
+
+    // Unpin the keyframe (item 10 is sequence id 11), and it becomes an ordinary tail.
+    splaycast.unpin_through(11);
+    publish_handle.send(15).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let mut late_joiner = splaycast.subscribe_at_tail();
+    assert_eq!(
+        Poll::Ready(entry(14)),
+        poll_next(&mut late_joiner),
+        "the keyframe was popped once it was unpinned, so the tail is back to 1 past the oldest"
+    );
+}
+
+/// Pins every 10th item, leaving it pinned until the application unpins it.
+struct PinningPolicy(BufferLengthPolicy);
+
+impl BufferPolicy<usize> for PinningPolicy {
+    fn buffer_tail_policy(
+        &mut self,
+        tail_item: &usize,
+        context: &PolicyContext,
+    ) -> BufferInstruction {
+        BufferPolicy::<usize>::buffer_tail_policy(&mut self.0, tail_item, context)
+    }
+
+    fn on_before_send(&mut self, new_item: &mut usize) {
+        self.0.on_before_send(new_item)
+    }
+
+    fn on_after_pop(&mut self, popped_item: &usize) {
+        self.0.on_after_pop(popped_item)
+    }
+
+    fn pin_policy(&mut self, new_item: &usize) -> PinInstruction {
+        if new_item.is_multiple_of(10) {
+            PinInstruction::Pin
+        } else {
+            PinInstruction::Unpinned
+        }
+    }
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn unpin_through() {
+    let (publish_handle, upstream) = unbounded_channel::<usize>();
+    let (mut engine, splaycast) = splaycast::wrap_with_policy(
+        UnboundedReceiverStream::new(upstream),
+        PinningPolicy(BufferLengthPolicy::new(3)),
+    );
+    (0..8).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let mut joiner = splaycast.subscribe_at_tail();
+    assert_eq!(
+        Poll::Ready(entry(0)),
+        poll_next(&mut joiner),
+        "the pinned entry survived overflowing the buffer"
+    );
+    assert_eq!(
+        Poll::Ready(lag(5)),
+        poll_next(&mut joiner),
+        "the entries behind it were popped instead"
+    );
+    assert_eq!(Poll::Ready(entry(6)), poll_next(&mut joiner));
+    assert_eq!(Poll::Ready(entry(7)), poll_next(&mut joiner));
+
+    // Item 0 is sequence id 1.
+    splaycast.unpin_through(1);
+    publish_handle.send(8).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let mut late_joiner = splaycast.subscribe_at_tail();
+    assert_eq!(
+        Poll::Ready(entry(7)),
+        poll_next(&mut late_joiner),
+        "the unpinned entry was evicted like any other tail, so the tail is back to 1 past the oldest"
+    );
+}
+
+/// Keeps a running sum of everything that fell off the buffer.
+struct SummingPolicy(BufferLengthPolicy);
+
+impl BufferPolicy<usize> for SummingPolicy {
+    fn buffer_tail_policy(
+        &mut self,
+        tail_item: &usize,
+        context: &PolicyContext,
+    ) -> BufferInstruction {
+        BufferPolicy::<usize>::buffer_tail_policy(&mut self.0, tail_item, context)
+    }
+
+    fn on_before_send(&mut self, new_item: &mut usize) {
+        self.0.on_before_send(new_item)
+    }
+
+    fn on_after_pop(&mut self, popped_item: &usize) {
+        self.0.on_after_pop(popped_item)
+    }
+
+    fn fold_summary(&mut self, summary: &mut Option<usize>, popped_item: &usize) {
+        *summary = Some(summary.unwrap_or_default() + popped_item);
+    }
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn summary_entry() {
+    let (publish_handle, upstream) = unbounded_channel::<usize>();
+    let (mut engine, splaycast) = splaycast::wrap_with_policy(
+        UnboundedReceiverStream::new(upstream),
+        SummingPolicy(BufferLengthPolicy::new(3)),
+    );
+    let mut laggard = splaycast.subscribe();
+    (1..=6).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(4, splaycast.buffer_len(), "3 entries and the summary");
+
+    let mut joiner = splaycast.subscribe_at_tail();
+    assert_eq!(
+        Poll::Ready(entry(6)),
+        poll_next(&mut joiner),
+        "late joiners start with the summary of 1, 2 and 3"
+    );
+    assert_eq!(Poll::Ready(entry(4)), poll_next(&mut joiner));
+
+    assert_eq!(
+        Poll::Ready(lag(2)),
+        poll_next(&mut laggard),
+        "the summary took the place of the last entry it folded"
+    );
+    assert_eq!(Poll::Ready(entry(6)), poll_next(&mut laggard));
+    assert_eq!(Poll::Ready(entry(4)), poll_next(&mut laggard));
+
+    publish_handle.send(7).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    let mut late_joiner = splaycast.subscribe_at_tail();
+    assert_eq!(
+        Poll::Ready(entry(10)),
+        poll_next(&mut late_joiner),
+        "4 was folded into the summary"
+    );
+    assert_eq!(Poll::Ready(entry(5)), poll_next(&mut late_joiner));
+
+    assert_eq!(
+        Poll::Ready(entry(5)),
+        poll_next(&mut joiner),
+        "4 is gone, but the joiner read it already"
+    );
+    (8..=9).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(lag(1)),
+        poll_next(&mut joiner),
+        "the summary of 1 to 6 would repeat what the joiner read, so 6 is just lag"
+    );
+    assert_eq!(Poll::Ready(entry(7)), poll_next(&mut joiner));
+
+    let mut resumed = splaycast.subscribe_from(2).expect("not sealed");
+    assert_eq!(
+        Poll::Ready(lag(5)),
+        poll_next(&mut resumed),
+        "resuming from an id skips the summary too"
+    );
+    assert_eq!(Poll::Ready(entry(7)), poll_next(&mut resumed));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn mirror() {
+    let (_publish_handle, splaycast, mut engine) = get_splaycast();
+    let mirror = splaycast.mirror();
+    let subscribers: Vec<splaycast::Receiver<usize>> =
+        (0..3).map(|_| splaycast.subscribe()).collect();
+    assert_eq!(3, mirror.subscriber_count());
+
+    drop(mirror.clone());
+    assert_eq!(
+        Poll::Pending,
+        poll(&mut engine),
+        "dropping a mirror does not affect the channel"
+    );
+
+    drop(splaycast);
+    assert_eq!(
+        Poll::Ready(()),
+        poll(&mut engine),
+        "a mirror does not keep the channel alive"
+    );
+
+    drop(subscribers);
+    drop(engine);
+    assert_eq!(0, mirror.subscriber_count(), "nor its state");
+    assert_eq!(None, mirror.stats_handle().get());
+    assert!(mirror.receiver_cursors().is_empty());
+    assert_eq!(0, mirror.snapshot().len());
+}
+
+#[cfg(feature = "registry")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn registry() {
+    let (_publish_handle, splaycast, mut engine) = get_splaycast();
+    splaycast::registry::register("registry test", splaycast.mirror());
+    let _subscriber = splaycast.subscribe();
+
+    let stats = splaycast::registry::channels()
+        .into_iter()
+        .find(|channel| channel.name == "registry test");
+    assert_eq!(
+        Some(1),
+        stats.map(|stats| stats.subscriber_count),
+        "the registered channel is listed with its stats"
+    );
+
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert!(
+        !splaycast::registry::channels()
+            .iter()
+            .any(|channel| channel.name == "registry test"),
+        "terminated channels are pruned"
+    );
+}
+
+/// Keeps up to 4 entries, but only while somebody is listening.
+struct ListenerPolicy;
+
+impl BufferPolicy<usize> for ListenerPolicy {
+    fn buffer_tail_policy(
+        &mut self,
+        _tail_item: &usize,
+        context: &PolicyContext,
+    ) -> BufferInstruction {
+        if context.subscriber_count == 0 || 4 <= context.buffer_len {
+            BufferInstruction::Pop
+        } else {
+            BufferInstruction::Retain
+        }
+    }
+
+    fn on_before_send(&mut self, _new_item: &mut usize) {}
+
+    fn on_after_pop(&mut self, _popped_item: &usize) {}
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn policy_context() {
+    let (publish_handle, upstream) = unbounded_channel::<usize>();
+    let (mut engine, splaycast) =
+        splaycast::wrap_with_policy(UnboundedReceiverStream::new(upstream), ListenerPolicy);
+    let mirror = splaycast.mirror();
+
+    (0..3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(1, mirror.buffer_len(), "nobody is listening");
+
+    let _subscriber = splaycast.subscribe();
+    (3..9).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(4, mirror.buffer_len(), "somebody is listening");
+}
+
+/// A length policy that defers its pops, so subscribers get one more swap to read the tail.
+struct DeferringPolicy(BufferLengthPolicy);
+
+impl BufferPolicy<usize> for DeferringPolicy {
+    fn buffer_tail_policy(
+        &mut self,
+        tail_item: &usize,
+        context: &PolicyContext,
+    ) -> BufferInstruction {
+        match BufferPolicy::<usize>::buffer_tail_policy(&mut self.0, tail_item, context) {
+            BufferInstruction::Pop => BufferInstruction::Defer,
+            instruction => instruction,
+        }
+    }
+
+    fn on_before_send(&mut self, new_item: &mut usize) {
+        self.0.on_before_send(new_item)
+    }
+
+    fn on_after_pop(&mut self, popped_item: &usize) {
+        self.0.on_after_pop(popped_item)
+    }
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn deferred_pops() {
+    let (publish_handle, upstream) = unbounded_channel::<usize>();
+    let (mut engine, splaycast) = splaycast::wrap_with_policy(
+        UnboundedReceiverStream::new(upstream),
+        DeferringPolicy(BufferLengthPolicy::new(2)),
+    );
+    let mirror = splaycast.mirror();
+    let mut subscriber = splaycast.subscribe();
+
+    (0..3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(3, mirror.buffer_len(), "the pop of 0 is deferred");
+    assert_eq!(
+        Poll::Ready(entry(0)),
+        poll_next(&mut subscriber),
+        "a deferred entry is still visible"
+    );
+
+    publish_handle.send(3).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        3,
+        mirror.buffer_len(),
+        "0 is gone, and the pop of 1 is deferred"
+    );
+
+    let mut joiner = splaycast.subscribe_at_tail();
+    assert_eq!(
+        Poll::Ready(entry(3)),
+        poll_next(&mut joiner),
+        "joiners start past deferred entries"
+    );
+
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(3)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Pending, poll_next(&mut subscriber));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn prefetch() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast();
+    let mut subscriber = splaycast.subscribe();
+    subscriber.set_prefetch(1);
+
+    (0..2).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(entry(0)),
+        poll_next(&mut subscriber),
+        "reading 0 prefetches 1"
+    );
+
+    (2..5).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(entry(1)),
+        poll_next(&mut subscriber),
+        "1 fell off the buffer, but it was already prefetched"
+    );
+    assert_eq!(Poll::Ready(lag(1)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(3)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(4)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Pending, poll_next(&mut subscriber));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn seal() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast();
+    let mut subscriber = splaycast.subscribe();
+
+    splaycast.seal();
+    assert!(splaycast.is_sealed());
+    assert_eq!(
+        Some(SubscribeError::Sealed),
+        splaycast.try_subscribe().err(),
+        "sealed splaycasts refuse new subscribers"
+    );
+    let mut refused = splaycast.subscribe_at_tail();
+    assert!(refused.is_terminated());
+    assert_eq!(
+        Poll::Ready(None),
+        poll_next(&mut refused),
+        "a refused subscription's stream ends right away"
+    );
+    assert_eq!(1, splaycast.subscriber_count(), "and it never counted");
+
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(entry(1)),
+        poll_next(&mut subscriber),
+        "existing subscribers are still served"
+    );
+
+    splaycast.unseal();
+    assert!(!splaycast.is_sealed());
+    let mut late = splaycast.try_subscribe().expect("unsealed");
+    publish_handle.send(2).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut late));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn reorder_window() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    engine.set_reorder_window(ReorderWindow::count(2), |item: &usize| *item);
+    let mut subscriber = splaycast.subscribe();
+
+    [1, 0, 3, 2, 4]
+        .into_iter()
+        .for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(0)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+    assert_eq!(
+        Poll::Pending,
+        poll_next(&mut subscriber),
+        "3 and 4 are held back in case something lower shows up"
+    );
+
+    drop(publish_handle);
+    assert_eq!(Poll::Ready(()), poll(&mut engine), "the upstream closed");
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn reorder_window_max_delay() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    engine.set_reorder_window(
+        ReorderWindow::count(8).with_max_delay(std::time::Duration::from_millis(20)),
+        |item: &usize| *item,
+    );
+    let mut subscriber = splaycast.subscribe();
+    tokio::spawn(engine);
+
+    [1, 0]
+        .into_iter()
+        .for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(
+        entry(0),
+        subscriber.next().await,
+        "the engine wakes itself up to release held items"
+    );
+    assert_eq!(entry(1), subscriber.next().await);
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn sharded_process() {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    let (publish_handle, splaycast, engine) = get_splaycast_with_buffer(64);
+    let subscriber = splaycast.subscribe();
+    tokio::spawn(engine);
+
+    let handled: Arc<Mutex<HashMap<usize, Vec<usize>>>> = Default::default();
+    let shared_handled = handled.clone();
+    let process = tokio::spawn(subscriber.sharded_process(
+        4,
+        |item| item % 3,
+        move |item| {
+            let handled = shared_handled.clone();
+            async move {
+                tokio::task::yield_now().await;
+                handled
+                    .lock()
+                    .expect("not poisoned")
+                    .entry(item % 3)
+                    .or_default()
+                    .push(item);
+            }
+        },
+    ));
+
+    (0..30).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    while handled
+        .lock()
+        .expect("not poisoned")
+        .values()
+        .map(Vec::len)
+        .sum::<usize>()
+        < 30
+    {
+        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    }
+    drop(publish_handle);
+    process
+        .await
+        .expect("the workers finish when the splaycast ends");
+
+    let handled = handled.lock().expect("not poisoned");
+    for key in 0..3 {
+        let expected: Vec<usize> = (0..30).filter(|i| i % 3 == key).collect();
+        assert_eq!(Some(&expected), handled.get(&key), "per-key order is kept");
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn liveness_tick() {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    // An upstream that ends without waking the Engine.
+    let closed = Arc::new(AtomicBool::new(false));
+    let upstream_closed = closed.clone();
+    let upstream = futures::stream::poll_fn(move |_context| {
+        if upstream_closed.load(Ordering::Acquire) {
+            Poll::Ready(None::<usize>)
+        } else {
+            Poll::Pending
+        }
+    });
+    let (mut engine, splaycast) = splaycast::wrap(upstream, 4);
+    engine.set_liveness_tick(std::time::Duration::from_millis(5));
+    let mut subscriber = splaycast.subscribe();
+    tokio::spawn(engine);
+
+    closed.store(true, Ordering::Release);
+    let end = tokio::time::timeout(std::time::Duration::from_secs(5), subscriber.next()).await;
+    assert_eq!(
+        Ok(None),
+        end,
+        "the tick notices the upstream ended without a wake"
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn spawn() {
+    let (publish_handle, upstream) = unbounded_channel::<usize>();
+    let (splaycast, engine) = splaycast::spawn(
+        UnboundedReceiverStream::new(upstream),
+        BufferLengthPolicy::new(4),
+    );
+    let mut subscriber = splaycast.subscribe();
+
+    publish_handle.send(1).expect("unbound send");
+    publish_handle.send(2).expect("unbound send");
+    assert_eq!(entry(1), subscriber.next().await);
+    assert_eq!(entry(2), subscriber.next().await);
+    assert!(!engine.is_finished());
+
+    drop(engine);
+    assert_eq!(
+        None,
+        subscriber.next().await,
+        "dropping the handle aborts the Engine"
+    );
+    assert!(splaycast.is_closed());
+    assert!(publish_handle.send(3).is_err(), "the upstream went with it");
+
+    let (publish_handle, upstream) = unbounded_channel::<usize>();
+    let (splaycast, engine) = splaycast::spawn(
+        UnboundedReceiverStream::new(upstream),
+        BufferLengthPolicy::new(4),
+    );
+    let mut subscriber = splaycast.subscribe();
+    engine.detach();
+    publish_handle.send(1).expect("unbound send");
+    assert_eq!(
+        entry(1),
+        subscriber.next().await,
+        "a detached Engine keeps running"
+    );
+    drop(publish_handle);
+    assert_eq!(None, subscriber.next().await, "until the upstream ends");
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test(start_paused = true)]
+async fn activity_markers() {
+    use splaycast::Activity;
+    use std::time::Duration;
+
+    let (publish_handle, splaycast, engine) = get_splaycast_with_buffer(2);
+    let mut subscriber = splaycast
+        .subscribe()
+        .with_activity_markers(Duration::from_secs(10));
+    tokio::spawn(engine);
+
+    publish_handle.send(1).expect("unbound send");
+    assert_eq!(Some(Activity::Entry(1)), subscriber.next().await);
+    assert_eq!(
+        Some(Activity::Idle(Duration::from_secs(10))),
+        subscriber.next().await
+    );
+    assert_eq!(
+        Some(Activity::Idle(Duration::from_secs(20))),
+        subscriber.next().await,
+        "idle keeps counting from the last message"
+    );
+
+    tokio::time::advance(Duration::from_secs(5)).await;
+    publish_handle.send(2).expect("unbound send");
+    assert_eq!(Some(Activity::Entry(2)), subscriber.next().await);
+    for i in 3..=6 {
+        publish_handle.send(i).expect("unbound send");
+    }
+    tokio::task::yield_now().await;
+    assert_eq!(Some(Activity::Lag(2)), subscriber.next().await);
+    assert_eq!(Some(Activity::Entry(5)), subscriber.next().await);
+    assert_eq!(Some(Activity::Entry(6)), subscriber.next().await);
+    assert_eq!(
+        Some(Activity::Idle(Duration::from_secs(10))),
+        subscriber.next().await,
+        "a message restarts the idle clock"
+    );
+
+    drop(publish_handle);
+    assert_eq!(None, subscriber.next().await);
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn panicking_waker() {
+    struct PanickingWaker;
+    impl futures::task::ArcWake for PanickingWaker {
+        fn wake_by_ref(_arc_self: &std::sync::Arc<Self>) {
+            panic!("this executor is broken");
+        }
+    }
+
+    let (publish_handle, splaycast, mut engine) = get_splaycast();
+    let mut poisoned = splaycast.subscribe();
+    let mut healthy = splaycast.subscribe();
+    let panicking_waker = futures::task::waker(std::sync::Arc::new(PanickingWaker));
+    assert_eq!(
+        Poll::Pending,
+        pin!(&mut poisoned).poll_next(&mut Context::from_waker(&panicking_waker))
+    );
+    assert_eq!(Poll::Pending, poll_next(&mut healthy));
+    assert_eq!(Poll::Pending, poll(&mut engine), "park both receivers");
+
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(
+        Poll::Pending,
+        poll(&mut engine),
+        "the engine survives the panicking waker"
+    );
+    assert_eq!(1, splaycast.poisoned_receivers());
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut healthy));
+    assert_eq!(Poll::Ready(None), poll_next(&mut poisoned));
+    assert!(poisoned.is_disconnected());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn announce() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut subscriber = splaycast.subscribe();
+
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    splaycast.announce(100).expect("open");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    publish_handle.send(2).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(
+        Poll::Ready(entry(100)),
+        poll_next(&mut subscriber),
+        "announcements are delivered in order with upstream entries"
+    );
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+
+    splaycast.close();
+    assert_eq!(Err(101), splaycast.announce(101));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn inline_channel() {
+    use futures::future::BoxFuture;
+    use std::sync::{Arc, Mutex};
+
+    let promoted: Arc<Mutex<Option<BoxFuture<'static, ()>>>> = Default::default();
+    let spawned = promoted.clone();
+    let (sender, splaycast) =
+        splaycast::inline_channel(8, BufferLengthPolicy::new(8), 2, move |engine| {
+            *spawned.lock().expect("not poisoned") = Some(engine);
+        });
+
+    let mut first = splaycast.subscribe();
+    assert_eq!(Poll::Pending, poll_next(&mut first));
+    sender.send(1).expect("room in the send buffer");
+    assert_eq!(
+        Poll::Ready(entry(1)),
+        poll_next(&mut first),
+        "the send fanned out inline"
+    );
+    assert!(promoted.lock().expect("not poisoned").is_none());
+
+    let mut second = splaycast.subscribe();
+    assert_eq!(Poll::Pending, poll_next(&mut second));
+    assert!(
+        promoted.lock().expect("not poisoned").is_none(),
+        "a receiver parking doesn't drive the Engine"
+    );
+    sender.send(2).expect("room in the send buffer");
+    let mut engine = promoted
+        .lock()
+        .expect("not poisoned")
+        .take()
+        .expect("promoted at 2 subscribers");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut first));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut second));
+
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn send_to() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut target = splaycast.subscribe();
+    let mut bystander = splaycast.subscribe();
+
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut target));
+    splaycast.send_to(target.id(), 100).expect("live receiver");
+    publish_handle.send(2).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    assert_eq!(Poll::Ready(entry(100)), poll_next(&mut target));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut target));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut bystander));
+    assert_eq!(
+        Poll::Ready(entry(2)),
+        poll_next(&mut bystander),
+        "only the target gets the direct item"
+    );
+
+    let gone = target.id();
+    drop(target);
+    assert_eq!(Err(101), splaycast.send_to(gone, 101));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn multi_engine() {
+    let (mut multi_engine, handle) = MultiEngine::new();
+    let (first_sender, first_engine, first) = splaycast::channel::<usize>(4);
+    let (second_sender, second_engine, second) = splaycast::channel::<usize>(4);
+    let first_id = handle.add(first_engine);
+    handle.add(second_engine);
+
+    let mut first_subscriber = first.subscribe();
+    let mut second_subscriber = second.subscribe();
+    first_sender.send(1).expect("room in the send buffer");
+    second_sender.send(2).expect("room in the send buffer");
+    assert_eq!(Poll::Pending, poll(&mut multi_engine));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut first_subscriber));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut second_subscriber));
+
+    handle.remove(first_id);
+    assert_eq!(Poll::Pending, poll(&mut multi_engine));
+    assert_eq!(
+        Poll::Ready(None),
+        poll_next(&mut first_subscriber),
+        "removing an engine terminates its splaycast"
+    );
+
+    drop(handle);
+    assert_eq!(
+        Poll::Pending,
+        poll(&mut multi_engine),
+        "the second engine is still running"
+    );
+    drop(second);
+    drop(second_subscriber);
+    assert_eq!(Poll::Ready(()), poll(&mut multi_engine));
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn subscribe_for() {
+    let (publish_handle, splaycast, engine) = get_splaycast_with_buffer(8);
+    let mut trial = splaycast
+        .subscribe_for(std::time::Duration::from_millis(20))
+        .expect("not sealed");
+    tokio::spawn(engine);
+
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(
+        entry(1),
+        trial.next().await,
+        "delivered before the deadline"
+    );
+    assert!(!trial.is_expired());
+    assert_eq!(1, splaycast.subscriber_count());
+    let end = tokio::time::timeout(std::time::Duration::from_secs(5), trial.next()).await;
+    assert_eq!(Ok(None), end, "the stream ends at the deadline");
+    assert!(trial.is_expired(), "the end has a reason");
+    assert!(!splaycast.is_closed());
+    assert_eq!(
+        0,
+        splaycast.subscriber_count(),
+        "an expired receiver is cleaned up before it is dropped"
+    );
+    assert!(splaycast.receiver_cursors().is_empty());
+
+    drop(trial);
+    assert_eq!(0, splaycast.subscriber_count(), "and only once");
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test(start_paused = true)]
+async fn expiry_without_a_poll() {
+    use std::{sync::Arc, time::Duration};
+
+    struct NoopWaker;
+    impl futures::task::ArcWake for NoopWaker {
+        fn wake_by_ref(_arc_self: &Arc<Self>) {}
+    }
+    let parked = Arc::new(NoopWaker);
+    let waker = futures::task::waker(parked.clone());
+    let mut context = Context::from_waker(&waker);
+
+    let (_publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    splaycast.set_max_subscribers(1);
+    let mut trial = splaycast
+        .subscribe_for(Duration::from_secs(1))
+        .expect("not sealed");
+    assert_eq!(Poll::Pending, pin!(&mut trial).poll_next(&mut context));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    let with_parked_waker = Arc::strong_count(&parked);
+    assert_eq!(
+        Some(SubscribeError::AtCapacity),
+        splaycast.try_subscribe().err()
+    );
+
+    tokio::time::advance(Duration::from_secs(2)).await;
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        0,
+        splaycast.subscriber_count(),
+        "the Engine released it without a poll"
+    );
+    assert!(splaycast.receiver_cursors().is_empty());
+    assert!(trial.is_expired(), "expired before it was polled again");
+    assert!(
+        Arc::strong_count(&parked) < with_parked_waker,
+        "the Engine dropped the parked waker"
+    );
+    let admitted = splaycast.try_subscribe().expect("its place went back");
+
+    assert_eq!(Poll::Ready(None), pin!(&mut trial).poll_next(&mut context));
+    assert!(trial.is_expired());
+    drop(trial);
+    assert_eq!(1, splaycast.subscriber_count(), "and only once");
+    drop(admitted);
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn subscribe_with_permit() {
+    let (_publish_handle, splaycast, _engine) = get_splaycast();
+    let permits = SubscribePermits::new(1);
+
+    let permit = permits.try_acquire().expect("one permit");
+    assert!(permits.try_acquire().is_none(), "the pool is empty");
+    let subscriber = splaycast.subscribe_with_permit(permit).expect("not sealed");
+    assert_eq!(0, permits.available());
+
+    drop(subscriber);
+    assert_eq!(
+        1,
+        permits.available(),
+        "dropping the receiver returns the permit"
+    );
+
+    splaycast.seal();
+    let permit = permits.try_acquire().expect("one permit");
+    assert_eq!(
+        Some(SubscribeError::Sealed),
+        splaycast.subscribe_with_permit(permit).err()
+    );
+    assert_eq!(
+        1,
+        permits.available(),
+        "a refused subscribe returns the permit"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn stats_handle() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(2);
+    let stats = splaycast.stats_handle();
+    let mut subscriber = splaycast.subscribe();
+
+    for i in 1..=4 {
+        publish_handle.send(i).expect("unbound send");
+    }
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    let current = stats.get().expect("the splaycast is alive");
+    assert_eq!(1, current.subscriber_count);
+    assert_eq!(4, current.messages_published);
+    assert_eq!(2, current.buffered_entries);
+    assert_eq!(2, current.evictions);
+    assert_eq!(0, current.lag_events);
+    assert!(current.engine_alive);
+
+    assert_eq!(Poll::Ready(lag(2)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(3)), poll_next(&mut subscriber));
+    assert_eq!(1, stats.get().expect("alive").lag_events);
+
+    drop(publish_handle);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert!(!stats.get().expect("the splaycast is alive").engine_alive);
+
+    drop(subscriber);
+    drop(engine);
+    drop(splaycast);
+    assert_eq!(None, stats.get(), "the splaycast is gone");
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn try_next() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(2);
+    let mut subscriber = splaycast.subscribe();
+    assert_eq!(None, subscriber.try_next(), "nothing yet");
+
+    for i in 1..=3 {
+        publish_handle.send(i).expect("unbound send");
+    }
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(lag(1), subscriber.try_next());
+    assert_eq!(entry(2), subscriber.try_next());
+    assert_eq!(entry(3), subscriber.try_next());
+    assert_eq!(None, subscriber.try_next(), "caught up");
+    assert!(!subscriber.is_closed());
+
+    drop(publish_handle);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(None, subscriber.try_next());
+    assert!(subscriber.is_closed(), "the end");
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn next_batch() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(2);
+    let mut subscriber = splaycast.subscribe();
+    let mut context = Context::from_waker(noop_waker_ref());
+    let mut batch = Vec::new();
+    assert_eq!(
+        Poll::Pending,
+        subscriber.poll_next_batch(&mut context, &mut batch, 8)
+    );
+
+    for i in 1..=4 {
+        publish_handle.send(i).expect("unbound send");
+    }
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(2),
+        subscriber.poll_next_batch(&mut context, &mut batch, 2),
+        "up to the limit"
+    );
+    assert_eq!(
+        Poll::Ready(1),
+        subscriber.poll_next_batch(&mut context, &mut batch, 2),
+        "whatever is available"
+    );
+    assert_eq!(
+        vec![
+            Message::Lagged { count: 2 },
+            Message::Entry { item: 3 },
+            Message::Entry { item: 4 }
+        ],
+        batch
+    );
+
+    drop(publish_handle);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(0),
+        subscriber.poll_next_batch(&mut context, &mut Vec::new(), 2),
+        "the end"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn next_chunk() {
+    let (publish_handle, splaycast, engine) = get_splaycast_with_buffer(8);
+    let mut subscriber = splaycast.subscribe();
+    for i in 1..=5 {
+        publish_handle.send(i).expect("unbound send");
+    }
+    tokio::spawn(engine);
+
+    let entries = |range: std::ops::RangeInclusive<usize>| {
+        range
+            .map(|item| Message::Entry { item })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(entries(1..=3), subscriber.next_chunk(3).await, "up to max");
+    assert_eq!(
+        entries(4..=5),
+        subscriber.next_chunk(10).await,
+        "what is buffered"
+    );
+
+    drop(publish_handle);
+    assert!(subscriber.next_chunk(10).await.is_empty(), "the end");
+}
+
+#[cfg(feature = "bytes")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn vectored_receiver() {
+    use bytes::Bytes;
+
+    let (publish_handle, upstream) = unbounded_channel::<Bytes>();
+    let (mut engine, splaycast) = splaycast::wrap(UnboundedReceiverStream::new(upstream), 2);
+    let mut subscriber = splaycast.subscribe().into_vectored(8);
+    let mut context = Context::from_waker(noop_waker_ref());
+    let lengths = |ready: Poll<Option<Message<Vec<std::io::IoSlice<'_>>>>>| match ready {
+        Poll::Ready(Some(Message::Entry { item })) => {
+            item.iter().map(|slice| slice.len()).collect::<Vec<_>>()
+        }
+        other => panic!("expected slices, got {:?}", other.map(|o| o.is_some())),
+    };
+
+    for message in ["a", "bb", "ccc"] {
+        publish_handle
+            .send(Bytes::from(message))
+            .expect("unbound send");
+    }
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert!(
+        matches!(
+            subscriber.poll_ready_slices(&mut context),
+            Poll::Ready(Some(Message::Lagged { count: 1 }))
+        ),
+        "lag comes through before the slices"
+    );
+    assert_eq!(
+        vec![2, 3],
+        lengths(subscriber.poll_ready_slices(&mut context))
+    );
+
+    subscriber.consume(3);
+    assert_eq!(
+        vec![2],
+        lengths(subscriber.poll_ready_slices(&mut context)),
+        "a partial write is offered again"
+    );
+    publish_handle
+        .send(Bytes::from("dddd"))
+        .expect("unbound send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        vec![2, 4],
+        lengths(subscriber.poll_ready_slices(&mut context)),
+        "new entries join the batch"
+    );
+
+    subscriber.consume(6);
+    assert!(subscriber.poll_ready_slices(&mut context).is_pending());
+    drop(publish_handle);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert!(matches!(
+        subscriber.poll_ready_slices(&mut context),
+        Poll::Ready(None)
+    ));
+}
+
+#[cfg(feature = "bridge")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn bridge_checksum() {
+    use splaycast::bridge::{checksum_hook, Checksum, Frame};
+
+    let (publish_handle, upstream) = unbounded_channel::<Vec<u8>>();
+    let (mut engine, splaycast) = splaycast::wrap(UnboundedReceiverStream::new(upstream), 4);
+    engine.set_metadata_hook(checksum_hook());
+    let mut subscriber = splaycast.subscribe();
+
+    publish_handle
+        .send(b"hello".to_vec())
+        .expect("unbound send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    let Poll::Ready(Some(Message::Entry { item })) = poll_next(&mut subscriber) else {
+        panic!("expected an entry");
+    };
+    let checksum = Checksum::from_metadata(subscriber.metadata()).expect("checksummed");
+    assert_eq!(Checksum::of(b"hello"), checksum);
+
+    let mut wire = Vec::new();
+    Frame::CheckedEntry {
+        sequence_id: 1,
+        checksum,
+        payload: item,
+    }
+    .encode(&mut wire);
+    let corrupt = wire.len() - 1;
+    wire[corrupt] ^= 0x20;
+    assert_eq!(
+        Ok(Some((Frame::Corrupt { sequence_id: 1 }, wire.len()))),
+        Frame::decode(&wire),
+        "corruption after absorption is caught on the far side"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn adaptive_conflation() {
+    const CONFLATED: usize = 1000;
+    const FULL: usize = 2000;
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(16);
+    engine.set_adaptive_conflation(
+        AdaptiveConflation::backlog(4, 1).sample_interval(std::time::Duration::ZERO),
+        |item: &usize| item % 10,
+        |fidelity| match fidelity {
+            Fidelity::Conflated => CONFLATED,
+            Fidelity::Full => FULL,
+        },
+    );
+    let mut subscriber = splaycast.subscribe();
+    let mut publish = |items: &[usize]| {
+        for item in items {
+            publish_handle.send(*item).expect("unbound send");
+        }
+        assert_eq!(Poll::Pending, poll(&mut engine));
+    };
+
+    publish(&[1, 2, 3, 4, 5]);
+    publish(&[11, 12]);
+    publish(&[21, 22, 3]);
+    for expected in [1, 2, 3, 4, 5, CONFLATED] {
+        assert_eq!(Poll::Ready(entry(expected)), poll_next(&mut subscriber));
+    }
+    assert_eq!(
+        Poll::Ready(lag(2)),
+        poll_next(&mut subscriber),
+        "11 and 12 were superseded"
+    );
+    for expected in [21, 22, 3] {
+        assert_eq!(Poll::Ready(entry(expected)), poll_next(&mut subscriber));
+    }
+    assert_eq!(Poll::Pending, poll_next(&mut subscriber));
+
+    publish(&[4, 14]);
+    for expected in [FULL, 4, 14] {
+        assert_eq!(
+            Poll::Ready(entry(expected)),
+            poll_next(&mut subscriber),
+            "caught up, so back to full fidelity"
+        );
+    }
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn adaptive_conflation_samples_backlog() {
+    const CONFLATED: usize = 1000;
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(16);
+    engine.set_adaptive_conflation(
+        AdaptiveConflation::backlog(4, 1).sample_interval(std::time::Duration::from_secs(3600)),
+        |item: &usize| item % 10,
+        |_| CONFLATED,
+    );
+    let mut subscriber = splaycast.subscribe();
+    for items in [&[1, 2, 3, 4, 5][..], &[11, 12]] {
+        for item in items {
+            publish_handle.send(*item).expect("unbound send");
+        }
+        assert_eq!(Poll::Pending, poll(&mut engine));
+    }
+    for expected in [1, 2, 3, 4, 5, 11, 12] {
+        assert_eq!(
+            Poll::Ready(entry(expected)),
+            poll_next(&mut subscriber),
+            "the backlog was sampled before it built up"
+        );
+    }
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn resubscribe() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut subscriber = splaycast.subscribe();
+    publish_handle.send(1).expect("unbound send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let mut resubscribed = subscriber.resubscribe().expect("not sealed");
+    assert_eq!(2, splaycast.subscriber_count());
+    publish_handle.send(2).expect("unbound send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(
+        Poll::Ready(entry(2)),
+        poll_next(&mut resubscribed),
+        "starts at the head"
+    );
+
+    splaycast.seal();
+    assert_eq!(Some(SubscribeError::Sealed), subscriber.resubscribe().err());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn position_stream() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(2);
+    let mut subscriber = splaycast.subscribe();
+    let mut positions = subscriber.position_stream();
+    assert_eq!(Poll::Pending, poll_next(&mut positions));
+
+    for i in 1..=4 {
+        publish_handle.send(i).expect("unbound send");
+    }
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(lag(2)), poll_next(&mut subscriber));
+    let Poll::Ready(Some((sequence_id, _))) = poll_next(&mut positions) else {
+        panic!("expected a position");
+    };
+    assert_eq!(2, sequence_id, "a lag jump is one step");
+
+    assert_eq!(Poll::Ready(entry(3)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(4)), poll_next(&mut subscriber));
+    let Poll::Ready(Some((sequence_id, _))) = poll_next(&mut positions) else {
+        panic!("expected a position");
+    };
+    assert_eq!(4, sequence_id, "positions are coalesced");
+    assert_eq!(Poll::Pending, poll_next(&mut subscriber));
+    assert_eq!(
+        Poll::Pending,
+        poll_next(&mut positions),
+        "parking is not progress"
+    );
+
+    drop(subscriber);
+    assert_eq!(Poll::Ready(None), poll_next(&mut positions));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn clone_receiver() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut subscriber = splaycast.subscribe();
+    subscriber.set_prefetch(1);
+    for i in 1..=3 {
+        publish_handle.send(i).expect("unbound send");
+    }
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+
+    let mut tee = subscriber.clone();
+    assert_eq!(2, splaycast.subscriber_count());
+    assert_ne!(subscriber.id(), tee.id());
+    for expected in 2..=3 {
+        assert_eq!(Poll::Ready(entry(expected)), poll_next(&mut tee));
+    }
+    assert_eq!(Poll::Pending, poll_next(&mut tee));
+    for expected in 2..=3 {
+        assert_eq!(
+            Poll::Ready(entry(expected)),
+            poll_next(&mut subscriber),
+            "the tee doesn't disturb the original"
+        );
+    }
+
+    drop(tee);
+    assert_eq!(1, splaycast.subscriber_count());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn resume_at() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut at_tail = splaycast.subscribe();
+    let mut at_head = splaycast.subscribe_with(SubscribeOptions::new().resume_at(ResumeAt::Head));
+    let mut halfway = splaycast.subscribe();
+    halfway.set_resume_at(ResumeAt::Percent(50));
+
+    (0..10).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    // Items 6 through 9 are still buffered.
+    assert_eq!(Poll::Ready(lag(6)), poll_next(&mut at_tail));
+    assert_eq!(Poll::Ready(entry(6)), poll_next(&mut at_tail));
+
+    assert_eq!(Poll::Ready(lag(9)), poll_next(&mut at_head));
+    assert_eq!(Poll::Ready(entry(9)), poll_next(&mut at_head));
+    assert_eq!(Poll::Pending, poll_next(&mut at_head));
+
+    assert_eq!(Poll::Ready(lag(7)), poll_next(&mut halfway));
+    assert_eq!(Poll::Ready(entry(7)), poll_next(&mut halfway));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn async_stream_upstream() {
+    let (publish_handle, inputs) = unbounded_channel::<usize>();
+    let mut inputs = UnboundedReceiverStream::new(inputs);
+    let upstream = async_stream::stream! {
+        while let Some(input) = inputs.next().await {
+            for i in 0..input {
+                yield input * 10 + i;
+            }
+        }
+    };
+    let (engine, splaycast) = splaycast::wrap_boxed(upstream, BufferLengthPolicy::new(8));
+    let mut subscriber = splaycast.subscribe();
+    tokio::spawn(engine);
+
+    publish_handle.send(1).expect("unbounded send");
+    publish_handle.send(3).expect("unbounded send");
+    for expected in [10, 30, 31, 32] {
+        assert_eq!(entry(expected), subscriber.next().await);
+    }
+
+    drop(publish_handle);
+    assert_eq!(
+        None,
+        subscriber.next().await,
+        "the generator returned, so the upstream ended"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn async_stream_cancellation() {
+    /// Reports when the generator is dropped, wherever it was suspended.
+    struct Guard(UnboundedSender<&'static str>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let _ = self.0.send("dropped");
+        }
+    }
+
+    let (report, mut reports) = unbounded_channel();
+    let upstream = async_stream::stream! {
+        let _guard = Guard(report);
+        let mut i = 0;
+        loop {
+            yield i;
+            i += 1;
+            futures::future::pending::<()>().await;
+        }
+    };
+    let (mut engine, splaycast) = splaycast::wrap_boxed(upstream, BufferLengthPolicy::new(8));
+    let mut subscriber = splaycast.subscribe();
+
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(0)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Pending, poll_next(&mut subscriber));
+    assert!(reports.try_recv().is_err(), "still suspended at the await");
+
+    drop(engine);
+    assert_eq!(Ok("dropped"), reports.try_recv());
+    assert_eq!(
+        Poll::Ready(None),
+        poll_next(&mut subscriber),
+        "the receiver finds out right away"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn resume_position() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut subscriber = splaycast.subscribe();
+    subscriber.set_prefetch(2);
+    assert_eq!(1, subscriber.position());
+
+    (0..4).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(0)), poll_next(&mut subscriber));
+    assert_eq!(
+        2,
+        subscriber.position(),
+        "prefetched entries haven't been delivered"
+    );
+    let position = subscriber.position();
+    drop(subscriber);
+
+    let mut reconnected = splaycast.subscribe_from(position).expect("not sealed");
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut reconnected));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn channel_metadata() {
+    let (_publish_handle, splaycast, _engine) = get_splaycast();
+    let subscriber = splaycast.subscribe();
+    assert_eq!(None, subscriber.channel_metadata().name());
+    assert!(subscriber.channel_metadata().value().is_none());
+
+    let (_engine, splaycast) = splaycast::wrap_with_metadata(
+        futures::stream::pending::<usize>(),
+        BufferLengthPolicy::new(4),
+        ChannelMetadata::new()
+            .with_name("orders")
+            .with_schema_id(3)
+            .with_value(std::sync::Arc::new(42_u32)),
+    );
+    let latest: splaycast::Receiver<usize, TipOnly> =
+        splaycast.subscribe_latest_only().expect("not sealed");
+    let metadata = latest.channel_metadata();
+    assert_eq!(Some("orders"), metadata.name());
+    assert_eq!(Some(3), metadata.schema_id());
+    assert_eq!(Some(&42_u32), metadata.downcast_ref());
+    assert_eq!(None, metadata.downcast_ref::<u64>(), "wrong type");
+    assert_eq!(Some(3), splaycast.channel_metadata().schema_id());
+}
+
+#[cfg(feature = "simulate")]
+#[test]
+fn simulate() {
+    use splaycast::simulate::{Simulation, SubscriberProfile};
+
+    let simulation = Simulation::new(BufferLengthPolicy::new(32))
+        .publish_per_tick(4)
+        .subscribers(SubscriberProfile::new(8), 10)
+        .subscribers(SubscriberProfile::new(4).with_stalls(20, 10), 1)
+        .subscribers(SubscriberProfile::new(2).starting_at(50), 1);
+    let report = simulation.run(100);
+
+    assert_eq!(100, report.ticks);
+    assert_eq!(400, report.published);
+    assert_eq!(32, report.peak_occupancy());
+    assert_eq!(12, report.subscribers.len());
+    for keeping_up in &report.subscribers[..10] {
+        assert_eq!(400, keeping_up.received);
+        assert_eq!(0, keeping_up.lag_events);
+    }
+    let stalled = &report.subscribers[10];
+    assert!(
+        5 <= stalled.lag_events,
+        "40 entries per stall, with room for 32"
+    );
+    assert!(stalled.received + stalled.lagged_entries <= 400);
+    assert!(32 < stalled.max_lag);
+    let slow = &report.subscribers[11];
+    assert_eq!(
+        100,
+        slow.received + slow.lag_events,
+        "2 messages per tick for the last 50 ticks"
+    );
+    assert!(0 < slow.lagged_entries, "it can't keep up");
+    assert!(
+        10 <= report.peak_wakes(),
+        "the subscribers that keep up park every tick"
+    );
+
+    assert_eq!(report, simulation.run(100), "runs are deterministic");
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn skip_to_latest() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut subscriber = splaycast.subscribe();
+    subscriber.set_prefetch(2);
+    (0..10).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    subscriber.skip_to_latest();
+    assert_eq!(
+        Poll::Pending,
+        poll_next(&mut subscriber),
+        "no lag, and nothing new yet"
+    );
+    assert_eq!(
+        Some(0),
+        splaycast.stats_handle().get().map(|stats| stats.lag_events)
+    );
+
+    publish_handle.send(10).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(10)), poll_next(&mut subscriber));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn peek() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut subscriber = splaycast.subscribe();
+    assert_eq!(None, subscriber.peek(), "nothing yet");
+
+    (0..3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Some(0), subscriber.peek());
+    assert_eq!(Some(0), subscriber.peek(), "peeking doesn't advance");
+    assert_eq!(Poll::Ready(entry(0)), poll_next(&mut subscriber));
+
+    subscriber.set_prefetch(1);
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(Some(2), subscriber.peek(), "from the prefetch");
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+    assert_eq!(None, subscriber.peek());
+
+    (3..10).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(None, subscriber.peek(), "the next message is a Lagged");
+    assert_eq!(Poll::Ready(lag(3)), poll_next(&mut subscriber));
+    assert_eq!(Some(6), subscriber.peek());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn lag_events() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut first = splaycast.subscribe();
+    let mut second = splaycast.subscribe();
+    let mut lag_events = splaycast.lag_events();
+    assert_eq!(Poll::Pending, poll_next(&mut lag_events));
+
+    (0..10).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(lag(6)), poll_next(&mut second));
+    assert_eq!(Poll::Ready(lag(6)), poll_next(&mut first));
+
+    let Poll::Ready(Some(event)) = poll_next(&mut lag_events) else {
+        panic!("expected a lag event");
+    };
+    assert_eq!((second.id(), 6), (event.receiver_id, event.count));
+    let Poll::Ready(Some(event)) = poll_next(&mut lag_events) else {
+        panic!("expected a lag event");
+    };
+    assert_eq!((first.id(), 6), (event.receiver_id, event.count));
+    assert_eq!(Poll::Pending, poll_next(&mut lag_events));
+    assert_eq!(0, lag_events.missed());
+
+    drop(splaycast);
+    assert_eq!(Poll::Ready(None), poll_next(&mut lag_events));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn pause_resume() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    struct CountingWaker(AtomicUsize);
+    impl futures::task::ArcWake for CountingWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = futures::task::waker(wakes.clone());
+    let mut context = Context::from_waker(&waker);
+
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut subscriber = splaycast.subscribe();
+    subscriber.pause();
+    assert!(subscriber.is_paused());
+
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Pending,
+        pin!(&mut subscriber).poll_next(&mut context),
+        "paused with an entry available"
+    );
+    publish_handle.send(2).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(0, wakes.0.load(Ordering::Relaxed), "not woken while paused");
+
+    subscriber.resume();
+    assert!(!subscriber.is_paused());
+    assert_eq!(
+        1,
+        wakes.0.load(Ordering::Relaxed),
+        "resume wakes the last poller"
+    );
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+
+    subscriber.pause();
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(Poll::Ready(None), poll_next(&mut subscriber));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn hand_off() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut subscriber = splaycast.subscribe();
+    subscriber.set_prefetch(1);
+    (0..4).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(0)), poll_next(&mut subscriber));
+    splaycast
+        .send_to(subscriber.id(), 100)
+        .expect("the receiver is alive");
+    let mut changes = splaycast.subscriber_count_changes([1]);
+
+    let old_id = subscriber.id();
+    let state = subscriber.hand_off();
+    assert_eq!(1, splaycast.subscriber_count(), "in transit");
+    let mut adopted = splaycast.adopt(state).expect("same splaycast");
+    assert_eq!(1, splaycast.subscriber_count());
+    assert_eq!(
+        Poll::Pending,
+        poll_next(&mut changes),
+        "the count never dipped"
+    );
+    assert_ne!(old_id, adopted.id());
+    assert_eq!(Err(100), splaycast.send_to(old_id, 100));
+
+    assert_eq!(Poll::Ready(entry(100)), poll_next(&mut adopted));
+    for expected in 1..4 {
+        assert_eq!(Poll::Ready(entry(expected)), poll_next(&mut adopted));
+    }
+    assert_eq!(
+        splaycast::AbandonedBacklog::default(),
+        splaycast.abandoned_backlog(),
+        "handing off is not abandoning"
+    );
+
+    let (_publish_handle, other, _engine) = get_splaycast();
+    let state = adopted.hand_off().position_only();
+    let state = other.adopt(state).expect_err("a different splaycast");
+    let adopted = splaycast.adopt(state).expect("same splaycast");
+    assert!(!adopted.is_paused());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn ignore_lag() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut lagged = 0;
+    let mut items = splaycast
+        .subscribe()
+        .ignore_lag_with(|count| lagged += count);
+    let mut quiet = splaycast.subscribe().ignore_lag();
+
+    (0..10).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    for expected in 6..10 {
+        assert_eq!(Poll::Ready(Some(expected)), poll_next(&mut items));
+        assert_eq!(Poll::Ready(Some(expected)), poll_next(&mut quiet));
+    }
+    assert_eq!(Poll::Pending, poll_next(&mut items));
+
+    splaycast.reset_sequence();
+    publish_handle.send(100).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(Some(100)),
+        poll_next(&mut items),
+        "resets are skipped"
+    );
+    drop(items);
+    assert_eq!(6, lagged);
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test]
+async fn lag_as_error() {
+    use futures::TryStreamExt;
+    use splaycast::Lagged;
+
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut results = splaycast.subscribe().lag_as_error();
+
+    (0..10).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Err(Lagged { count: 6 }),
+        TryStreamExt::try_next(&mut results).await
+    );
+    assert_eq!(
+        Ok(Some(6)),
+        TryStreamExt::try_next(&mut results).await,
+        "lag is not the end"
+    );
+
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    let rest: Result<Vec<usize>, Lagged> = results.try_collect().await;
+    assert_eq!(Ok(vec![]), rest);
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn quiesced() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    assert_eq!(Poll::Ready(true), poll(&mut splaycast.quiesced()));
+    let mut fast = splaycast.subscribe();
+    let slow = splaycast.subscribe();
+    let mut quiesced = splaycast.quiesced();
+    assert_eq!(
+        Poll::Ready(true),
+        poll(&mut quiesced),
+        "nothing published yet"
+    );
+
+    (0..3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    let mut quiesced = splaycast.quiesced();
+    assert_eq!(Poll::Pending, poll(&mut quiesced));
+    while let Poll::Ready(Some(_)) = poll_next(&mut fast) {}
+    assert_eq!(Poll::Pending, poll(&mut quiesced), "slow is still behind");
+
+    drop(slow);
+    assert_eq!(Poll::Ready(true), poll(&mut quiesced));
+
+    publish_handle.send(3).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    let mut quiesced = splaycast.quiesced();
+    assert_eq!(Poll::Pending, poll(&mut quiesced));
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(Poll::Ready(false), poll(&mut quiesced));
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test(start_paused = true)]
+async fn next_timeout() {
+    use splaycast::NextTimeout;
+    use std::time::Duration;
+
+    let (publish_handle, splaycast, engine) = get_splaycast_with_buffer(2);
+    let mut subscriber = splaycast.subscribe();
+    tokio::spawn(engine);
+
+    let timeout = Duration::from_secs(15);
+    assert_eq!(NextTimeout::Timeout, subscriber.next_timeout(timeout).await);
+    publish_handle.send(1).expect("unbound send");
+    assert_eq!(
+        NextTimeout::Item(Message::Entry { item: 1 }),
+        subscriber.next_timeout(timeout).await
+    );
+    assert_eq!(NextTimeout::Timeout, subscriber.next_timeout(timeout).await);
+    publish_handle.send(2).expect("unbound send");
+    assert_eq!(
+        NextTimeout::Item(Message::Entry { item: 2 }),
+        subscriber.next_timeout(timeout).await,
+        "nothing is lost to a timeout"
+    );
+
+    drop(splaycast);
+    assert_eq!(NextTimeout::Closed, subscriber.next_timeout(timeout).await);
+}
+
+#[cfg(feature = "tokio")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[tokio::test(start_paused = true)]
+async fn subscribe_throttled() {
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    let (publish_handle, splaycast, engine) = get_splaycast_with_buffer(8);
+    let interval = Duration::from_millis(100);
+    let mut throttled = splaycast.subscribe_throttled(interval).expect("not sealed");
+    tokio::spawn(engine);
+
+    publish_handle.send(1).expect("unbound send");
+    let start = Instant::now();
+    assert_eq!(entry(1), throttled.next().await);
+    assert_eq!(start, Instant::now(), "the first entry isn't held back");
+
+    for i in 2..=5 {
+        publish_handle.send(i).expect("unbound send");
+    }
+    tokio::task::yield_now().await;
+    assert_eq!(
+        entry(5),
+        throttled.next().await,
+        "intermediates are skipped"
+    );
+    assert_eq!(start + interval, Instant::now());
+
+    tokio::time::sleep(interval * 3).await;
+    publish_handle.send(6).expect("unbound send");
+    let quiet = Instant::now();
+    assert_eq!(entry(6), throttled.next().await);
+    assert_eq!(
+        quiet,
+        Instant::now(),
+        "an entry after a quiet interval isn't held back"
+    );
+
+    drop(splaycast);
+    assert_eq!(None, throttled.next().await);
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn size_hint() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut subscriber = splaycast.subscribe();
+    let latest = splaycast.subscribe_latest_only().expect("not sealed");
+    let filtered = splaycast
+        .subscribe_filtered(|item: &usize| item.is_multiple_of(2))
+        .expect("not sealed");
+    assert_eq!((0, None), subscriber.size_hint());
+
+    (1..=3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!((3, None), subscriber.size_hint());
+    assert_eq!(
+        (1, None),
+        latest.size_hint(),
+        "latest-only yields just the newest"
+    );
+    assert_eq!(
+        (0, None),
+        filtered.size_hint(),
+        "filtered entries may be skipped"
+    );
+
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!((2, None), subscriber.size_hint());
+
+    (4..=8).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!((1, None), subscriber.size_hint(), "a Lagged is next");
+    assert_eq!(Poll::Ready(lag(3)), poll_next(&mut subscriber));
+    assert_eq!((4, None), subscriber.size_hint());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn on_terminate() {
+    use splaycast::{TerminationReason, TerminationReport};
+    use std::sync::mpsc::channel;
+
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(2);
+    let (reports, report) = channel::<TerminationReport>();
+    engine.set_on_terminate(move |report| reports.send(report).expect("test is running"));
+    let _first = splaycast.subscribe();
+    drop(splaycast.subscribe());
+    let _third = splaycast.subscribe();
+    (1..=4).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert!(report.try_recv().is_err(), "still running");
+
+    drop(publish_handle);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    let summary = report.try_recv().expect("reported on completion");
+    assert_eq!(TerminationReason::UpstreamEnded, summary.reason);
+    assert_eq!(4, summary.items_absorbed);
+    assert_eq!(2, summary.pops);
+    assert_eq!(2, summary.peak_subscriber_count);
+    assert_eq!(4, summary.final_sequence_id);
+    drop(engine);
+    assert!(report.try_recv().is_err(), "reported only once");
+
+    let (_publish_handle, splaycast, mut engine) = get_splaycast();
+    let (reports, report) = channel::<TerminationReport>();
+    engine.set_on_terminate(move |report| reports.send(report).expect("test is running"));
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    let summary = report.try_recv().expect("reported on completion");
+    assert_eq!(TerminationReason::Closed, summary.reason);
+    assert_eq!(0, summary.final_sequence_id);
+
+    let (_publish_handle, _splaycast, mut engine) = get_splaycast();
+    let (reports, report) = channel::<TerminationReport>();
+    engine.set_on_terminate(move |report| reports.send(report).expect("test is running"));
+    drop(engine);
+    let summary = report.try_recv().expect("reported on drop");
+    assert_eq!(TerminationReason::EngineDropped, summary.reason);
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn behind_by_and_is_terminated() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut subscriber = splaycast.subscribe();
+    assert_eq!(0, subscriber.behind_by());
+    assert!(!subscriber.is_terminated());
+
+    (1..=5).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(5, subscriber.behind_by());
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(4, subscriber.behind_by());
+
+    subscriber.set_prefetch(4);
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+    assert_eq!(
+        3,
+        subscriber.behind_by(),
+        "prefetched entries are still behind"
+    );
+
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert!(subscriber.is_terminated());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn wrap_relay() {
+    let (upstream_handle, upstream) = unbounded_channel::<Message<usize>>();
+    let (mut engine, splaycast) = splaycast::wrap_relay(
+        UnboundedReceiverStream::new(upstream),
+        BufferLengthPolicy::new(8),
+    );
+    let mut subscriber = splaycast.subscribe();
+    let send = |message| upstream_handle.send(message).expect("unbounded send");
+
+    send(Message::Entry { item: 1 });
+    send(Message::Entry { item: 2 });
+    send(Message::Lagged { count: 3 });
+    send(Message::Entry { item: 6 });
+    for _ in 0..2 {
+        assert_eq!(Poll::Pending, poll(&mut engine));
+    }
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+    assert_eq!(
+        Poll::Ready(lag(3)),
+        poll_next(&mut subscriber),
+        "upstream lag comes through as lag, in place"
+    );
+    assert_eq!(Poll::Ready(entry(6)), poll_next(&mut subscriber));
+    assert_eq!(7, subscriber.position());
+
+    send(Message::Reset { epoch: 1 });
+    send(Message::Entry { item: 7 });
+    for _ in 0..2 {
+        assert_eq!(Poll::Pending, poll(&mut engine));
+    }
+    assert_eq!(
+        Poll::Ready(Some(Message::Reset { epoch: 1 })),
+        poll_next(&mut subscriber)
+    );
+    assert_eq!(Poll::Ready(entry(7)), poll_next(&mut subscriber));
+    assert_eq!(2, subscriber.position(), "the new epoch starts at 1");
+}
+
+#[cfg(feature = "compression")]
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn compression() {
+    use splaycast::{Compression, Payload};
+
+    let (publish_handle, upstream) = unbounded_channel::<Payload>();
+    let (mut engine, splaycast) = splaycast::wrap(UnboundedReceiverStream::new(upstream), 4);
+    engine.set_compression(Compression::new(64).cache_decompressed(true));
+    let stats = splaycast.stats_handle();
+    let mut subscriber = splaycast.subscribe();
+    let mut other = splaycast.subscribe();
+
+    let large = vec![7u8; 1024];
+    publish_handle
+        .send(Payload::from(large.clone()))
+        .expect("unbound send");
+    publish_handle
+        .send(Payload::from(&b"tiny"[..]))
+        .expect("unbound send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let Poll::Ready(Some(Message::Entry { item })) = poll_next(&mut subscriber) else {
+        panic!("expected the large entry");
+    };
+    assert!(item.is_compressed());
+    assert_eq!(1024, item.len());
+    let stored_len = item.stored_len();
+    assert!(stored_len < 1024);
+    assert_eq!(&large[..], &item.bytes()[..]);
+    let Poll::Ready(Some(Message::Entry { item: shared })) = poll_next(&mut other) else {
+        panic!("expected the large entry");
+    };
+    assert!(
+        std::sync::Arc::ptr_eq(&item.bytes(), &shared.bytes()),
+        "receivers share the cached decompression"
+    );
+
+    let Poll::Ready(Some(Message::Entry { item })) = poll_next(&mut subscriber) else {
+        panic!("expected the tiny entry");
+    };
+    assert!(!item.is_compressed(), "below the threshold");
+    assert_eq!(b"tiny", &item.bytes()[..]);
+
+    let current = stats.get().expect("the splaycast is alive");
+    assert_eq!(1, current.compressed_entries);
+    assert_eq!(1024, current.compression_input_bytes);
+    assert_eq!(stored_len as u64, current.compression_output_bytes);
+    assert!(current.compression_ratio() < 0.1);
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn receiver_labels() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(2);
+    let mut labelled = splaycast.subscribe_with(SubscribeOptions::new().label("conn-42"));
+    let mut relabelled = splaycast.subscribe();
+    assert_eq!(None, relabelled.label());
+    relabelled.set_label("conn-43");
+    assert_eq!(Some("conn-42"), labelled.label());
+    assert_eq!(
+        Some("conn-42"),
+        labelled.clone().label(),
+        "clones keep the label"
+    );
+
+    let labels: Vec<_> = splaycast
+        .receiver_cursors()
+        .into_iter()
+        .map(|cursor| cursor.label)
+        .collect();
+    assert_eq!(vec![Some("conn-42".into()), Some("conn-43".into())], labels);
+
+    let mut lag_events = splaycast.lag_events();
+    (1..=4).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(lag(2)), poll_next(&mut labelled));
+    let Poll::Ready(Some(event)) = poll_next(&mut lag_events) else {
+        panic!("expected a lag event");
+    };
+    assert_eq!(Some("conn-42".into()), event.label);
+    assert_eq!(Poll::Ready(lag(2)), poll_next(&mut relabelled));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn per_receiver_lag_counters() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(2);
+    let mut stalled = splaycast.subscribe();
+    let mut draining = splaycast.subscribe();
+
+    (1..=5).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(lag(3)), poll_next(&mut stalled));
+    assert_eq!(Poll::Ready(lag(3)), poll_next(&mut draining));
+    while let Poll::Ready(Some(_)) = poll_next(&mut draining) {}
+
+    (6..=10).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(lag(5)), poll_next(&mut stalled));
+    assert_eq!(Poll::Ready(lag(3)), poll_next(&mut draining));
+    assert_eq!((2, 8), (stalled.lag_events(), stalled.entries_skipped()));
+    assert_eq!((2, 6), (draining.lag_events(), draining.entries_skipped()));
+
+    let cursor = splaycast
+        .receiver_cursors()
+        .into_iter()
+        .find(|cursor| cursor.receiver_id == stalled.id())
+        .expect("the receiver is registered");
+    assert_eq!((2, 8), (cursor.lag_events, cursor.entries_skipped));
+    assert_eq!(
+        0,
+        stalled.clone().lag_events(),
+        "clones start counting afresh"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn receiver_close() {
+    use splaycast::{CloseReason, ReceiverClosed};
+    use std::sync::{Arc, Mutex};
+
+    struct NoopWaker;
+    impl futures::task::ArcWake for NoopWaker {
+        fn wake_by_ref(_arc_self: &Arc<Self>) {}
+    }
+    let parked = Arc::new(NoopWaker);
+    let waker = futures::task::waker(parked.clone());
+    let mut context = Context::from_waker(&waker);
+
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let closes: Arc<Mutex<Vec<ReceiverClosed>>> = Default::default();
+    engine.set_on_receiver_closed({
+        let closes = closes.clone();
+        move |closed| closes.lock().expect("not poisoned").push(closed)
+    });
+    let mut navigated = splaycast.subscribe_with(SubscribeOptions::new().label("conn-1"));
+    let crashed = splaycast.subscribe();
+    let (navigated_id, crashed_id) = (navigated.id(), crashed.id());
+
+    assert_eq!(Poll::Pending, pin!(&mut navigated).poll_next(&mut context));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    navigated.close(CloseReason::Finished);
+    crashed.close(CloseReason::Failed);
+    assert_eq!(0, splaycast.subscriber_count());
+    let with_parked_waker = Arc::strong_count(&parked);
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        with_parked_waker - 1,
+        Arc::strong_count(&parked),
+        "the Engine dropped the parked waker"
+    );
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let closes = closes.lock().expect("not poisoned");
+    assert_eq!(
+        vec![
+            (navigated_id, Some("conn-1".into()), CloseReason::Finished),
+            (crashed_id, None, CloseReason::Failed),
+        ],
+        closes
+            .iter()
+            .map(|closed| (
+                closed.receiver_id,
+                closed.label.clone(),
+                closed.reason.clone()
+            ))
+            .collect::<Vec<_>>()
+    );
+    let stats = splaycast
+        .stats_handle()
+        .get()
+        .expect("the splaycast is alive");
+    assert_eq!((2, 1), (stats.closed_receivers, stats.failed_receivers));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn receiver_group() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut members = splaycast.subscribe_group(2).expect("not sealed");
+    let group = members[0].receiver_group();
+    assert_eq!(2, splaycast.subscriber_count());
+
+    (0..2).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let mut barrier = group.wait_all_at(1);
+    assert_eq!(Poll::Ready(entry(0)), poll_next(&mut members[0]));
+    assert_eq!(2, members[0].position(), "it expects 1's successor next");
+    assert_eq!(
+        Poll::Pending,
+        poll(&mut barrier),
+        "the second member has not reached 1 yet"
+    );
+
+    assert_eq!(Poll::Ready(entry(0)), poll_next(&mut members[1]));
+    assert_eq!(Poll::Ready(()), poll(&mut barrier), "everybody is at 1");
+    assert_eq!(vec![Some(1), Some(1)], group.positions());
+
+    let mut barrier = group.wait_all_at(2);
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut members[1]));
+    assert_eq!(Poll::Pending, poll(&mut barrier));
+    members.remove(0);
+    assert_eq!(
+        Poll::Ready(()),
+        poll(&mut barrier),
+        "members that leave do not hold the group back"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn subscribe_from() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    (0..10).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    // Item i has sequence id i + 1, and ids 7 through 10 are still buffered.
+    let mut resumed = splaycast.subscribe_from(9).expect("not sealed");
+    assert_eq!(Poll::Ready(entry(8)), poll_next(&mut resumed));
+    assert_eq!(Poll::Ready(entry(9)), poll_next(&mut resumed));
+    assert_eq!(Poll::Pending, poll_next(&mut resumed));
+
+    let mut too_late = splaycast.subscribe_from(3).expect("not sealed");
+    assert_eq!(
+        Poll::Ready(lag(4)),
+        poll_next(&mut too_late),
+        "ids 3 through 6 fell off the buffer"
+    );
+    assert_eq!(Poll::Ready(entry(6)), poll_next(&mut too_late));
+
+    let mut future = splaycast.subscribe_from(100).expect("not sealed");
+    publish_handle.send(10).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(entry(10)),
+        poll_next(&mut future),
+        "ids that haven't been published yet start at the head"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn subscribe_with() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    (0..4).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let mut subscriber = splaycast.subscribe_with(
+        SubscribeOptions::new()
+            .start_at(StartAt::Sequence(2))
+            .prefetch(2),
+    );
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+
+    (4..8).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(entry(2)),
+        poll_next(&mut subscriber),
+        "prefetched before it fell off the buffer"
+    );
+    assert_eq!(Poll::Ready(entry(3)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(4)), poll_next(&mut subscriber));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn reset_sequence() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(4);
+    let mut subscriber = splaycast.subscribe();
+    (0..3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(0)), poll_next(&mut subscriber));
+
+    splaycast.reset_sequence();
+    publish_handle.send(100).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(Some(Message::Reset { epoch: 1 })),
+        poll_next(&mut subscriber),
+        "the rest of the old epoch was cleared"
+    );
+    assert_eq!(
+        Poll::Ready(entry(100)),
+        poll_next(&mut subscriber),
+        "no lag across the reset, even though the new ids are lower"
+    );
+    assert_eq!(Poll::Pending, poll_next(&mut subscriber));
+
+    let mut late_joiner = splaycast.subscribe_from(1).expect("not sealed");
+    assert_eq!(
+        Poll::Ready(entry(100)),
+        poll_next(&mut late_joiner),
+        "ids start over from 1"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn flush_buffer() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut laggard = splaycast.subscribe();
+    (1..=3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut laggard));
+
+    splaycast.flush_buffer();
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert!(splaycast.snapshot().is_empty());
+    assert_eq!(
+        Poll::Ready(lag(2)),
+        poll_next(&mut laggard),
+        "the laggard jumps past the flushed entries"
+    );
+    assert_eq!(Poll::Pending, poll_next(&mut laggard));
+
+    let mut late_joiner = splaycast.subscribe_at_tail();
+    assert_eq!(
+        Poll::Pending,
+        poll_next(&mut late_joiner),
+        "new subscribers start empty"
+    );
+
+    publish_handle.send(4).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(entry(4)),
+        poll_next(&mut laggard),
+        "ids carry on"
+    );
+    assert_eq!(Poll::Ready(entry(4)), poll_next(&mut late_joiner));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn snapshot() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(3);
+    assert!(splaycast.snapshot().is_empty());
+    assert_eq!(None, splaycast.buffer_sequence_range());
+
+    (0..5).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(3, splaycast.buffer_len());
+    assert_eq!(Some((3, 5)), splaycast.buffer_sequence_range());
+    let snapshot = splaycast.snapshot();
+    assert_eq!(
+        vec![(3, &2), (4, &3), (5, &4)],
+        snapshot.iter().collect::<Vec<_>>()
+    );
+
+    publish_handle.send(5).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(3, snapshot.len(), "a snapshot is a point in time");
+    assert_eq!(Some((6, &5)), splaycast.snapshot().iter().last());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn subscribe_filtered() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut evens = splaycast
+        .subscribe_filtered(|item: &usize| item.is_multiple_of(2))
+        .expect("not sealed");
+    let mut all = splaycast.subscribe();
+
+    (1..=5).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(Some(Message::Entry { item: 2 })),
+        poll_next(&mut evens)
+    );
+    assert_eq!(
+        Poll::Ready(Some(Message::Entry { item: 4 })),
+        poll_next(&mut evens)
+    );
+    assert_eq!(Poll::Pending, poll_next(&mut evens), "5 is skipped");
+    assert_eq!(
+        Poll::Ready(Some(Message::Entry { item: 1 })),
+        poll_next(&mut all)
+    );
+
+    publish_handle.send(7).expect("unbounded send");
+    publish_handle.send(8).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(Some(Message::Entry { item: 8 })),
+        poll_next(&mut evens)
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn filtered_batch_position() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(16);
+    let mut evens = splaycast
+        .subscribe_filtered(|item: &usize| item.is_multiple_of(2))
+        .expect("not sealed");
+    evens.set_prefetch(3);
+
+    (1..=10).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    let mut batch = Vec::new();
+    assert_eq!(
+        Poll::Ready(2),
+        evens.poll_next_batch(&mut Context::from_waker(noop_waker_ref()), &mut batch, 2)
+    );
+    assert_eq!(
+        vec![Message::Entry { item: 2 }, Message::Entry { item: 4 }],
+        batch
+    );
+    assert_eq!(
+        6,
+        evens.position(),
+        "6 and 8 are prefetched, with 7 skipped between them"
+    );
+
+    let mut resumed = splaycast
+        .subscribe_from(evens.position())
+        .expect("not sealed");
+    assert_eq!(Poll::Ready(entry(6)), poll_next(&mut resumed));
+    assert_eq!(Poll::Ready(entry(6)), poll_next(&mut evens));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn subscribe_map() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(2);
+    let mut names = splaycast
+        .subscribe_map(|item: &usize| format!("item-{item}"))
+        .expect("not sealed");
+
+    (1..=4).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(Some(Message::Lagged { count: 2 })),
+        poll_next(&mut names)
+    );
+    assert_eq!(
+        Poll::Ready(Some(Message::Entry {
+            item: "item-3".to_string()
+        })),
+        poll_next(&mut names)
+    );
+    assert_eq!(
+        Poll::Ready(Some(Message::Entry {
+            item: "item-4".to_string()
+        })),
+        poll_next(&mut names)
+    );
+    assert_eq!(Poll::Pending, poll_next(&mut names));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn multi_receiver() {
+    let (publish_a, splaycast_a, mut engine_a) = get_splaycast_with_buffer(8);
+    let (publish_b, splaycast_b, mut engine_b) = get_splaycast_with_buffer(8);
+    let mut multi: MultiReceiver<usize> = [&splaycast_a, &splaycast_b]
+        .into_iter()
+        .map(|splaycast| splaycast.subscribe())
+        .collect();
+    assert_eq!(2, multi.len());
+    assert_eq!(Poll::Pending, poll_next(&mut multi));
+
+    (1..=2).for_each(|i| publish_a.send(i).expect("unbounded send"));
+    (10..=11).for_each(|i| publish_b.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine_a));
+    assert_eq!(Poll::Pending, poll(&mut engine_b));
+
+    assert_eq!(
+        Poll::Ready(Some((0, Message::Entry { item: 1 }))),
+        poll_next(&mut multi)
+    );
+    assert_eq!(
+        Poll::Ready(Some((1, Message::Entry { item: 10 }))),
+        poll_next(&mut multi),
+        "ready receivers take turns"
+    );
+    assert_eq!(
+        Poll::Ready(Some((0, Message::Entry { item: 2 }))),
+        poll_next(&mut multi)
+    );
+    assert_eq!(
+        Poll::Ready(Some((1, Message::Entry { item: 11 }))),
+        poll_next(&mut multi)
+    );
+    assert_eq!(Poll::Pending, poll_next(&mut multi));
+
+    drop(splaycast_a);
+    assert_eq!(Poll::Ready(()), poll(&mut engine_a));
+    assert_eq!(Poll::Pending, poll_next(&mut multi));
+    assert_eq!(1, multi.len(), "a terminated receiver is removed");
+
+    drop(splaycast_b);
+    assert_eq!(Poll::Ready(()), poll(&mut engine_b));
+    assert_eq!(Poll::Ready(None), poll_next(&mut multi));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn splaycast_template() {
+    let mut template = SplaycastTemplate::new(BufferLengthPolicy::new(8));
+    template.set_reorder_window(ReorderWindow::count(1), |item: &usize| *item);
+
+    let channels: Vec<_> = (0..2)
+        .map(|_| {
+            let (publish_handle, upstream) = unbounded_channel::<usize>();
+            let (engine, splaycast) = template.instantiate(UnboundedReceiverStream::new(upstream));
+            (publish_handle, engine, splaycast)
+        })
+        .collect();
+    for (publish_handle, mut engine, splaycast) in channels {
+        let mut subscriber = splaycast.subscribe();
+        [2, 1, 3]
+            .into_iter()
+            .for_each(|i| publish_handle.send(i).expect("unbounded send"));
+        assert_eq!(Poll::Pending, poll(&mut engine));
+        assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+        assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+        assert_eq!(
+            Poll::Pending,
+            poll_next(&mut subscriber),
+            "each channel reorders"
+        );
+    }
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn subscribe_latest_only() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    (1..=3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+
+    let mut latest = splaycast.subscribe_latest_only().expect("not sealed");
+    assert_eq!(
+        Poll::Ready(entry(3)),
+        poll_next(&mut latest),
+        "starts with the current value"
+    );
+    assert_eq!(Poll::Pending, poll_next(&mut latest));
+
+    (4..=6).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(entry(6)),
+        poll_next(&mut latest),
+        "no lag, just the newest"
+    );
+    assert_eq!(Poll::Pending, poll_next(&mut latest));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn weak_splaycast() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let weak = splaycast.downgrade();
+    let mut subscriber = weak.subscribe().expect("still alive");
+    drop(weak.clone());
+
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(entry(1)),
+        poll_next(&mut subscriber),
+        "weak handles don't kill it"
+    );
+
+    drop(splaycast);
+    assert!(weak.upgrade().is_none(), "weak handles don't keep it alive");
+    assert_eq!(Some(SubscribeError::Closed), weak.subscribe().err());
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(Poll::Ready(None), poll_next(&mut subscriber));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn detach_splaycast() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut subscriber = splaycast.subscribe();
+    let weak = splaycast.downgrade();
+    splaycast.detach();
+
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(
+        Poll::Pending,
+        poll(&mut engine),
+        "the engine outlives the handle"
+    );
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    let mut late = weak.subscribe().expect("still running");
+
+    drop(publish_handle);
+    assert_eq!(Poll::Ready(()), poll(&mut engine), "the upstream ended");
+    assert_eq!(Poll::Ready(None), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(None), poll_next(&mut late));
+    assert_eq!(Some(SubscribeError::Closed), weak.subscribe().err());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn into_results() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(2);
+    let mut results = splaycast
+        .subscribe()
+        .into_results(|count| format!("lagged {count}"));
+
+    (1..=4).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(Some(Err("lagged 2".to_string()))),
+        poll_next(&mut results)
+    );
+    assert_eq!(Poll::Ready(Some(Ok(3))), poll_next(&mut results));
+    assert_eq!(Poll::Ready(Some(Ok(4))), poll_next(&mut results));
+
+    splaycast.reset_sequence();
+    publish_handle.send(5).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(Some(Ok(5))),
+        poll_next(&mut results),
+        "resets are passed over"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn wrap_with_initial() {
+    let (publish_handle, upstream) = unbounded_channel::<usize>();
+    let (mut engine, splaycast) = splaycast::wrap_with_initial(
+        UnboundedReceiverStream::new(upstream),
+        vec![1, 2, 3, 4],
+        BufferLengthPolicy::new(3),
+    );
+    assert_eq!(Some((2, 4)), splaycast.buffer_sequence_range());
+    let mut replay = splaycast.subscribe_from(1).expect("not sealed");
+    let mut tail = splaycast.subscribe_at_tail();
+
+    publish_handle.send(5).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(lag(2)),
+        poll_next(&mut replay),
+        "the policy applies"
+    );
+    for i in 3..=5 {
+        assert_eq!(
+            Poll::Ready(entry(i)),
+            poll_next(&mut replay),
+            "no gap to live"
+        );
+    }
+    assert_eq!(Poll::Ready(entry(3)), poll_next(&mut tail));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn max_subscribers() {
+    let (_publish_handle, splaycast, _engine) = get_splaycast();
+    splaycast.set_max_subscribers(2);
+    let first = splaycast.try_subscribe().expect("room for one");
+    let _second = splaycast.try_subscribe().expect("room for two");
+    assert_eq!(
+        Some(SubscribeError::AtCapacity),
+        splaycast.try_subscribe().err()
+    );
+    assert_eq!(
+        Some(SubscribeError::AtCapacity),
+        splaycast.try_subscribe_with(SubscribeOptions::new()).err()
+    );
+    assert_eq!(
+        Some(SubscribeError::AtCapacity),
+        splaycast.subscribe_group(1).err()
+    );
+
+    drop(first);
+    splaycast.try_subscribe().expect("room again after a drop");
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn disconnect() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut kicked = splaycast.subscribe();
+    let mut kept = splaycast.subscribe();
+    assert_ne!(kicked.id(), kept.id());
+    assert_eq!(Poll::Pending, poll_next(&mut kicked));
+
+    assert!(splaycast.disconnect(kicked.id()));
+    assert_eq!(Poll::Ready(None), poll_next(&mut kicked));
+    assert!(kicked.is_disconnected());
+
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(
+        Poll::Ready(entry(1)),
+        poll_next(&mut kept),
+        "the channel is unaffected"
+    );
+    assert!(!kept.is_disconnected());
+
+    let kicked_id = kicked.id();
+    drop(kicked);
+    assert!(!splaycast.disconnect(kicked_id), "it's gone");
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn entry_metadata() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    engine.set_metadata_hook(|item: &usize| {
+        (item % 2 == 1).then(|| EntryMetadata::new(format!("trace-{item}")))
+    });
+    let mut subscriber = splaycast.subscribe();
+    assert!(subscriber.metadata().is_none());
+
+    (1..=2).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut subscriber));
+    assert_eq!(
+        Some(&"trace-1".to_string()),
+        subscriber
+            .metadata()
+            .and_then(|metadata| metadata.downcast_ref::<String>())
+    );
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+    assert!(
+        subscriber.metadata().is_none(),
+        "the hook attached nothing to 2"
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn engine_runtime_shutdown() {
+    let engine_runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .build()
+        .expect("can build a runtime");
+    let receiver_runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_time()
+        .build()
+        .expect("can build a runtime");
+    let next = |subscriber: &mut splaycast::Receiver<usize>| {
+        receiver_runtime.block_on(async {
+            tokio::time::timeout(
+                std::time::Duration::from_secs(1),
+                futures::StreamExt::next(subscriber),
+            )
+            .await
+            .expect("the receiver is not left hanging")
+        })
+    };
+
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let reasons = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    engine.set_on_terminate({
+        let reasons = reasons.clone();
+        move |report| reasons.lock().expect("not poisoned").push(report.reason)
+    });
+    let mut subscriber = splaycast.subscribe();
+    let task = engine_runtime.spawn(engine);
+    publish_handle.send(1).expect("unbounded send");
+    assert_eq!(entry(1), next(&mut subscriber));
+
+    engine_runtime.shutdown_timeout(std::time::Duration::from_secs(1));
+    assert_eq!(None, next(&mut subscriber), "a clean end of stream");
+    #[cfg(feature = "tokio")]
+    assert_eq!(
+        vec![splaycast::TerminationReason::RuntimeShutdown],
+        *reasons.lock().expect("not poisoned"),
+        "the Engine noticed the runtime shutting down"
+    );
+    drop(task);
+    drop(splaycast);
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn receiver_cursors() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut fast = splaycast.subscribe();
+    let slow = splaycast.subscribe();
+
+    (1..=3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    while let Poll::Ready(Some(_)) = poll_next(&mut fast) {}
+
+    assert_eq!(
+        vec![
+            ReceiverCursor {
+                receiver_id: fast.id(),
+                label: None,
+                next_message_id: 4,
+                lag_behind_head: 0,
+                lag_events: 0,
+                entries_skipped: 0,
+            },
+            ReceiverCursor {
+                receiver_id: slow.id(),
+                label: None,
+                next_message_id: 1,
+                lag_behind_head: 3,
+                lag_events: 0,
+                entries_skipped: 0,
+            },
+        ],
+        splaycast.receiver_cursors()
+    );
+    drop(slow);
+    assert_eq!(1, splaycast.mirror().receiver_cursors().len());
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn abandoned_backlog() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut caught_up = splaycast.subscribe();
+    let mut behind = splaycast.subscribe();
+    let idle = splaycast.subscribe();
+
+    (1..=3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    while let Poll::Ready(Some(_)) = poll_next(&mut caught_up) {}
+    assert_eq!(Poll::Ready(entry(1)), poll_next(&mut behind));
+
+    drop(caught_up);
+    assert_eq!(AbandonedBacklog::default(), splaycast.abandoned_backlog());
+    drop(behind);
+    drop(idle);
+    assert_eq!(
+        AbandonedBacklog {
+            receivers: 2,
+            entries: 5,
+        },
+        splaycast.abandoned_backlog()
+    );
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn await_subscribers() {
+    let (_publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut two = splaycast.await_subscribers(2);
+    let mut three = splaycast.await_subscribers(3);
+    assert_eq!(Poll::Pending, poll(&mut two));
+
+    let _first = splaycast.subscribe();
+    assert_eq!(Poll::Pending, poll(&mut two));
+    let _second = splaycast.subscribe();
+    assert_eq!(Poll::Ready(true), poll(&mut two));
+
+    assert_eq!(Poll::Pending, poll(&mut three));
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(Poll::Ready(false), poll(&mut three), "terminated first");
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn subscriber_count_changes() {
+    let (_publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut changes = splaycast.subscriber_count_changes([1, 3]);
+    assert_eq!(Poll::Pending, poll_next(&mut changes));
+
+    let first = splaycast.subscribe();
+    assert_eq!(Poll::Ready(Some(1)), poll_next(&mut changes), "0 -> 1");
+    let second = splaycast.subscribe();
+    assert_eq!(Poll::Pending, poll_next(&mut changes), "no threshold at 2");
+    let third = splaycast.subscribe();
+    assert_eq!(Poll::Ready(Some(3)), poll_next(&mut changes), "2 -> 3");
+
+    drop(third);
+    drop(second);
+    assert_eq!(
+        Poll::Ready(Some(1)),
+        poll_next(&mut changes),
+        "coalesced 3 -> 1"
+    );
+    drop(first);
+    assert_eq!(Poll::Ready(Some(0)), poll_next(&mut changes), "1 -> 0");
+
+    drop(splaycast);
+    assert_eq!(Poll::Ready(()), poll(&mut engine));
+    assert_eq!(Poll::Ready(None), poll_next(&mut changes));
+}
+
+#[allow(clippy::expect_used)] // i mean, it's a test
+#[test]
+fn replay_seek() {
+    let (publish_handle, splaycast, mut engine) = get_splaycast_with_buffer(8);
+    let mut subscriber = splaycast.subscribe();
+    assert_eq!(1, subscriber.position());
+
+    (1..=3).for_each(|i| publish_handle.send(i).expect("unbounded send"));
+    assert_eq!(Poll::Pending, poll(&mut engine));
+    while let Poll::Ready(Some(_)) = poll_next(&mut subscriber) {}
+    assert_eq!(4, subscriber.position());
+
+    subscriber.seek(2);
+    assert_eq!(2, subscriber.position());
+    assert_eq!(Poll::Ready(entry(2)), poll_next(&mut subscriber));
+    assert_eq!(Poll::Ready(entry(3)), poll_next(&mut subscriber));
+
+    let _latest: splaycast::Receiver<usize, TipOnly> =
+        splaycast.subscribe_latest_only().expect("not sealed");
+}